@@ -0,0 +1,12 @@
+create table if not exists response (
+        id uuid primary key,
+        result_id uuid not null,
+        question_id uuid not null,
+        level int not null,
+        attempt int not null,
+        drawing bytea not null,
+        created_at timestamptz not null default now(),
+
+        foreign key (result_id) references result(id),
+        foreign key (question_id) references question(id)
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "result" DROP COLUMN IF EXISTS "language";
+ALTER TABLE "test" DROP COLUMN IF EXISTS "language";
+DROP TABLE IF EXISTS "question_translation";
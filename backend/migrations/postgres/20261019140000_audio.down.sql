@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "test" DROP COLUMN IF EXISTS "voice";
+ALTER TABLE "test" DROP COLUMN IF EXISTS "speech_rate";
+ALTER TABLE "test" DROP COLUMN IF EXISTS "autoplay";
+
+ALTER TABLE "question" DROP COLUMN IF EXISTS "audio_path";
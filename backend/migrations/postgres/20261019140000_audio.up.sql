@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE "question" ADD COLUMN IF NOT EXISTS "audio_path" text;
+
+ALTER TABLE "test" ADD COLUMN IF NOT EXISTS "autoplay" boolean NOT NULL DEFAULT false;
+ALTER TABLE "test" ADD COLUMN IF NOT EXISTS "speech_rate" double precision NOT NULL DEFAULT 1.0;
+ALTER TABLE "test" ADD COLUMN IF NOT EXISTS "voice" text;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+create table if not exists roster (
+        id uuid primary key,
+        test_id uuid not null,
+        name text not null,
+        language text,
+
+        unique (test_id, name),
+        foreign key (test_id) references test(id)
+);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "result" DROP COLUMN IF EXISTS "finished";
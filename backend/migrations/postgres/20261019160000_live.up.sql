@@ -0,0 +1 @@
+alter table result add column finished boolean not null default false;
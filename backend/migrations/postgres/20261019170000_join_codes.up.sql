@@ -0,0 +1,15 @@
+alter table test add column join_code text;
+alter table test add column pin text;
+
+-- Existing tests get a code too, new ones are given one when they're created. The letters are
+-- the ones new codes are made of, which leave out those easily mistaken for another
+update test set join_code =
+    substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1)
+    || substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1)
+    || substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1)
+    || substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1)
+    || substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1)
+    || substr('ABCDEFGHJKMNPQRSTUVWXYZ23456789', 1 + floor(random() * 31)::int, 1);
+
+alter table test alter column join_code set not null;
+create unique index if not exists test_join_code on test (join_code);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE "response" DROP COLUMN IF EXISTS "score";
+ALTER TABLE "response" DROP COLUMN IF EXISTS "transcription";
+ALTER TABLE "response" DROP COLUMN IF EXISTS "suggested_score";
+ALTER TABLE "response" DROP COLUMN IF EXISTS "suggested_transcription";
+ALTER TABLE "response" DROP COLUMN IF EXISTS "strokes";
+ALTER TABLE "question" DROP COLUMN IF EXISTS "word";
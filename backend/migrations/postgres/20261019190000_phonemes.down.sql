@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "response" DROP COLUMN IF EXISTS "stage";
+ALTER TABLE "response" DROP COLUMN IF EXISTS "phonetic_accuracy";
+ALTER TABLE "question" DROP COLUMN IF EXISTS "phonemes";
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DELETE FROM "question" WHERE "organization_id" IS NOT NULL;
+ALTER TABLE "question" DROP COLUMN "organization_id";
+ALTER TABLE "teacher" DROP COLUMN "organization_admin";
+ALTER TABLE "teacher" DROP COLUMN "organization_id";
+DROP TABLE IF EXISTS "organization";
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "result" DROP COLUMN "consent_recorded_by";
+ALTER TABLE "result" DROP COLUMN "consent_recorded_at";
+ALTER TABLE "result" DROP COLUMN "consent";
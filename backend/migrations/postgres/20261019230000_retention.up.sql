@@ -0,0 +1,28 @@
+-- How many days after a student registered their drawings are deleted and their name removed.
+-- The strictest of the test's, its teacher's organization's and that organization's district's
+-- applies.
+alter table test add column drawings_retention_days int;
+alter table test add column names_retention_days int;
+alter table organization add column drawings_retention_days int;
+alter table organization add column names_retention_days int;
+
+-- Seconds since the Unix epoch, compared the same way on every database. Students who registered
+-- before it was kept count from their first answer, or from now if they never answered.
+alter table result add column registered_at bigint;
+update result set registered_at = coalesce(
+        (select extract(epoch from min(created_at))::bigint from response where result_id = result.id),
+        extract(epoch from now())::bigint
+);
+alter table result add column name_purged_at bigint;
+alter table response add column drawing_purged_at bigint;
+
+-- What every run of the retention job removed, by test
+create table if not exists retention_purge (
+        id uuid primary key,
+        test_id uuid not null,
+        purged_at bigint not null,
+        drawings bigint not null,
+        names bigint not null,
+
+        foreign key (test_id) references test(id)
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "job_due";
+DROP TABLE IF EXISTS "job";
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS "audit_log_append_only" ON "audit_log";
+DROP FUNCTION IF EXISTS "audit_log_append_only";
+DROP INDEX IF EXISTS "audit_log_at";
+DROP TABLE IF EXISTS "audit_log";
@@ -0,0 +1,28 @@
+-- Every change a teacher made and every export they downloaded, never changed or removed. Names
+-- are kept as they were then, so entries outlive the teachers and tests they mention.
+create table if not exists audit_log (
+        id uuid primary key,
+        teacher_id uuid not null,
+        actor text not null,
+        -- The teacher's organization then, so its admins can see what its teachers did
+        organization_id uuid,
+        -- The method and route, like `POST /v1/test/:test_id/manage`
+        action text not null,
+        -- The path that was requested, with the ids of what was acted on
+        target text not null,
+        status int not null,
+        ip text,
+        -- Seconds since the Unix epoch
+        at bigint not null
+);
+
+create index if not exists audit_log_at on audit_log (at);
+
+create or replace function audit_log_append_only() returns trigger as $$
+begin
+        raise exception 'The audit log is append-only';
+end;
+$$ language plpgsql;
+
+create trigger audit_log_append_only before update or delete on audit_log
+        for each row execute function audit_log_append_only();
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "response" DROP COLUMN "drawing_key_id";
+ALTER TABLE "result" DROP COLUMN "name_key_id";
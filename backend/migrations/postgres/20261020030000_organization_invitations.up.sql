@@ -0,0 +1,11 @@
+-- Teachers invited to an organization by one of its admins. They only join it once they accept.
+create table if not exists organization_invitation (
+        organization_id uuid not null references organization(id),
+        teacher_id uuid not null references teacher(id),
+        -- Whether they'll administer the organization once they join it
+        admin bool not null default false,
+        -- Seconds since the Unix epoch
+        created_at bigint not null,
+
+        primary key (organization_id, teacher_id)
+);
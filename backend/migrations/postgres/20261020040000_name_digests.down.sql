@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "result_name_digest";
+ALTER TABLE "result" DROP COLUMN "name_digest";
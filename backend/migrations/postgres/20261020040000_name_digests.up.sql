@@ -0,0 +1,6 @@
+-- An HMAC of a student's name, for the database to keep names unique within a test now that
+-- they're encrypted. Null for what was stored before, until the next key rotation digests it, and
+-- for names removed under the retention policy.
+alter table result add column name_digest bytea;
+
+create unique index if not exists result_name_digest on result (test_id, name_digest);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "roster_name_digest";
+ALTER TABLE "roster" DROP COLUMN "name_digest";
+ALTER TABLE "roster" DROP COLUMN "name_key_id";
@@ -0,0 +1,6 @@
+-- Names on rosters are encrypted like students' names on their results, and kept unique within a
+-- test by their digest instead of by name.
+alter table roster add column name_key_id text;
+alter table roster add column name_digest bytea;
+
+create unique index if not exists roster_name_digest on roster (test_id, name_digest);
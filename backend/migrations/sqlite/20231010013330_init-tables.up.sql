@@ -0,0 +1,6 @@
+create table if not exists teacher (
+        id blob primary key not null,
+        username text not null,
+        email text not null unique,
+        password text not null
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS "result";
+DROP TABLE IF EXISTS "test";
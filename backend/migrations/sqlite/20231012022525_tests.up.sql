@@ -0,0 +1,17 @@
+create table if not exists test (
+        id blob primary key not null,
+        teacher_id blob not null,
+        name text not null,
+        closed boolean not null default false,
+
+        foreign key (teacher_id) references teacher(id)
+);
+
+create table if not exists result (
+        id blob primary key not null,
+        test_id blob not null,
+        name text not null,
+        level integer not null default 1,
+
+        foreign key (test_id) references test(id)
+);
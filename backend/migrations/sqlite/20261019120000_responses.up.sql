@@ -0,0 +1,12 @@
+create table if not exists response (
+        id blob primary key not null,
+        result_id blob not null,
+        question_id blob not null,
+        level integer not null,
+        attempt integer not null,
+        drawing blob not null,
+        created_at timestamp not null default current_timestamp,
+
+        foreign key (result_id) references result(id),
+        foreign key (question_id) references question(id)
+);
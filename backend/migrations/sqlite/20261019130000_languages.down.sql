@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "result" DROP COLUMN "language";
+ALTER TABLE "test" DROP COLUMN "language";
+DROP TABLE IF EXISTS "question_translation";
@@ -0,0 +1,29 @@
+create table if not exists question_translation (
+        question_id blob not null,
+        language text not null,
+        question text not null,
+
+        primary key (question_id, language),
+        foreign key (question_id) references question(id)
+);
+
+alter table test add column language text not null default 'en';
+alter table result add column language text;
+
+with spanish (english, question) as (values
+    ('Draw a picture of yourself in the box.', 'Haz un dibujo de ti en el cuadro.'),
+    ('Draw a picture of an animal in the box.', 'Haz un dibujo de un animal en el cuadro.'),
+    ('Draw a picture of a tree in the box.', 'Haz un dibujo de un árbol en el cuadro.'),
+    ('Write your name in the box.', 'Escribe tu nombre en el cuadro.'),
+    ('Write the name of someone you know.', 'Escribe el nombre de alguien que conozcas.'),
+    ('Write the name of your favorite character.', 'Escribe el nombre de tu personaje favorito.'),
+    ('Spell the word in the box.', 'Escribe la palabra en el cuadro.'),
+    ('Can you write one word of something you like to do in the box?', '¿Puedes escribir en el cuadro una palabra de algo que te guste hacer?'),
+    ('Write a sentence about your family, if you can write one sentence, write more.', 'Escribe una oración sobre tu familia. Si puedes escribir una oración, escribe más.'),
+    ('Write a sentence about your favorite season, if you can write one sentence, write more.', 'Escribe una oración sobre tu estación del año favorita. Si puedes escribir una oración, escribe más.'),
+    ('Write a sentence about your favorite holiday, if you can write one sentence, write more.', 'Escribe una oración sobre tu día festivo favorito. Si puedes escribir una oración, escribe más.')
+)
+insert into question_translation (question_id, language, question)
+select question.id, 'es', spanish.question
+from question
+join spanish on question.question = spanish.english;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "test" DROP COLUMN "voice";
+ALTER TABLE "test" DROP COLUMN "speech_rate";
+ALTER TABLE "test" DROP COLUMN "autoplay";
+
+ALTER TABLE "question" DROP COLUMN "audio_path";
@@ -0,0 +1,5 @@
+alter table question add column audio_path text;
+
+alter table test add column autoplay boolean not null default false;
+alter table test add column speech_rate real not null default 1.0;
+alter table test add column voice text;
@@ -0,0 +1,9 @@
+create table if not exists roster (
+        id blob primary key not null,
+        test_id blob not null,
+        name text not null,
+        language text,
+
+        unique (test_id, name),
+        foreign key (test_id) references test(id)
+);
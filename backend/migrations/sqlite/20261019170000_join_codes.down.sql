@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "test_join_code";
+ALTER TABLE "test" DROP COLUMN "pin";
+ALTER TABLE "test" DROP COLUMN "join_code";
@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE "response" DROP COLUMN "score";
+ALTER TABLE "response" DROP COLUMN "transcription";
+ALTER TABLE "response" DROP COLUMN "suggested_score";
+ALTER TABLE "response" DROP COLUMN "suggested_transcription";
+ALTER TABLE "response" DROP COLUMN "strokes";
+ALTER TABLE "question" DROP COLUMN "word";
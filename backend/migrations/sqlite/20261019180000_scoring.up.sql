@@ -0,0 +1,9 @@
+alter table question add column word text;
+alter table response add column strokes text;
+alter table response add column suggested_transcription text;
+alter table response add column suggested_score real;
+alter table response add column transcription text;
+alter table response add column score real;
+
+-- The spelling questions ask for the word their picture shows
+update question set word = replace(image_path, '.jpg', '') where level between 3 and 6;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "response" DROP COLUMN "stage";
+ALTER TABLE "response" DROP COLUMN "phonetic_accuracy";
+ALTER TABLE "question" DROP COLUMN "phonemes";
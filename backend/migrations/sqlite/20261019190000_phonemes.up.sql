@@ -0,0 +1,17 @@
+alter table question add column phonemes text;
+alter table response add column phonetic_accuracy real;
+alter table response add column stage text;
+
+-- The spelling questions' words broken down into their sounds, in ARPAbet
+update question set phonemes = 'K AE T' where word = 'cat';
+update question set phonemes = 'P IH G' where word = 'pig';
+update question set phonemes = 'HH AE T' where word = 'hat';
+update question set phonemes = 'K R AE B' where word = 'crab';
+update question set phonemes = 'T R IY' where word = 'tree';
+update question set phonemes = 'F R AA G' where word = 'frog';
+update question set phonemes = 'CH IY T AH' where word = 'cheetah';
+update question set phonemes = 'CH ER CH' where word = 'church';
+update question set phonemes = 'K R UW Z' where word = 'cruise';
+update question set phonemes = 'B ER TH D EY' where word = 'birthday';
+update question set phonemes = 'K AE T ER P IH L ER' where word = 'caterpillar';
+update question set phonemes = 'P L EY G R AW N D' where word = 'playground';
@@ -0,0 +1,11 @@
+create table if not exists test_share (
+        test_id blob not null,
+        teacher_id blob not null,
+        permission text not null,
+        -- Seconds since the Unix epoch, compared the same way on every database
+        expires_at integer,
+
+        primary key (test_id, teacher_id),
+        foreign key (test_id) references test(id),
+        foreign key (teacher_id) references teacher(id)
+);
@@ -0,0 +1,19 @@
+create table if not exists organization (
+        id blob primary key not null,
+        -- The district a school belongs to
+        parent_id blob,
+        kind text not null,
+        name text not null,
+        -- What the tests the organization's teachers create start with
+        autoplay boolean not null default false,
+        speech_rate real not null default 1,
+        voice text,
+
+        foreign key (parent_id) references organization(id)
+);
+
+alter table teacher add column organization_id blob;
+alter table teacher add column organization_admin boolean not null default false;
+
+-- Questions added by an organization, asked on its teachers' tests instead of the shared bank's
+alter table question add column organization_id blob;
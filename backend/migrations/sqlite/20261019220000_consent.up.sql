@@ -0,0 +1,6 @@
+-- What the student's parents agreed their drawings may be used for
+alter table result add column consent text not null default 'none';
+-- Seconds since the Unix epoch, compared the same way on every database
+alter table result add column consent_recorded_at integer;
+-- The name of the teacher who recorded it, as it was then
+alter table result add column consent_recorded_by text;
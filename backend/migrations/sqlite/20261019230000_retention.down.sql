@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "retention_purge";
+ALTER TABLE "response" DROP COLUMN "drawing_purged_at";
+ALTER TABLE "result" DROP COLUMN "name_purged_at";
+ALTER TABLE "result" DROP COLUMN "registered_at";
+ALTER TABLE "organization" DROP COLUMN "names_retention_days";
+ALTER TABLE "organization" DROP COLUMN "drawings_retention_days";
+ALTER TABLE "test" DROP COLUMN "names_retention_days";
+ALTER TABLE "test" DROP COLUMN "drawings_retention_days";
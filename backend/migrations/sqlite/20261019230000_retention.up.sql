@@ -0,0 +1,28 @@
+-- How many days after a student registered their drawings are deleted and their name removed.
+-- The strictest of the test's, its teacher's organization's and that organization's district's
+-- applies.
+alter table test add column drawings_retention_days integer;
+alter table test add column names_retention_days integer;
+alter table organization add column drawings_retention_days integer;
+alter table organization add column names_retention_days integer;
+
+-- Seconds since the Unix epoch, compared the same way on every database. Students who registered
+-- before it was kept count from their first answer, or from now if they never answered.
+alter table result add column registered_at integer;
+update result set registered_at = coalesce(
+        (select cast(strftime('%s', min(created_at)) as integer) from response where result_id = result.id),
+        cast(strftime('%s', 'now') as integer)
+);
+alter table result add column name_purged_at integer;
+alter table response add column drawing_purged_at integer;
+
+-- What every run of the retention job removed, by test
+create table if not exists retention_purge (
+        id blob primary key not null,
+        test_id blob not null,
+        purged_at integer not null,
+        drawings integer not null,
+        names integer not null,
+
+        foreign key (test_id) references test(id)
+);
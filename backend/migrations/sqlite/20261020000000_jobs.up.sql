@@ -0,0 +1,19 @@
+-- Background work that isn't tied to a request, kept until it's done so it survives restarts.
+-- Times are seconds since the Unix epoch.
+create table if not exists job (
+        id blob primary key not null,
+        kind text not null,
+        -- The task as JSON, with whatever it needs to run
+        payload text not null,
+        status text not null default 'pending',
+        attempts integer not null default 0,
+        max_attempts integer not null,
+        -- When it's due, pushed back after every failed attempt
+        run_at integer not null,
+        created_at integer not null,
+        started_at integer,
+        finished_at integer,
+        last_error text
+);
+
+create index if not exists job_due on job (status, run_at);
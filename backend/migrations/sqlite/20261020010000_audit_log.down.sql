@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS "audit_log_no_delete";
+DROP TRIGGER IF EXISTS "audit_log_no_update";
+DROP INDEX IF EXISTS "audit_log_at";
+DROP TABLE IF EXISTS "audit_log";
@@ -0,0 +1,29 @@
+-- Every change a teacher made and every export they downloaded, never changed or removed. Names
+-- are kept as they were then, so entries outlive the teachers and tests they mention.
+create table if not exists audit_log (
+        id blob primary key not null,
+        teacher_id blob not null,
+        actor text not null,
+        -- The teacher's organization then, so its admins can see what its teachers did
+        organization_id blob,
+        -- The method and route, like `POST /v1/test/:test_id/manage`
+        action text not null,
+        -- The path that was requested, with the ids of what was acted on
+        target text not null,
+        status integer not null,
+        ip text,
+        -- Seconds since the Unix epoch
+        at integer not null
+);
+
+create index if not exists audit_log_at on audit_log (at);
+
+create trigger if not exists audit_log_no_update before update on audit_log
+begin
+        select raise(abort, 'The audit log is append-only');
+end;
+
+create trigger if not exists audit_log_no_delete before delete on audit_log
+begin
+        select raise(abort, 'The audit log is append-only');
+end;
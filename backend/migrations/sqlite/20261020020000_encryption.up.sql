@@ -0,0 +1,5 @@
+-- The id of the key a student's name, or a response's drawing and strokes, are encrypted with.
+-- Null for what was stored before encryption was set up, or removed under the retention policy,
+-- which is kept as is.
+alter table result add column name_key_id text;
+alter table response add column drawing_key_id text;
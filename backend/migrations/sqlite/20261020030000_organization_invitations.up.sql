@@ -0,0 +1,13 @@
+-- Teachers invited to an organization by one of its admins. They only join it once they accept.
+create table if not exists organization_invitation (
+        organization_id blob not null,
+        teacher_id blob not null,
+        -- Whether they'll administer the organization once they join it
+        admin boolean not null default false,
+        -- Seconds since the Unix epoch
+        created_at integer not null,
+
+        primary key (organization_id, teacher_id),
+        foreign key (organization_id) references organization(id),
+        foreign key (teacher_id) references teacher(id)
+);
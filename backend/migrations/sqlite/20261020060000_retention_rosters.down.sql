@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "retention_purge" DROP COLUMN "transcriptions";
+ALTER TABLE "retention_purge" DROP COLUMN "roster_entries";
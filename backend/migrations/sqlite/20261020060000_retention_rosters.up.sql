@@ -0,0 +1,4 @@
+-- Students' roster entries and the transcriptions of their answers are removed along with their
+-- names
+alter table retention_purge add column roster_entries integer not null default 0;
+alter table retention_purge add column transcriptions integer not null default 0;
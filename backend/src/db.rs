@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+
+const MAX_CONNECTIONS: u32 = 20;
+
+/// A connection pool to whichever database `DATABASE_URL` points at.
+///
+/// Postgres is used for shared deployments, SQLite for a single classroom running everything on one
+/// laptop. Queries are written once in SQL both understand and run through [`with_pool`].
+#[derive(Debug, Clone)]
+pub enum Db {
+    Postgres(PgPool),
+    Sqlite(SqlitePool),
+}
+
+/// Runs `$body` with `$pool` bound to the pool inside `$db`.
+///
+/// The body is expanded once per backend, so it must only use runtime-checked queries
+/// (`sqlx::query`, `sqlx::query_as`, ...) whose SQL is valid for both Postgres and SQLite.
+macro_rules! with_pool {
+    ($db:expr, $pool:ident => $body:expr) => {
+        match &$db {
+            $crate::db::Db::Postgres($pool) => $body,
+            $crate::db::Db::Sqlite($pool) => $body,
+        }
+    };
+}
+
+pub(crate) use with_pool;
+
+impl Db {
+    /// Connects to `url`, using SQLite for `sqlite:` URLs and Postgres otherwise. SQLite databases
+    /// are created if they don't exist yet.
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        if url.starts_with("sqlite:") {
+            let options = SqliteConnectOptions::from_str(url)
+                .context("Invalid SQLite database URL")?
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal);
+
+            let pool = SqlitePoolOptions::new()
+                .max_connections(MAX_CONNECTIONS)
+                .connect_with(options)
+                .await?;
+
+            Ok(Db::Sqlite(pool))
+        } else {
+            let pool = PgPoolOptions::new()
+                .max_connections(MAX_CONNECTIONS)
+                .connect(url)
+                .await?;
+
+            Ok(Db::Postgres(pool))
+        }
+    }
+
+    /// A migrated SQLite database that only lives in memory, for tests. It has a single connection,
+    /// since every connection to `sqlite::memory:` opens a database of its own.
+    #[cfg(test)]
+    pub(crate) async fn memory() -> Self {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to open an in-memory database");
+        let db = Db::Sqlite(pool);
+        db.migrate().await.expect("Failed to migrate the database");
+
+        db
+    }
+
+    /// Applies the migrations of this backend that haven't been run yet.
+    pub async fn migrate(&self) -> anyhow::Result<()> {
+        match self {
+            Db::Postgres(pool) => sqlx::migrate!("./migrations/postgres").run(pool).await,
+            Db::Sqlite(pool) => sqlx::migrate!("./migrations/sqlite").run(pool).await,
+        }
+        .context("Failed to run the database migrations")
+    }
+
+    pub async fn close(&self) {
+        with_pool!(self, pool => pool.close().await)
+    }
+
+    /// The number of open connections, idle or not.
+    pub fn size(&self) -> u32 {
+        with_pool!(self, pool => pool.size())
+    }
+
+    pub fn num_idle(&self) -> usize {
+        with_pool!(self, pool => pool.num_idle())
+    }
+
+    pub fn max_connections(&self) -> u32 {
+        with_pool!(self, pool => pool.options().get_max_connections())
+    }
+}
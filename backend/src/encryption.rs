@@ -0,0 +1,947 @@
+//! Encrypting students' names, and their drawings and the strokes they were made with, before
+//! they're stored.
+//!
+//! Every value is encrypted with a data key of its own, which is in turn encrypted with one of the
+//! keys given in `ENCRYPTION_KEYS` and stored along with it. The id of that key is stored next to
+//! the value, in `result.name_key_id` or `response.drawing_key_id`, so the keys can be rotated:
+//! new values are encrypted with the active key, and [`rotate`] re-encrypts the older ones in the
+//! background. Names on a test's roster are encrypted the same way, keyed in `roster.name_key_id`.
+//! Values without a key id were stored unencrypted, before any key was given.
+//!
+//! Names are also stored as an HMAC keyed with `NAME_DIGEST_KEY`, which is the same every time
+//! for the same name, so the database can keep them unique without ever seeing them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shared::test::{RosterEntry, StudentResult};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+
+/// The first byte of everything encrypted, for the layout to change later on.
+const VERSION: u8 = 1;
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+
+/// The layout of an encrypted value: the version, the nonce and encrypted data key, then the nonce
+/// and the value encrypted with the data key.
+const HEADER_LEN: usize = 1 + NONCE_LEN + KEY_LEN + TAG_LEN + NONCE_LEN;
+
+/// How many rows a rotation re-encrypts at once.
+const BATCH: i64 = 100;
+
+/// What's encrypted. Each value is tied to its kind and the id of its row, so it can't be passed
+/// off as another one by copying it over.
+#[derive(Clone, Copy)]
+pub enum Field {
+    Name,
+    RosterName,
+    Drawing,
+    Strokes,
+}
+
+impl Field {
+    fn associated_data(self, id: Uuid) -> Vec<u8> {
+        let label: &[u8] = match self {
+            Field::Name => b"name",
+            Field::RosterName => b"roster_name",
+            Field::Drawing => b"drawing",
+            Field::Strokes => b"strokes",
+        };
+
+        [label, id.as_bytes()].concat()
+    }
+}
+
+/// A value ready to be stored, and the id of the key it's encrypted with, if it is.
+pub struct Sealed<T> {
+    pub key_id: Option<String>,
+    pub value: T,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// The id of the key new values are encrypted with.
+    active: Option<String>,
+    keys: HashMap<String, Aes256Gcm>,
+    /// What names are digested with. Empty without `ENCRYPTION_KEYS`, when names are stored
+    /// unencrypted anyway.
+    digest_key: Vec<u8>,
+}
+
+/// The keys student data is encrypted with. Without any, it's stored unencrypted.
+#[derive(Clone, Default)]
+pub struct Keys(Arc<Inner>);
+
+impl Keys {
+    /// Reads the keys from `ENCRYPTION_KEYS` and `NAME_DIGEST_KEY`, see [`Keys::parse`]. None are
+    /// given without them.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let list = std::env::var("ENCRYPTION_KEYS").unwrap_or_default();
+        let digest_key = std::env::var("NAME_DIGEST_KEY").unwrap_or_default();
+
+        Keys::parse(&list, &digest_key).context("Invalid ENCRYPTION_KEYS or NAME_DIGEST_KEY")
+    }
+
+    /// Reads the keys from a comma separated list of `<id>:<key>`, with the keys 32 random bytes
+    /// encoded in base64. The first one is the active key, the others can only decrypt. Names are
+    /// digested with `digest_key`, at least 32 random bytes in base64 as well, which is required
+    /// along with the keys.
+    pub fn parse(list: &str, digest_key: &str) -> anyhow::Result<Self> {
+        let mut inner = Inner::default();
+
+        for entry in list
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (id, key) = entry
+                .split_once(':')
+                .context("Every encryption key must be given as <id>:<base64 key>")?;
+            let id = id.trim();
+            if id.is_empty() {
+                bail!("Encryption key ids can't be empty");
+            }
+
+            let key = STANDARD
+                .decode(key.trim())
+                .with_context(|| format!("The encryption key {id} isn't valid base64"))?;
+            let cipher = Aes256Gcm::new_from_slice(&key)
+                .map_err(|_| anyhow!("The encryption key {id} must be {KEY_LEN} bytes long"))?;
+
+            if inner.keys.insert(id.to_string(), cipher).is_some() {
+                bail!("The encryption key {id} is given twice");
+            }
+            inner.active.get_or_insert_with(|| id.to_string());
+        }
+
+        let digest_key = digest_key.trim();
+        if !digest_key.is_empty() {
+            inner.digest_key = STANDARD
+                .decode(digest_key)
+                .context("The name digest key isn't valid base64")?;
+            if inner.digest_key.len() < KEY_LEN {
+                bail!("The name digest key must be at least {KEY_LEN} bytes long");
+            }
+        } else if inner.active.is_some() {
+            bail!("A name digest key is required along with the encryption keys");
+        }
+
+        Ok(Keys(Arc::new(inner)))
+    }
+
+    /// The id of the key new values are encrypted with, if there are keys.
+    pub fn active_id(&self) -> Option<&str> {
+        self.0.active.as_deref()
+    }
+
+    fn key(&self, id: &str) -> anyhow::Result<&Aes256Gcm> {
+        self.0
+            .keys
+            .get(id)
+            .with_context(|| format!("The encryption key {id} isn't configured"))
+    }
+
+    /// Encrypts the `field` of row `id` with the active key, or leaves it as is without one.
+    pub fn seal(
+        &self,
+        field: Field,
+        id: Uuid,
+        plaintext: &[u8],
+    ) -> anyhow::Result<Sealed<Vec<u8>>> {
+        let Some(active) = self.active_id() else {
+            return Ok(Sealed {
+                key_id: None,
+                value: plaintext.to_vec(),
+            });
+        };
+        let aad = field.associated_data(id);
+        let error = |_| anyhow!("Failed to encrypt a value");
+
+        let data_key = Aes256Gcm::generate_key(&mut OsRng);
+        let key_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = self
+            .key(active)?
+            .encrypt(
+                &key_nonce,
+                Payload {
+                    msg: &data_key,
+                    aad: &aad,
+                },
+            )
+            .map_err(error)?;
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(error)?;
+
+        Ok(Sealed {
+            key_id: Some(active.to_string()),
+            value: [
+                &[VERSION][..],
+                &key_nonce,
+                &wrapped_key,
+                &nonce,
+                &ciphertext,
+            ]
+            .concat(),
+        })
+    }
+
+    /// Decrypts the `field` of row `id`, stored encrypted with the key `key_id` or unencrypted
+    /// without one.
+    pub fn open(
+        &self,
+        field: Field,
+        id: Uuid,
+        key_id: Option<&str>,
+        stored: Vec<u8>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let Some(key_id) = key_id else {
+            return Ok(stored);
+        };
+        if stored.len() < HEADER_LEN || stored[0] != VERSION {
+            bail!("The encrypted value isn't in a known format");
+        }
+        let aad = field.associated_data(id);
+        let error = |_| anyhow!("Failed to decrypt a value with the encryption key {key_id}");
+
+        let (key_nonce, rest) = stored[1..].split_at(NONCE_LEN);
+        let (wrapped_key, rest) = rest.split_at(KEY_LEN + TAG_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let data_key = self
+            .key(key_id)?
+            .decrypt(
+                Nonce::from_slice(key_nonce),
+                Payload {
+                    msg: wrapped_key,
+                    aad: &aad,
+                },
+            )
+            .map_err(error)?;
+
+        Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|_| anyhow!("The encrypted data key isn't valid"))?
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(error)
+    }
+
+    /// Like [`Keys::seal`] for text columns, encoding what's encrypted in base64.
+    pub fn seal_text(&self, field: Field, id: Uuid, text: &str) -> anyhow::Result<Sealed<String>> {
+        let Sealed { key_id, value } = self.seal(field, id, text.as_bytes())?;
+        let value = match key_id {
+            Some(_) => STANDARD.encode(value),
+            None => text.to_string(),
+        };
+
+        Ok(Sealed { key_id, value })
+    }
+
+    /// Like [`Keys::open`] for text columns.
+    pub fn open_text(
+        &self,
+        field: Field,
+        id: Uuid,
+        key_id: Option<&str>,
+        stored: String,
+    ) -> anyhow::Result<String> {
+        if key_id.is_none() {
+            return Ok(stored);
+        }
+        let stored = STANDARD
+            .decode(stored)
+            .context("The encrypted value isn't valid base64")?;
+
+        String::from_utf8(self.open(field, id, key_id, stored)?)
+            .context("The decrypted value isn't valid text")
+    }
+
+    /// The digest a student's `name` is kept unique within test `test_id` by, the same every time
+    /// for the same name and test.
+    pub fn digest_name(&self, test_id: Uuid, name: &str) -> Vec<u8> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.0.digest_key)
+            .expect("HMAC takes keys of any length");
+        mac.update(test_id.as_bytes());
+        mac.update(name.as_bytes());
+
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// Decrypts a student's name as it was read from the database.
+    pub fn open_name(&self, result: &mut StudentResult) -> anyhow::Result<()> {
+        let Some(key_id) = result.name_key_id.take() else {
+            return Ok(());
+        };
+        let name = std::mem::take(&mut result.name);
+        result.name = self
+            .open_text(Field::Name, result.id, Some(&key_id), name)
+            .with_context(|| format!("Failed to decrypt the name of result {}", result.id))?;
+
+        Ok(())
+    }
+
+    pub fn open_names(&self, results: &mut [StudentResult]) -> anyhow::Result<()> {
+        results
+            .iter_mut()
+            .try_for_each(|result| self.open_name(result))
+    }
+
+    /// Decrypts the names of a test's roster as they were read from the database.
+    pub fn open_roster(&self, roster: &mut [RosterEntry]) -> anyhow::Result<()> {
+        roster.iter_mut().try_for_each(|entry| {
+            let Some(key_id) = entry.name_key_id.take() else {
+                return Ok(());
+            };
+            let name = std::mem::take(&mut entry.name);
+            entry.name = self
+                .open_text(Field::RosterName, entry.id, Some(&key_id), name)
+                .with_context(|| format!("Failed to decrypt roster entry {}", entry.id))?;
+
+            Ok(())
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredName {
+    id: Uuid,
+    test_id: Uuid,
+    name: String,
+    name_key_id: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredDrawing {
+    id: Uuid,
+    drawing: Vec<u8>,
+    strokes: Option<String>,
+    drawing_key_id: Option<String>,
+}
+
+/// What a rotation re-encrypted, and how many rows it had to leave as they were because they
+/// couldn't be decrypted or updated.
+#[derive(Debug, Default)]
+pub struct Rotated {
+    /// Students' names, on their results and on rosters.
+    pub names: u64,
+    pub drawings: u64,
+    pub skipped: u64,
+}
+
+/// Re-encrypts every name, on results and rosters, and every drawing that isn't encrypted with the
+/// active key yet, including those stored before there were keys, a batch at a time in the order
+/// of their ids. Names are digested again as well, and so are those that weren't digested yet.
+/// What was removed under the retention policy is left as is, and so is what fails to re-encrypt,
+/// which is logged and counted instead of holding up the rest.
+pub async fn rotate(db: &Db, keys: &Keys) -> anyhow::Result<Rotated> {
+    let Some(active) = keys.active_id() else {
+        bail!("There is no encryption key to rotate to");
+    };
+    let mut rotated = Rotated::default();
+
+    let mut last_id = Uuid::nil();
+    loop {
+        let batch = with_pool!(db, pool => {
+            sqlx::query_as::<_, StoredName>(
+                "SELECT id, test_id, name, name_key_id FROM result
+                WHERE name_purged_at IS NULL
+                    AND (name_key_id IS NULL OR name_key_id <> $1 OR name_digest IS NULL)
+                    AND id > $2
+                ORDER BY id
+                LIMIT $3",
+            )
+            .bind(active)
+            .bind(last_id)
+            .bind(BATCH)
+            .fetch_all(pool)
+            .await
+        })?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for stored in batch {
+            let id = stored.id;
+            match rotate_name(db, keys, stored).await {
+                Ok(updated) => rotated.names += updated,
+                Err(e) => {
+                    tracing::warn!("Failed to re-encrypt the name of result {id}: {e:?}");
+                    rotated.skipped += 1;
+                }
+            }
+        }
+    }
+
+    let mut last_id = Uuid::nil();
+    loop {
+        let batch = with_pool!(db, pool => {
+            sqlx::query_as::<_, StoredName>(
+                "SELECT id, test_id, name, name_key_id FROM roster
+                WHERE (name_key_id IS NULL OR name_key_id <> $1 OR name_digest IS NULL)
+                    AND id > $2
+                ORDER BY id
+                LIMIT $3",
+            )
+            .bind(active)
+            .bind(last_id)
+            .bind(BATCH)
+            .fetch_all(pool)
+            .await
+        })?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for stored in batch {
+            let id = stored.id;
+            match rotate_roster_name(db, keys, stored).await {
+                Ok(updated) => rotated.names += updated,
+                Err(e) => {
+                    tracing::warn!("Failed to re-encrypt roster entry {id}: {e:?}");
+                    rotated.skipped += 1;
+                }
+            }
+        }
+    }
+
+    let mut last_id = Uuid::nil();
+    loop {
+        let batch = with_pool!(db, pool => {
+            sqlx::query_as::<_, StoredDrawing>(
+                "SELECT id, drawing, strokes, drawing_key_id FROM response
+                WHERE drawing_purged_at IS NULL AND (drawing_key_id IS NULL OR drawing_key_id <> $1)
+                    AND id > $2
+                ORDER BY id
+                LIMIT $3",
+            )
+            .bind(active)
+            .bind(last_id)
+            .bind(BATCH)
+            .fetch_all(pool)
+            .await
+        })?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for stored in batch {
+            let id = stored.id;
+            match rotate_drawing(db, keys, stored).await {
+                Ok(updated) => rotated.drawings += updated,
+                Err(e) => {
+                    tracing::warn!("Failed to re-encrypt the drawing of response {id}: {e:?}");
+                    rotated.skipped += 1;
+                }
+            }
+        }
+    }
+
+    tracing::info!(
+        key_id = active,
+        names = rotated.names,
+        drawings = rotated.drawings,
+        skipped = rotated.skipped,
+        "Re-encrypted student data with the active key"
+    );
+
+    Ok(rotated)
+}
+
+impl Keys {
+    /// Digests the names on results and rosters that were stored before names were digested, which
+    /// the database can't keep unique until they are. Run before the server starts taking students,
+    /// whether or not there are keys to rotate to. Names that another student of the same test
+    /// already has, or that can't be decrypted, are logged and left as they are.
+    pub async fn digest_legacy_names(&self, db: &Db) -> anyhow::Result<u64> {
+        let mut digested = 0;
+
+        // Removed names aren't there to digest anymore
+        for (table, field, kept) in [
+            ("result", Field::Name, "name_purged_at IS NULL"),
+            ("roster", Field::RosterName, "TRUE"),
+        ] {
+            let mut last_id = Uuid::nil();
+            loop {
+                let batch = with_pool!(db, pool => {
+                    sqlx::query_as::<_, StoredName>(&format!(
+                        "SELECT id, test_id, name, name_key_id FROM {table}
+                        WHERE name_digest IS NULL AND {kept} AND id > $1
+                        ORDER BY id
+                        LIMIT $2"
+                    ))
+                    .bind(last_id)
+                    .bind(BATCH)
+                    .fetch_all(pool)
+                    .await
+                })?;
+                let Some(last) = batch.last() else {
+                    break;
+                };
+                last_id = last.id;
+
+                for stored in batch {
+                    let id = stored.id;
+                    let digest = self
+                        .open_text(field, id, stored.name_key_id.as_deref(), stored.name)
+                        .map(|name| self.digest_name(stored.test_id, name.trim()));
+                    let Ok(digest) = digest else {
+                        tracing::warn!("Failed to decrypt the name of {table} {id} to digest it");
+                        continue;
+                    };
+
+                    let res = with_pool!(db, pool => {
+                        sqlx::query(&format!(
+                            "UPDATE {table} SET name_digest = $1 WHERE id = $2 AND name_digest IS NULL"
+                        ))
+                        .bind(digest)
+                        .bind(id)
+                        .execute(pool)
+                        .await
+                        .map(|done| done.rows_affected())
+                    });
+
+                    match res {
+                        Ok(updated) => digested += updated,
+                        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => tracing::warn!(
+                            "The name of {table} {id} is a duplicate within its test, so it can't be digested"
+                        ),
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            }
+        }
+
+        if digested > 0 {
+            tracing::info!(
+                digested,
+                "Digested the names stored before names were digested"
+            );
+        }
+
+        Ok(digested)
+    }
+}
+
+/// Re-encrypts and digests a result's name, returning whether it was updated. That fails if
+/// another student of the test has the same name, which was possible before names were digested.
+async fn rotate_name(db: &Db, keys: &Keys, stored: StoredName) -> anyhow::Result<u64> {
+    let name = keys.open_text(
+        Field::Name,
+        stored.id,
+        stored.name_key_id.as_deref(),
+        stored.name,
+    )?;
+    let sealed = keys.seal_text(Field::Name, stored.id, &name)?;
+    let digest = keys.digest_name(stored.test_id, name.trim());
+
+    // Unless the name was removed or re-encrypted since it was read
+    let updated = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE result SET name = $1, name_key_id = $2, name_digest = $3
+            WHERE id = $4 AND name_purged_at IS NULL
+                AND (name_key_id = $5 OR (name_key_id IS NULL AND $5 IS NULL))",
+        )
+        .bind(sealed.value)
+        .bind(sealed.key_id)
+        .bind(digest)
+        .bind(stored.id)
+        .bind(&stored.name_key_id)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+    })?;
+
+    Ok(updated)
+}
+
+/// Like [`rotate_name`] for a name on a test's roster.
+async fn rotate_roster_name(db: &Db, keys: &Keys, stored: StoredName) -> anyhow::Result<u64> {
+    let name = keys.open_text(
+        Field::RosterName,
+        stored.id,
+        stored.name_key_id.as_deref(),
+        stored.name,
+    )?;
+    let sealed = keys.seal_text(Field::RosterName, stored.id, &name)?;
+    let digest = keys.digest_name(stored.test_id, name.trim());
+
+    let updated = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE roster SET name = $1, name_key_id = $2, name_digest = $3
+            WHERE id = $4 AND (name_key_id = $5 OR (name_key_id IS NULL AND $5 IS NULL))",
+        )
+        .bind(sealed.value)
+        .bind(sealed.key_id)
+        .bind(digest)
+        .bind(stored.id)
+        .bind(&stored.name_key_id)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+    })?;
+
+    Ok(updated)
+}
+
+/// Re-encrypts a response's drawing and strokes, returning whether they were updated.
+async fn rotate_drawing(db: &Db, keys: &Keys, stored: StoredDrawing) -> anyhow::Result<u64> {
+    let old_key = stored.drawing_key_id.as_deref();
+
+    let drawing = keys.open(Field::Drawing, stored.id, old_key, stored.drawing)?;
+    let strokes = stored
+        .strokes
+        .map(|strokes| keys.open_text(Field::Strokes, stored.id, old_key, strokes))
+        .transpose()?;
+
+    let drawing = keys.seal(Field::Drawing, stored.id, &drawing)?;
+    let strokes = strokes
+        .map(|strokes| keys.seal_text(Field::Strokes, stored.id, &strokes))
+        .transpose()?
+        .map(|sealed| sealed.value);
+
+    let updated = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE response SET drawing = $1, strokes = $2, drawing_key_id = $3
+            WHERE id = $4 AND drawing_purged_at IS NULL
+                AND (drawing_key_id = $5 OR (drawing_key_id IS NULL AND $5 IS NULL))",
+        )
+        .bind(drawing.value)
+        .bind(strokes)
+        .bind(drawing.key_id)
+        .bind(stored.id)
+        .bind(&stored.drawing_key_id)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+    })?;
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> String {
+        STANDARD.encode([byte; KEY_LEN])
+    }
+
+    /// Keys with the given ids, each 32 times the given byte, the first one active.
+    fn keys(list: &[(&str, u8)]) -> Keys {
+        let list: Vec<_> = list
+            .iter()
+            .map(|(id, byte)| format!("{id}:{}", key(*byte)))
+            .collect();
+
+        Keys::parse(&list.join(","), &key(0)).unwrap()
+    }
+
+    #[test]
+    fn seals_and_opens() {
+        let keys = keys(&[("k1", 1)]);
+        let id = Uuid::new_v4();
+
+        let sealed = keys.seal(Field::Drawing, id, b"drawing").unwrap();
+        assert_eq!(sealed.key_id.as_deref(), Some("k1"));
+        assert!(!sealed.value.windows(7).any(|window| window == b"drawing"));
+
+        let opened = keys.open(Field::Drawing, id, Some("k1"), sealed.value.clone());
+        assert_eq!(opened.unwrap(), b"drawing");
+
+        // It can't be passed off as another field or another row's, or opened with another key
+        let other_id = Uuid::new_v4();
+        let other_keys = self::keys(&[("k1", 2)]);
+        assert!(keys
+            .open(Field::Strokes, id, Some("k1"), sealed.value.clone())
+            .is_err());
+        assert!(keys
+            .open(Field::Drawing, other_id, Some("k1"), sealed.value.clone())
+            .is_err());
+        assert!(other_keys
+            .open(Field::Drawing, id, Some("k1"), sealed.value)
+            .is_err());
+    }
+
+    #[test]
+    fn seals_and_opens_text() {
+        let keys = keys(&[("k1", 1)]);
+        let id = Uuid::new_v4();
+
+        let sealed = keys.seal_text(Field::Name, id, "Ada").unwrap();
+        assert_ne!(sealed.value, "Ada");
+
+        let opened = keys.open_text(Field::Name, id, sealed.key_id.as_deref(), sealed.value);
+        assert_eq!(opened.unwrap(), "Ada");
+    }
+
+    #[test]
+    fn leaves_values_as_they_are_without_keys() {
+        let keys = Keys::default();
+        let id = Uuid::new_v4();
+
+        let sealed = keys.seal_text(Field::Name, id, "Ada").unwrap();
+        assert_eq!(sealed.key_id, None);
+        assert_eq!(sealed.value, "Ada");
+        assert_eq!(
+            keys.open_text(Field::Name, id, None, sealed.value).unwrap(),
+            "Ada"
+        );
+    }
+
+    #[test]
+    fn parses_keys() {
+        let keys = Keys::parse(&format!("k2:{}, k1:{}", key(2), key(1)), &key(0)).unwrap();
+        assert_eq!(keys.active_id(), Some("k2"));
+        assert_eq!(Keys::parse("", "").unwrap().active_id(), None);
+
+        let short = STANDARD.encode([1; 16]);
+        assert!(Keys::parse(&format!("k1:{}", key(1)), "").is_err());
+        assert!(Keys::parse(&format!("k1:{}", key(1)), &short).is_err());
+        assert!(Keys::parse(&format!("k1:{short}"), &key(0)).is_err());
+        assert!(Keys::parse(&format!("k1:{}", key(1)).repeat(2), &key(0)).is_err());
+        assert!(Keys::parse(&format!("k1:{},k1:{}", key(1), key(2)), &key(0)).is_err());
+        assert!(Keys::parse(&key(1), &key(0)).is_err());
+    }
+
+    #[test]
+    fn digests_names_per_test() {
+        let keys = keys(&[("k1", 1)]);
+        let (test_id, other_test) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let digest = keys.digest_name(test_id, "Ada");
+        assert_eq!(digest, keys.digest_name(test_id, "Ada"));
+        assert_ne!(digest, keys.digest_name(test_id, "Bea"));
+        assert_ne!(digest, keys.digest_name(other_test, "Ada"));
+
+        let other_key = Keys::parse("", &key(9)).unwrap();
+        assert_ne!(digest, other_key.digest_name(test_id, "Ada"));
+    }
+
+    /// A test of a teacher of its own, for results to be added to.
+    async fn add_test(db: &Db) -> Uuid {
+        let (teacher_id, test_id) = (Uuid::new_v4(), Uuid::new_v4());
+        with_pool!(db, pool => {
+            sqlx::query("INSERT INTO teacher (id, username, email, password) VALUES ($1, $2, $3, $4)")
+                .bind(teacher_id)
+                .bind("teacher")
+                .bind("teacher@example.com")
+                .bind("")
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })
+        .unwrap();
+        with_pool!(db, pool => {
+            sqlx::query("INSERT INTO test (id, teacher_id, name) VALUES ($1, $2, $3)")
+                .bind(test_id)
+                .bind(teacher_id)
+                .bind("Fall")
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })
+        .unwrap();
+
+        test_id
+    }
+
+    async fn add_result(db: &Db, keys: &Keys, test_id: Uuid, name: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        let sealed = keys.seal_text(Field::Name, id, name).unwrap();
+        // Names stored before there were keys weren't digested either
+        let digest = sealed
+            .key_id
+            .as_ref()
+            .map(|_| keys.digest_name(test_id, name));
+
+        with_pool!(db, pool => {
+            sqlx::query(
+                "INSERT INTO result (id, test_id, name, name_key_id, name_digest)
+                VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(id)
+            .bind(test_id)
+            .bind(sealed.value)
+            .bind(sealed.key_id)
+            .bind(digest)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })
+        .unwrap();
+
+        id
+    }
+
+    #[tokio::test]
+    async fn rotates_to_the_active_key() {
+        let db = Db::memory().await;
+        let old = keys(&[("k1", 1)]);
+        let removed = keys(&[("k0", 3)]);
+        let new = keys(&[("k2", 2), ("k1", 1)]);
+
+        let test_id = add_test(&db).await;
+
+        let ada = add_result(&db, &old, test_id, "Ada").await;
+        add_result(&db, &Keys::default(), test_id, "Bea").await;
+        // Its key was removed before the rotation got to it
+        let cy = add_result(&db, &removed, test_id, "Cy").await;
+
+        let entry_id = Uuid::new_v4();
+        let sealed = old.seal_text(Field::RosterName, entry_id, "Dee").unwrap();
+        with_pool!(db, pool => {
+            sqlx::query(
+                "INSERT INTO roster (id, test_id, name, name_key_id, name_digest)
+                VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(entry_id)
+            .bind(test_id)
+            .bind(sealed.value)
+            .bind(sealed.key_id)
+            .bind(old.digest_name(test_id, "Dee"))
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })
+        .unwrap();
+
+        let response_id = Uuid::new_v4();
+        let drawing = old.seal(Field::Drawing, response_id, b"drawing").unwrap();
+        let strokes = old.seal_text(Field::Strokes, response_id, "[]").unwrap();
+        with_pool!(db, pool => {
+            sqlx::query(
+                "INSERT INTO response
+                    (id, result_id, question_id, level, attempt, drawing, strokes, drawing_key_id)
+                VALUES ($1, $2, (SELECT id FROM question LIMIT 1), $3, $4, $5, $6, $7)",
+            )
+            .bind(response_id)
+            .bind(ada)
+            .bind(1)
+            .bind(1)
+            .bind(drawing.value)
+            .bind(strokes.value)
+            .bind(drawing.key_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })
+        .unwrap();
+
+        let rotated = rotate(&db, &new).await.unwrap();
+        assert_eq!(
+            (rotated.names, rotated.drawings, rotated.skipped),
+            (3, 1, 1)
+        );
+
+        let results = with_pool!(db, pool => {
+            sqlx::query_as::<_, (Uuid, String, Option<String>, Option<Vec<u8>>)>(
+                "SELECT id, name, name_key_id, name_digest FROM result WHERE id <> $1",
+            )
+            .bind(cy)
+            .fetch_all(pool)
+            .await
+        })
+        .unwrap();
+        let mut names = Vec::new();
+        for (id, name, key_id, digest) in results {
+            assert_eq!(key_id.as_deref(), Some("k2"));
+            let name = new
+                .open_text(Field::Name, id, key_id.as_deref(), name)
+                .unwrap();
+            assert_eq!(digest, Some(new.digest_name(test_id, &name)));
+            names.push(name);
+        }
+        names.sort();
+        assert_eq!(names, ["Ada", "Bea"]);
+
+        let (name, key_id) = with_pool!(db, pool => {
+            sqlx::query_as::<_, (String, Option<String>)>(
+                "SELECT name, name_key_id FROM roster WHERE id = $1",
+            )
+            .bind(entry_id)
+            .fetch_one(pool)
+            .await
+        })
+        .unwrap();
+        assert_eq!(key_id.as_deref(), Some("k2"));
+        let name = new.open_text(Field::RosterName, entry_id, key_id.as_deref(), name);
+        assert_eq!(name.unwrap(), "Dee");
+
+        let (drawing, strokes, key_id) = with_pool!(db, pool => {
+            sqlx::query_as::<_, (Vec<u8>, String, Option<String>)>(
+                "SELECT drawing, strokes, drawing_key_id FROM response WHERE id = $1",
+            )
+            .bind(response_id)
+            .fetch_one(pool)
+            .await
+        })
+        .unwrap();
+        assert_eq!(key_id.as_deref(), Some("k2"));
+        let key_id = key_id.as_deref();
+        let drawing = new.open(Field::Drawing, response_id, key_id, drawing);
+        assert_eq!(drawing.unwrap(), b"drawing");
+        let strokes = new.open_text(Field::Strokes, response_id, key_id, strokes);
+        assert_eq!(strokes.unwrap(), "[]");
+
+        // Only what can't be decrypted is left
+        let rotated = rotate(&db, &new).await.unwrap();
+        assert_eq!(
+            (rotated.names, rotated.drawings, rotated.skipped),
+            (0, 0, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn digests_legacy_names() {
+        let db = Db::memory().await;
+        let keys = Keys::default();
+        let test_id = add_test(&db).await;
+
+        let ada = add_result(&db, &keys, test_id, "Ada").await;
+        let bea = add_result(&db, &keys, test_id, "Bea ").await;
+        // Two students could register under the same name back then
+        let again = add_result(&db, &keys, test_id, "Ada").await;
+
+        assert_eq!(keys.digest_legacy_names(&db).await.unwrap(), 2);
+        assert_eq!(keys.digest_legacy_names(&db).await.unwrap(), 0);
+
+        let digests = with_pool!(db, pool => {
+            sqlx::query_as::<_, (Uuid, Option<Vec<u8>>)>("SELECT id, name_digest FROM result")
+                .fetch_all(pool)
+                .await
+        })
+        .unwrap();
+        let digest = |id| digests.iter().find(|row| row.0 == id).unwrap().1.clone();
+        assert_eq!(digest(bea), Some(keys.digest_name(test_id, "Bea")));
+        // Whichever comes first keeps the name to itself
+        let mut adas = [digest(ada), digest(again)];
+        adas.sort();
+        assert_eq!(adas, [None, Some(keys.digest_name(test_id, "Ada"))]);
+    }
+}
@@ -0,0 +1,246 @@
+use std::net::SocketAddr;
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, MatchedPath, Query};
+use axum::http::{Method, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::{middleware, routing::get, Extension, Json, Router};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::{Admins, Error, Result};
+use shared::audit::{AuditEntry, AuditFilter};
+
+/// How many entries the audit log shows at once, the latest first.
+const ENTRIES: i64 = 500;
+
+/// The routes that hand student data over without changing anything, logged all the same.
+const EXPORTS: &[&str] = &[
+    "/v1/test/:test_id/manage",
+    "/v1/test/:test_id/roster",
+    "/v1/test/:test_id/live",
+    "/v1/test/:test_id/export",
+    "/v1/test/:test_id/report",
+    "/v1/test/:test_id/students/:result_id/report",
+    "/v1/test/:test_id/students/:result_id/responses",
+    "/v1/test/:test_id/responses/:response_id/drawing",
+    "/v1/organization/:organization_id/summary",
+];
+
+/// The teacher who made a request, put on its response by [`teacher_auth`] so the audit log,
+/// which wraps every router, knows who it was.
+#[derive(Clone)]
+pub(crate) struct Actor {
+    pub id: Uuid,
+    pub username: String,
+    pub organization_id: Option<Uuid>,
+}
+
+impl Actor {
+    pub fn new(teacher: &Teacher) -> Self {
+        Actor {
+            id: teacher.id,
+            username: teacher.username.clone(),
+            organization_id: teacher.organization_id,
+        }
+    }
+}
+
+pub fn router() -> Router {
+    Router::new().route(
+        "/v1/audit",
+        get(get_audit_log).route_layer(middleware::from_fn(teacher_auth)),
+    )
+}
+
+/// Appends every request a teacher makes that changes something or exports student data to the
+/// audit log, whether it succeeded or not. Students and requests nobody logged in for aren't
+/// logged.
+pub async fn record(Extension(db): Extension<Db>, req: Request<Body>, next: Next) -> Response {
+    let Some(route) = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+    else {
+        return next.run(req).await;
+    };
+
+    let method = req.method().clone();
+    let audited = match method {
+        Method::GET | Method::HEAD => EXPORTS.contains(&route.as_str()),
+        Method::OPTIONS => false,
+        _ => true,
+    };
+    if !audited {
+        return next.run(req).await;
+    }
+
+    let target = req.uri().path().to_owned();
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+    let response = next.run(req).await;
+
+    let Some(actor) = response.extensions().get::<Actor>() else {
+        return response;
+    };
+
+    let res = with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO audit_log
+                (id, teacher_id, actor, organization_id, action, target, status, ip, at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(actor.id)
+        .bind(&actor.username)
+        .bind(actor.organization_id)
+        .bind(format!("{method} {route}"))
+        .bind(&target)
+        .bind(i32::from(response.status().as_u16()))
+        .bind(&ip)
+        .bind(Utc::now().timestamp())
+        .execute(pool)
+        .await
+        .map(|_| ())
+    });
+
+    // The request already happened, so it's answered even if it couldn't be logged
+    if let Err(e) = res {
+        tracing::error!("Failed to write to the audit log: {e:?}");
+    }
+
+    response
+}
+
+/// Get the latest entries of the audit log, optionally narrowed down. The server's admins see
+/// everything, and school and district admins what their teachers did.
+#[utoipa::path(
+    get,
+    path = "/v1/audit",
+    tag = "admin",
+    params(
+        ("actor" = Option<String>, Query, description = "Part of the teacher's username"),
+        ("action" = Option<String>, Query, description = "Part of the method and route"),
+        ("target" = Option<String>, Query, description = "Part of the requested path"),
+        ("since" = Option<i64>, Query, description = "Only entries from this time on, in seconds since the Unix epoch"),
+        ("until" = Option<i64>, Query, description = "Only entries before this time, in seconds since the Unix epoch"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The matching entries, the latest first", body = [AuditEntry]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher doesn't administer the server or an organization", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_audit_log(
+    Extension(db): Extension<Db>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<Vec<AuditEntry>>> {
+    // None for the server's admins, who see every organization and the teachers outside of them
+    let organization_id = if admins.contains(&teacher) {
+        None
+    } else {
+        let own = teacher
+            .organization_id
+            .filter(|_| teacher.organization_admin);
+        Some(own.ok_or_else(|| {
+            Error::Forbidden("Only administrators can see the audit log".to_string())
+        })?)
+    };
+
+    let like = |text: Option<String>| {
+        text.map(|text| text.trim().to_lowercase())
+            .filter(|text| !text.is_empty())
+            .map(|text| format!("%{text}%"))
+    };
+
+    let entries = with_pool!(db, pool => {
+        sqlx::query_as::<_, AuditEntry>(
+            "SELECT * FROM audit_log
+            WHERE ($1 IS NULL OR LOWER(actor) LIKE $1)
+                AND ($2 IS NULL OR LOWER(action) LIKE $2)
+                AND ($3 IS NULL OR LOWER(target) LIKE $3)
+                AND ($4 IS NULL OR at >= $4)
+                AND ($5 IS NULL OR at < $5)
+                AND ($6 IS NULL OR organization_id = $6
+                    OR organization_id IN (SELECT id FROM organization WHERE parent_id = $6))
+            ORDER BY at DESC
+            LIMIT $7",
+        )
+        .bind(like(filter.actor))
+        .bind(like(filter.action))
+        .bind(like(filter.target))
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(organization_id)
+        .bind(ENTRIES)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use utoipa::openapi::PathItemType;
+    use utoipa::OpenApi;
+
+    use super::EXPORTS;
+    use crate::http::docs::ApiDoc;
+
+    /// The GET routes that don't give a teacher anything about the students who took a test.
+    const NOT_EXPORTS: &[&str] = &[
+        "/v1/admin/encryption",
+        "/v1/admin/jobs",
+        "/v1/audit",
+        "/v1/join/:code",
+        "/v1/organization",
+        "/v1/organization/invitations",
+        "/v1/organization/:organization_id/members",
+        "/v1/organization/:organization_id/questions",
+        "/v1/organization/:organization_id/retention",
+        "/v1/question",
+        "/v1/question/:question_level",
+        "/v1/test",
+        "/v1/test/:test_id/control",
+        "/v1/test/:test_id/questions",
+        "/v1/test/:test_id/retention",
+        "/v1/test/:test_id/shares",
+    ];
+
+    /// Every documented GET route has to be put on one list or the other, so none that hands
+    /// over student data is added without being logged.
+    #[test]
+    fn every_export_is_audited() {
+        let gets: Vec<String> = ApiDoc::openapi()
+            .paths
+            .paths
+            .into_iter()
+            .filter(|(_, item)| item.operations.contains_key(&PathItemType::Get))
+            .map(|(path, _)| path.replace('{', ":").replace('}', ""))
+            .collect();
+
+        for route in &gets {
+            assert!(
+                EXPORTS.contains(&route.as_str()) || NOT_EXPORTS.contains(&route.as_str()),
+                "GET {route} has to be audited if it hands over student data"
+            );
+        }
+        for route in EXPORTS {
+            assert!(
+                gets.iter().any(|get| get == route),
+                "{route} isn't a GET route"
+            );
+        }
+    }
+}
@@ -9,10 +9,12 @@ use axum_extra::extract::cookie::{Cookie, SameSite};
 use axum_extra::extract::CookieJar;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
 
-use crate::http::{teacher::Teacher, test::student::StudentResult, Error, Result};
+use crate::db::{with_pool, Db};
+use crate::encryption::Keys;
+use crate::http::{audit::Actor, teacher::Teacher, Error, Result};
 use crate::util::Config;
+use shared::test::StudentResult;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
@@ -23,7 +25,7 @@ pub struct TokenClaims {
 
 pub async fn teacher_auth(
     cookie_jar: CookieJar,
-    Extension(db): Extension<PgPool>,
+    Extension(db): Extension<Db>,
     Extension(cfg): Extension<Config>,
     mut req: Request<Body>,
     next: Next,
@@ -59,39 +61,50 @@ pub async fn teacher_auth(
     let teacher_id = uuid::Uuid::parse_str(&claims.sub)
         .map_err(|_| Error::Authorization("Invalid token".into()))?;
 
-    let teacher = sqlx::query_as!(Teacher, "SELECT * FROM teacher WHERE id = $1", teacher_id)
-        .fetch_optional(&db)
-        .await?;
+    let teacher = with_pool!(db, pool => {
+        sqlx::query_as::<_, Teacher>("SELECT * FROM teacher WHERE id = $1")
+            .bind(teacher_id)
+            .fetch_optional(pool)
+            .await
+    })?;
 
     let teacher = teacher.ok_or_else(|| {
         Error::Authorization("The teacher belonging to this token no longer exists".to_string())
     })?;
 
+    let actor = Actor::new(&teacher);
     req.extensions_mut().insert(teacher);
-    Ok(next.run(req).await)
+
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(actor);
+    Ok(response)
 }
 
 pub async fn student_auth(
     cookie_jar: CookieJar,
-    Extension(db): Extension<PgPool>,
+    Extension(db): Extension<Db>,
     Extension(cfg): Extension<Config>,
+    Extension(keys): Extension<Keys>,
     mut req: Request<Body>,
     next: Next,
 ) -> Result<impl IntoResponse> {
-    let token = cookie_jar
-        .get("STUDENT_TOKEN")
-        .map(|cookie| cookie.value().to_string())
+    // A kiosk sends each student's token explicitly, which has to win over a cookie left behind by
+    // whoever used the device before
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| {
+            if auth_value.starts_with("Bearer ") {
+                Some(auth_value[7..].to_owned())
+            } else {
+                None
+            }
+        })
         .or_else(|| {
-            req.headers()
-                .get(header::AUTHORIZATION)
-                .and_then(|auth_header| auth_header.to_str().ok())
-                .and_then(|auth_value| {
-                    if auth_value.starts_with("Bearer ") {
-                        Some(auth_value[7..].to_owned())
-                    } else {
-                        None
-                    }
-                })
+            cookie_jar
+                .get("STUDENT_TOKEN")
+                .map(|cookie| cookie.value().to_string())
         });
 
     let token = token.ok_or_else(|| Error::Authorization("You are not logged in".to_string()))?;
@@ -107,25 +120,38 @@ pub async fn student_auth(
     let student_id = uuid::Uuid::parse_str(&claims.sub)
         .map_err(|_| Error::Authorization("Invalid token".to_string()))?;
 
-    let result = sqlx::query_as!(
-        StudentResult,
-        "SELECT * FROM result WHERE id = $1",
-        student_id
-    )
-    .fetch_optional(&db)
-    .await?;
+    let result = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1")
+            .bind(student_id)
+            .fetch_optional(pool)
+            .await
+    })?;
 
-    let result = result.ok_or_else(|| {
+    let mut result = result.ok_or_else(|| {
         Error::Authorization(
             "The student result belonging to this token no longer exists".to_string(),
         )
     })?;
+    keys.open_name(&mut result)?;
 
     req.extensions_mut().insert(result);
     Ok(next.run(req).await)
 }
 
 pub async fn create_cookie(cookie_name: &str, id: uuid::Uuid, cfg: Config) -> String {
+    let token = create_token(id, &cfg);
+
+    let cookie = Cookie::build((cookie_name, token))
+        .path("/")
+        .max_age(time::Duration::hours(1))
+        .same_site(SameSite::Lax)
+        .http_only(true);
+
+    cookie.to_string()
+}
+
+/// A token valid for an hour, for clients that send it as a bearer token rather than a cookie.
+pub fn create_token(id: uuid::Uuid, cfg: &Config) -> String {
     let now = chrono::Utc::now();
     let iat = now.timestamp() as usize;
     let exp = (now + chrono::Duration::minutes(60)).timestamp() as usize;
@@ -135,18 +161,10 @@ pub async fn create_cookie(cookie_name: &str, id: uuid::Uuid, cfg: Config) -> St
         iat,
     };
 
-    let token = encode(
+    encode(
         &Header::default(),
         &claims,
         &EncodingKey::from_secret(cfg.jwt_secret.as_ref()),
     )
-    .unwrap();
-
-    let cookie = Cookie::build((cookie_name, token.to_owned()))
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .http_only(true);
-
-    cookie.to_string()
+    .unwrap()
 }
@@ -0,0 +1,40 @@
+use axum::{routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::util::Opt;
+
+/// Runtime configuration handed to the frontend, so a single build can be deployed anywhere.
+///
+/// Missing values tell the frontend to fall back to the origin it was served from.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Clone)]
+pub struct ClientConfig {
+    api_url: Option<String>,
+    public_url: Option<String>,
+}
+
+pub fn router(opt: &Opt) -> Router {
+    let public_url = opt
+        .public_url
+        .as_ref()
+        .map(|url| url.trim_end_matches('/').to_string());
+
+    let api_url = opt
+        .api_url
+        .as_ref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .or_else(|| public_url.as_ref().map(|url| format!("{url}/v1")));
+
+    let config = ClientConfig {
+        api_url,
+        public_url,
+    };
+
+    Router::new().route(
+        "/config.json",
+        get(move || {
+            let config = config.clone();
+            async move { Json(config) }
+        }),
+    )
+}
@@ -0,0 +1,228 @@
+use axum::response::Html;
+use axum::{routing::get, Json, Router};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::http::{
+    audit, encryption, jobs, organization, question, retention, teacher, test, test::consent,
+    test::control, test::join, test::kiosk, test::live, test::report, test::score, test::share,
+    test::student,
+};
+use shared::audit::AuditEntry;
+use shared::consent::{Consent, RecordConsent, ResearchExport, ResearchResponse, ResearchStudent};
+use shared::encryption::{EncryptionStatus, KeyUsage, Rotation};
+use shared::error::{ErrorCode, ErrorResponse};
+use shared::i18n::Language;
+use shared::job::{Job, JobCounts, JobStatus, JobsReport};
+use shared::live::{
+    CanvasFrame, Command, ReportProgress, SendCommand, SessionControl, StudentProgress,
+};
+use shared::organization::{
+    AddMember, CreateOrganization, CreateQuestion, Invitation, Member, Organization,
+    OrganizationKind, OrganizationSummary, TeacherSummary,
+};
+use shared::phonetics::Stage;
+use shared::question::{Question, QuestionPlan, QuestionTranslation};
+use shared::retention::{Purge, RetentionPolicy, RetentionReport, TestRetention};
+use shared::score::{Point, ScoreResponse, ScoredResponse};
+use shared::share::{Permission, Share, ShareTest, TransferTests};
+use shared::teacher::{LoginTeacher, RegisterTeacher};
+use shared::test::{
+    CreateTest, JoinTest, KioskSession, RegisterStudent, RosterEntry, SetLevel, SetPin,
+    StudentResult, SubmitResponse, Test, TestSettings,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "fnk",
+        description = "The API behind fnk's teacher dashboard and student tests.",
+        license(name = "GPL-3.0")
+    ),
+    paths(
+        teacher::register_teacher,
+        teacher::login_teacher,
+        teacher::logout_teacher,
+        share::transfer_tests,
+        test::create_test,
+        test::get_tests,
+        test::get_results,
+        test::inverse_closed,
+        test::update_settings,
+        join::set_pin,
+        kiosk::get_roster,
+        kiosk::add_to_roster,
+        kiosk::remove_from_roster,
+        kiosk::start_kiosk_session,
+        kiosk::start_roster_session,
+        kiosk::renew_kiosk_session,
+        live::watch_test,
+        control::control_student,
+        control::control_all,
+        report::class_report,
+        report::student_report,
+        score::get_responses,
+        score::get_drawing,
+        score::score_response,
+        share::get_shares,
+        share::share_test,
+        share::unshare_test,
+        consent::record_consent,
+        consent::research_export,
+        retention::get_test_retention,
+        retention::set_test_retention,
+        join::find_test,
+        student::register_student,
+        student::set_score,
+        student::submit_response,
+        live::report_progress,
+        control::poll_control,
+        question::get_all_questions,
+        question::get_questions,
+        question::get_plan,
+        question::upload_audio,
+        question::remove_audio,
+        organization::get_organizations,
+        organization::create_organization,
+        organization::get_members,
+        organization::add_member,
+        organization::remove_member,
+        organization::get_invitations,
+        organization::accept_invitation,
+        organization::decline_invitation,
+        organization::update_settings,
+        organization::get_summary,
+        organization::get_questions,
+        organization::create_question,
+        organization::remove_question,
+        retention::get_organization_retention,
+        retention::set_organization_retention,
+        jobs::get_jobs,
+        jobs::retry_job,
+        encryption::get_encryption,
+        encryption::rotate_keys,
+        audit::get_audit_log,
+    ),
+    components(schemas(
+        ErrorResponse,
+        ErrorCode,
+        RegisterTeacher,
+        LoginTeacher,
+        Test,
+        TestSettings,
+        CreateTest,
+        StudentResult,
+        RegisterStudent,
+        JoinTest,
+        SetPin,
+        RosterEntry,
+        KioskSession,
+        SetLevel,
+        SubmitResponse,
+        Point,
+        ScoredResponse,
+        ScoreResponse,
+        Stage,
+        Permission,
+        Share,
+        ShareTest,
+        TransferTests,
+        Consent,
+        RecordConsent,
+        ResearchExport,
+        ResearchStudent,
+        ResearchResponse,
+        RetentionPolicy,
+        TestRetention,
+        Purge,
+        RetentionReport,
+        StudentProgress,
+        CanvasFrame,
+        ReportProgress,
+        Command,
+        SendCommand,
+        SessionControl,
+        Language,
+        Question,
+        QuestionTranslation,
+        QuestionPlan,
+        Organization,
+        OrganizationKind,
+        CreateOrganization,
+        Member,
+        AddMember,
+        Invitation,
+        TeacherSummary,
+        OrganizationSummary,
+        CreateQuestion,
+        Job,
+        JobStatus,
+        JobCounts,
+        JobsReport,
+        EncryptionStatus,
+        KeyUsage,
+        Rotation,
+        AuditEntry,
+    )),
+    modifiers(&SessionCookies),
+    tags(
+        (name = "teacher", description = "Teacher accounts and sessions"),
+        (name = "test", description = "Managing tests from the dashboard"),
+        (name = "student", description = "Taking a test as a student"),
+        (name = "question", description = "The question bank"),
+        (name = "organization", description = "Schools and districts, and their admins"),
+        (name = "admin", description = "Administering the server itself"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Teachers and students are authenticated by the cookies set when logging in or registering.
+struct SessionCookies;
+
+impl Modify for SessionCookies {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "teacher_token",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("TEACHER_TOKEN"))),
+        );
+        components.add_security_scheme(
+            "student_token",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("STUDENT_TOKEN"))),
+        );
+    }
+}
+
+// Swagger UI is pulled from a CDN, the same way the frontend pulls in tailwind.
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>fnk API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+    <script>
+        window.onload = () => {
+            window.ui = SwaggerUIBundle({
+                url: "/v1/openapi.json",
+                dom_id: "#swagger-ui",
+                withCredentials: true,
+            });
+        };
+    </script>
+</body>
+</html>
+"##;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/openapi.json",
+            get(|| async { Json(ApiDoc::openapi()) }),
+        )
+        .route("/v1/docs", get(|| async { Html(SWAGGER_UI) }))
+}
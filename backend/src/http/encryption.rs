@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use axum::http::StatusCode;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use chrono::Utc;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::Keys;
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::{Admins, Error, Result};
+use crate::jobs::{self, Task};
+use shared::encryption::{EncryptionStatus, KeyUsage, Rotation};
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/admin/encryption",
+            get(get_encryption).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/admin/encryption/rotate",
+            post(rotate_keys).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+const ONLY_ADMINS: &str = "Only the server's admins can manage its encryption keys";
+
+/// Get which key encrypts how many of the students' names and drawings, to know when a rotation
+/// is done and the older keys can be removed. Only for the server's admins.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/encryption",
+    tag = "admin",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The keys in use", body = EncryptionStatus),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher doesn't administer the server", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_encryption(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+) -> Result<Json<EncryptionStatus>> {
+    admins.check(&teacher, ONLY_ADMINS)?;
+
+    // What was removed under the retention policy isn't encrypted, and doesn't need to be
+    let names = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Option<String>, i64)>(
+            "SELECT name_key_id, COUNT(*) FROM (
+                SELECT name_key_id FROM result WHERE name_purged_at IS NULL
+                UNION ALL
+                SELECT name_key_id FROM roster
+            ) names
+            GROUP BY name_key_id",
+        )
+        .fetch_all(pool)
+        .await
+    })?;
+    let drawings = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Option<String>, i64)>(
+            "SELECT drawing_key_id, COUNT(*) FROM response WHERE drawing_purged_at IS NULL
+            GROUP BY drawing_key_id",
+        )
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let mut usage = BTreeMap::<Option<String>, (i64, i64)>::new();
+    for (key_id, count) in names {
+        usage.entry(key_id).or_default().0 = count;
+    }
+    for (key_id, count) in drawings {
+        usage.entry(key_id).or_default().1 = count;
+    }
+
+    Ok(Json(EncryptionStatus {
+        active_key_id: keys.active_id().map(str::to_string),
+        keys: usage
+            .into_iter()
+            .map(|(key_id, (names, drawings))| KeyUsage {
+                key_id,
+                names,
+                drawings,
+            })
+            .collect(),
+    }))
+}
+
+/// Re-encrypt every student name and drawing that isn't encrypted with the active key yet, the
+/// first one in `ENCRYPTION_KEYS`, in the background. Only for the server's admins.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/encryption/rotate",
+    tag = "admin",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The rotation was started", body = Rotation),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher doesn't administer the server", body = ErrorResponse),
+        (status = 422, description = "The server has no encryption keys", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn rotate_keys(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+) -> Result<(StatusCode, Json<Rotation>)> {
+    admins.check(&teacher, ONLY_ADMINS)?;
+
+    if keys.active_id().is_none() {
+        return Err(Error::UnprocessableEntity(
+            "The server has no encryption key to rotate to".to_string(),
+        ));
+    }
+
+    let job_id = jobs::enqueue(&db, &Task::RotateKeys, Utc::now().timestamp()).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(Rotation { job_id })))
+}
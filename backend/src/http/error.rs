@@ -2,7 +2,10 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
-use serde_with::DisplayFromStr;
+use shared::error::{validation_messages, ErrorCode, ErrorResponse};
+use shared::i18n::translate;
+
+use crate::http::{language, request_id};
 use validator::ValidationErrors;
 
 /// An API-friendly error type.
@@ -30,38 +33,23 @@ pub enum Error {
 
     #[error("{0}")]
     Conflict(String),
+
+    /// Logged in, but not allowed to. Unlike [`Error::Authorization`], this doesn't log the
+    /// client out.
+    #[error("{0}")]
+    Forbidden(String),
+
+    /// Like [`Error::Conflict`], but only until the teacher resumes the student's session.
+    #[error("{0}")]
+    Paused(String),
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        #[serde_with::serde_as]
-        #[serde_with::skip_serializing_none]
-        #[derive(serde::Serialize)]
-        struct ErrorResponse<'a> {
-            // Serialize the `Display` output as the error message
-            #[serde_as(as = "DisplayFromStr")]
-            message: &'a Error,
-
-            errors: Option<Vec<String>>,
-
-            auth_error: bool,
-        }
+        let language = language::current();
 
         let errors = match &self {
-            Self::InvalidEntity(errors) => Some(
-                errors
-                    .field_errors()
-                    .into_iter()
-                    .map(|error| {
-                        let default = format!("{} is required", error.0);
-                        error.1[0]
-                            .message
-                            .as_ref()
-                            .unwrap_or(&std::borrow::Cow::Owned(default))
-                            .to_string()
-                    })
-                    .collect(),
-            ),
+            Self::InvalidEntity(errors) => Some(validation_messages(errors, language)),
             _ => None,
         };
 
@@ -75,9 +63,11 @@ impl IntoResponse for Error {
         (
             self.status_code(),
             Json(ErrorResponse {
-                message: &self,
+                code: self.code(),
+                message: translate(language, &self.to_string()).to_string(),
                 errors,
                 auth_error,
+                request_id: request_id::current(),
             }),
         )
             .into_response()
@@ -85,6 +75,20 @@ impl IntoResponse for Error {
 }
 
 impl Error {
+    fn code(&self) -> ErrorCode {
+        use Error::*;
+
+        match self {
+            Sqlx(_) | Anyhow(_) => ErrorCode::Internal,
+            InvalidEntity(_) => ErrorCode::Validation,
+            Authorization(_) => ErrorCode::Unauthorized,
+            UnprocessableEntity(_) => ErrorCode::Unprocessable,
+            Conflict(_) => ErrorCode::Conflict,
+            Forbidden(_) => ErrorCode::Forbidden,
+            Paused(_) => ErrorCode::Paused,
+        }
+    }
+
     fn status_code(&self) -> StatusCode {
         use Error::*;
 
@@ -92,7 +96,8 @@ impl Error {
             Sqlx(_) | Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,
             InvalidEntity(_) | UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             Authorization(_) => StatusCode::UNAUTHORIZED,
-            Conflict(_) => StatusCode::CONFLICT,
+            Conflict(_) | Paused(_) => StatusCode::CONFLICT,
+            Forbidden(_) => StatusCode::FORBIDDEN,
         }
     }
 }
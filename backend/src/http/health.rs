@@ -0,0 +1,36 @@
+use crate::db::{with_pool, Db};
+use crate::shutdown::Shutdown;
+use axum::http::StatusCode;
+use axum::{routing::get, Extension, Router};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
+
+/// The process is up and serving requests.
+async fn healthz() -> StatusCode {
+    StatusCode::OK
+}
+
+/// The process can reach the database, and so can actually handle API requests. Fails as soon as a
+/// shutdown starts so load balancers stop routing new sessions here while the old ones drain.
+async fn readyz(
+    Extension(db): Extension<Db>,
+    Extension(shutdown): Extension<Shutdown>,
+) -> StatusCode {
+    if shutdown.is_triggered() {
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+
+    let res = with_pool!(db, pool => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()));
+
+    match res {
+        Ok(_) => StatusCode::OK,
+        Err(e) => {
+            tracing::warn!("Readiness check failed: {e}");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::{Error, Result};
+use crate::jobs;
+use shared::job::{Job, JobCounts, JobStatus, JobsReport};
+
+/// How many of the latest jobs the status lists.
+const JOBS: i64 = 100;
+
+/// The teachers who administer the server itself, by the ids given with `--admin`. Not by email,
+/// since anyone can register with any address.
+#[derive(Clone, Default)]
+pub struct Admins(Arc<Vec<Uuid>>);
+
+impl Admins {
+    pub fn new(ids: &[Uuid]) -> Self {
+        Admins(Arc::new(ids.to_vec()))
+    }
+
+    /// Whether `teacher` is one of them.
+    pub(crate) fn contains(&self, teacher: &Teacher) -> bool {
+        self.0.contains(&teacher.id)
+    }
+
+    /// Forbids whatever `teacher` is doing with `message` unless they're one of them.
+    pub(crate) fn check(&self, teacher: &Teacher, message: &str) -> Result<()> {
+        if self.contains(teacher) {
+            Ok(())
+        } else {
+            Err(Error::Forbidden(message.to_string()))
+        }
+    }
+}
+
+const ONLY_ADMINS: &str = "Only the server's admins can see its background jobs";
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/admin/jobs",
+            get(get_jobs).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/admin/jobs/:job_id/retry",
+            post(retry_job).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JobsParams {
+    /// Only list the jobs with this status.
+    status: Option<JobStatus>,
+}
+
+/// Get how many background jobs are waiting, running, done and given up on, with the latest of
+/// them and why they failed. Only for the server's admins.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/jobs",
+    tag = "admin",
+    params(
+        ("status" = Option<JobStatus>, Query, description = "Only list the jobs with this status"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The background jobs", body = JobsReport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher doesn't administer the server", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_jobs(
+    Extension(db): Extension<Db>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+    Query(params): Query<JobsParams>,
+) -> Result<Json<JobsReport>> {
+    admins.check(&teacher, ONLY_ADMINS)?;
+
+    let counts = with_pool!(db, pool => {
+        sqlx::query_as::<_, (JobStatus, i64)>("SELECT status, COUNT(*) FROM job GROUP BY status")
+            .fetch_all(pool)
+            .await
+    })?;
+
+    let mut totals = JobCounts::default();
+    for (status, count) in counts {
+        let total = match status {
+            JobStatus::Pending => &mut totals.pending,
+            JobStatus::Running => &mut totals.running,
+            JobStatus::Succeeded => &mut totals.succeeded,
+            JobStatus::Failed => &mut totals.failed,
+        };
+        *total = count;
+    }
+
+    let jobs = match params.status {
+        Some(status) => with_pool!(db, pool => {
+            sqlx::query_as::<_, Job>(
+                "SELECT * FROM job WHERE status = $1 ORDER BY created_at DESC LIMIT $2",
+            )
+            .bind(status)
+            .bind(JOBS)
+            .fetch_all(pool)
+            .await
+        })?,
+        None => with_pool!(db, pool => {
+            sqlx::query_as::<_, Job>("SELECT * FROM job ORDER BY created_at DESC LIMIT $1")
+                .bind(JOBS)
+                .fetch_all(pool)
+                .await
+        })?,
+    };
+
+    Ok(Json(JobsReport {
+        counts: totals,
+        jobs,
+    }))
+}
+
+/// Run a background job that was given up on again, from its first attempt. Only for the
+/// server's admins.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/jobs/{job_id}/retry",
+    tag = "admin",
+    params(("job_id" = Uuid, Path, description = "The job's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The job will be run again"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher doesn't administer the server", body = ErrorResponse),
+        (status = 422, description = "The job doesn't exist or hasn't failed", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn retry_job(
+    Extension(db): Extension<Db>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+    Path(job_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    admins.check(&teacher, ONLY_ADMINS)?;
+
+    if !jobs::retry(&db, job_id).await? {
+        return Err(Error::UnprocessableEntity(
+            "Only jobs that failed can be retried".to_string(),
+        ));
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
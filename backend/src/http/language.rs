@@ -0,0 +1,28 @@
+use axum::{
+    body::Body,
+    http::{header::ACCEPT_LANGUAGE, Request},
+    middleware::Next,
+    response::IntoResponse,
+};
+use shared::i18n::Language;
+
+tokio::task_local! {
+    static LANGUAGE: Language;
+}
+
+/// Makes the language asked for in `Accept-Language` available to `current`.
+pub async fn scope(req: Request<Body>, next: Next) -> impl IntoResponse {
+    let language = req
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|header| header.to_str().ok())
+        .and_then(Language::negotiate)
+        .unwrap_or_default();
+
+    LANGUAGE.scope(language, next.run(req)).await
+}
+
+/// The language messages of the request currently being handled should be written in.
+pub fn current() -> Language {
+    LANGUAGE.try_with(|language| *language).unwrap_or_default()
+}
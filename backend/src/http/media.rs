@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use axum::{
+    http::{header::CACHE_CONTROL, HeaderValue, Response},
+    Router,
+};
+use tokio::fs;
+use tower_http::{services::ServeDir, set_header::SetResponseHeaderLayer};
+
+use crate::http::MEDIA_CACHE_CONTROL;
+
+/// Where files uploaded through the API, such as question recordings, are kept.
+#[derive(Debug, Clone)]
+pub struct Media {
+    dir: PathBuf,
+}
+
+// Paths handed out to clients are relative to the public URL, like the question images are.
+const PREFIX: &str = "media/";
+
+impl Media {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Media {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Stores `bytes` as `name` and returns the path the file is served at.
+    pub async fn save(&self, name: &str, bytes: &[u8]) -> anyhow::Result<String> {
+        let path = self.dir.join(name);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        fs::write(&path, bytes)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(format!("{PREFIX}{name}"))
+    }
+
+    /// Deletes a file previously stored by `save`, given the path it was served at.
+    pub async fn remove(&self, served_at: &str) -> anyhow::Result<()> {
+        let Some(name) = served_at.strip_prefix(PREFIX) else {
+            return Ok(());
+        };
+
+        let path = self.dir.join(name);
+        match fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn router(media: &Media) -> Router {
+    // Uploads are saved under a new name every time, so they can be cached like images
+    let cache_control = SetResponseHeaderLayer::overriding(CACHE_CONTROL, |res: &Response<_>| {
+        res.status()
+            .is_success()
+            .then(|| HeaderValue::from_static(MEDIA_CACHE_CONTROL))
+    });
+
+    Router::new().nest_service(
+        &format!("/{}", PREFIX.trim_end_matches('/')),
+        tower::ServiceBuilder::new()
+            .layer(cache_control)
+            .service(ServeDir::new(&media.dir)),
+    )
+}
@@ -1,16 +1,22 @@
+use std::future::IntoFuture;
 use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
+use crate::db::Db;
+use crate::encryption::Keys;
+use crate::scoring::{Recognizer, Scoring};
+use crate::shutdown::Shutdown;
 use crate::util::{Config, Opt};
 use anyhow::Context;
 use axum::{
     body::Body,
     http::{
-        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+        header::{ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE},
         HeaderValue, Method, Request, StatusCode,
     },
-    Extension,
+    middleware, Extension,
 };
 use axum::{
     response::{Html, IntoResponse},
@@ -18,33 +24,92 @@ use axum::{
     Router,
 };
 use axum_server::tls_rustls::RustlsConfig;
-use sqlx::PgPool;
 use tokio::fs;
 use tower::{ServiceBuilder, ServiceExt};
-use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
+use tower_http::{
+    cors::CorsLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::ServeDir,
+    trace::TraceLayer,
+};
 
+mod audit;
 mod auth;
+mod client;
+mod docs;
+mod encryption;
 mod error;
+mod health;
+mod jobs;
+mod language;
+mod media;
+mod organization;
 mod question;
+mod request_id;
+mod retention;
 mod teacher;
+mod telemetry;
 mod test;
 
 pub use self::error::Error;
+pub use self::jobs::Admins;
+pub use self::media::Media;
+pub use self::test::live::Live;
 pub type Result<T, E = Error> = ::std::result::Result<T, E>;
 
-pub fn app(opt: Opt, db: PgPool, cfg: Config) -> Router {
+/// Question images and recordings rarely change, so browsers may keep using them for a day without
+/// asking again.
+const MEDIA_CACHE_CONTROL: &str = "public, max-age=86400";
+
+pub fn app(opt: Opt, db: Db, cfg: Config, keys: Keys, shutdown: Shutdown) -> Router {
+    let origin = opt
+        .public_url
+        .as_deref()
+        .unwrap_or("https://localhost:8080")
+        .trim_end_matches('/');
+
+    let media = Media::new(&opt.media_dir);
+    let admins = Admins::new(&opt.admins);
+
+    let scoring = match opt.recognizer.as_deref() {
+        Some(command) => Scoring::new(
+            Recognizer::new(command).expect("The recognizer command must name a program"),
+        ),
+        None => Scoring::default(),
+    };
+
     let cors = CorsLayer::new()
-        .allow_origin("https://localhost:8080".parse::<HeaderValue>().unwrap())
+        .allow_origin(
+            origin
+                .parse::<HeaderValue>()
+                .expect("The public URL must be a valid origin"),
+        )
         .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
         .allow_credentials(true)
-        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
+        .allow_headers([AUTHORIZATION, ACCEPT, ACCEPT_LANGUAGE, CONTENT_TYPE])
+        .expose_headers([request_id::X_REQUEST_ID.clone()]);
 
     Router::new()
+        .merge(client::router(&opt))
+        .merge(docs::router())
+        .merge(health::router())
+        .merge(jobs::router())
+        .merge(encryption::router())
+        .merge(audit::router())
+        .merge(telemetry::router())
         .merge(teacher::router())
         .merge(test::router())
         .merge(question::router())
+        .merge(organization::router())
+        .merge(retention::router())
+        .merge(media::router(&media))
         .fallback_service(get(|req: Request<Body>| async move {
-            let res = ServeDir::new(&opt.static_dir).oneshot(req).await.unwrap(); // serve dir is infallible
+            let mut res = ServeDir::new(&opt.static_dir).oneshot(req).await.unwrap(); // serve dir is infallible
+            let is_image = res
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .is_some_and(|content_type| content_type.starts_with("image/"));
             let status = res.status();
             match status {
                 StatusCode::NOT_FOUND => {
@@ -57,16 +122,65 @@ pub fn app(opt: Opt, db: PgPool, cfg: Config) -> Router {
                                 .into_response()
                         })
                 }
+                StatusCode::OK if is_image => {
+                    res.headers_mut()
+                        .insert(CACHE_CONTROL, HeaderValue::from_static(MEDIA_CACHE_CONTROL));
+                    res.into_response()
+                }
                 _ => res.into_response(),
             }
         }))
+        .layer(middleware::from_fn(audit::record))
+        .layer(middleware::from_fn(language::scope))
+        .layer(middleware::from_fn(request_id::scope))
+        .layer(middleware::from_fn(telemetry::track))
         .layer(Extension(db))
         .layer(Extension(cfg))
+        .layer(Extension(keys))
+        .layer(Extension(shutdown))
+        .layer(Extension(media))
+        .layer(Extension(admins))
+        .layer(Extension(scoring))
+        .layer(Extension(Live::default()))
         .layer(cors)
-        .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()))
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::new(
+                    request_id::X_REQUEST_ID.clone(),
+                    MakeRequestUuid,
+                ))
+                .layer(
+                    TraceLayer::new_for_http().make_span_with(|req: &Request<Body>| {
+                        let request_id = req
+                            .headers()
+                            .get(&request_id::X_REQUEST_ID)
+                            .and_then(|id| id.to_str().ok())
+                            .unwrap_or_default();
+
+                        tracing::info_span!(
+                            "request",
+                            method = %req.method(),
+                            uri = %req.uri(),
+                            request_id,
+                        )
+                    }),
+                )
+                .layer(PropagateRequestIdLayer::new(
+                    request_id::X_REQUEST_ID.clone(),
+                )),
+        )
 }
 
-pub async fn serve(opt: Opt, db: PgPool, cfg: Config, tls: RustlsConfig) -> anyhow::Result<()> {
+/// Serves the API until `shutdown` is triggered, then waits up to `--shutdown-timeout` for
+/// in-flight requests to finish.
+pub async fn serve(
+    opt: Opt,
+    db: Db,
+    cfg: Config,
+    keys: Keys,
+    tls: RustlsConfig,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
     // let sock_addr = SocketAddr::from((
     //     IpAddr::from_str(opt.addr.as_str()).unwrap_or(IpAddr::V6(Ipv6Addr::LOCALHOST)),
     //     opt.port,
@@ -78,9 +192,31 @@ pub async fn serve(opt: Opt, db: PgPool, cfg: Config, tls: RustlsConfig) -> anyh
 
     tracing::info!("listening on http://{}:{}", opt.addr, opt.port);
 
-    axum::serve(listener, app(opt, db, cfg).into_make_service())
-        .await
-        .context("failed to serve api")
+    shutdown.listen();
+
+    let timeout = Duration::from_secs(opt.shutdown_timeout);
+    let server = axum::serve(
+        listener,
+        // With the client's address, for the audit log
+        app(opt, db, cfg, keys, shutdown.clone())
+            .into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown.triggered());
+
+    let res = tokio::select! {
+        res = server.into_future() => res.context("failed to serve api"),
+        _ = async {
+            shutdown.triggered().await;
+            tokio::time::sleep(timeout).await;
+        } => {
+            tracing::warn!("In-flight requests didn't finish within {timeout:?}, dropping them");
+            Ok(())
+        }
+    };
+
+    // Stop any background work as well if the server went down on its own.
+    shutdown.trigger();
+    res
 
     // axum_server::bind_rustls(sock_addr, tls)
     //     .serve(app(opt, db, cfg).into_make_service())
@@ -0,0 +1,922 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{middleware, routing::delete, routing::get, routing::post, Extension, Json, Router};
+use chrono::Utc;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::{Error, Media, Result};
+use shared::organization::{
+    AddMember, CreateOrganization, CreateQuestion, Invitation, Member, Organization,
+    OrganizationKind, OrganizationSummary, TeacherSummary,
+};
+use shared::question::{Question, MAX_LEVEL};
+use shared::test::TestSettings;
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/organization",
+            get(get_organizations)
+                .post(create_organization)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/invitations",
+            get(get_invitations).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/invitations/:organization_id",
+            post(accept_invitation)
+                .delete(decline_invitation)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/members",
+            get(get_members)
+                .post(add_member)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/members/:teacher_id",
+            delete(remove_member).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/settings",
+            post(update_settings).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/summary",
+            get(get_summary).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/questions",
+            get(get_questions)
+                .post(create_question)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/questions/:question_id",
+            delete(remove_question).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+async fn find_organization(db: &Db, organization_id: Uuid) -> Result<Option<Organization>> {
+    let organization = with_pool!(db, pool => {
+        sqlx::query_as::<_, Organization>("SELECT * FROM organization WHERE id = $1")
+            .bind(organization_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    Ok(organization)
+}
+
+/// The organization, if `teacher` administers it: it's the one they're an admin of, or one of
+/// its schools.
+pub(crate) async fn administered(
+    db: &Db,
+    teacher: &Teacher,
+    organization_id: Uuid,
+) -> Result<Organization> {
+    let organization = find_organization(db, organization_id).await?;
+
+    let own = teacher
+        .organization_id
+        .filter(|_| teacher.organization_admin);
+    organization
+        .filter(|organization| {
+            own.is_some() && (Some(organization.id) == own || organization.parent_id == own)
+        })
+        .map(|organization| Organization {
+            admin: true,
+            ..organization
+        })
+        .ok_or_else(|| Error::UnprocessableEntity("This organization ID is not valid".to_string()))
+}
+
+/// Tests are only ever shared within an organization, so a teacher joining or leaving one stops
+/// sharing theirs and loses access to everyone else's.
+async fn clear_shares(db: &Db, teacher_id: Uuid) -> Result<()> {
+    with_pool!(db, pool => {
+        sqlx::query(
+            "DELETE FROM test_share WHERE teacher_id = $1
+            OR test_id IN (SELECT id FROM test WHERE teacher_id = $1)",
+        )
+        .bind(teacher_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(())
+}
+
+/// List the organization the logged in teacher belongs to, followed by its schools if they
+/// administer a district.
+#[utoipa::path(
+    get,
+    path = "/v1/organization",
+    tag = "organization",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The teacher's organizations, empty if they don't belong to one", body = [Organization]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_organizations(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+) -> Result<Json<Vec<Organization>>> {
+    let Some(organization_id) = teacher.organization_id else {
+        return Ok(Json(Vec::new()));
+    };
+
+    let mut organizations = with_pool!(db, pool => {
+        sqlx::query_as::<_, Organization>(
+            "SELECT * FROM organization WHERE id = $1 OR (parent_id = $1 AND $2)
+            ORDER BY name",
+        )
+        .bind(organization_id)
+        .bind(teacher.organization_admin)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    organizations.sort_by_key(|organization| organization.id != organization_id);
+    for organization in &mut organizations {
+        organization.admin = teacher.organization_admin;
+    }
+
+    Ok(Json(organizations))
+}
+
+/// Create an organization, which the logged in teacher joins as its admin, or a school of the
+/// district they administer.
+#[utoipa::path(
+    post,
+    path = "/v1/organization",
+    tag = "organization",
+    request_body = CreateOrganization,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The organization was created"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The teacher already belongs to an organization", body = ErrorResponse),
+        (status = 422, description = "The district doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_organization(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Json(req): Json<CreateOrganization>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    let CreateOrganization {
+        name,
+        kind,
+        parent_id,
+    } = req;
+    let id = Uuid::new_v4();
+
+    let Some(parent_id) = parent_id else {
+        if teacher.organization_id.is_some() {
+            return Err(Error::Conflict(
+                "You already belong to an organization".to_string(),
+            ));
+        }
+
+        with_pool!(db, pool => {
+            async {
+                let mut tx = pool.begin().await?;
+
+                sqlx::query("INSERT INTO organization (id, kind, name) VALUES ($1, $2, $3)")
+                    .bind(id)
+                    .bind(kind)
+                    .bind(name.trim())
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query(
+                    "UPDATE teacher SET organization_id = $1, organization_admin = $2
+                    WHERE id = $3",
+                )
+                .bind(id)
+                .bind(true)
+                .bind(teacher.id)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await
+            }
+            .await
+        })?;
+        clear_shares(&db, teacher.id).await?;
+
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    let district = administered(&db, &teacher, parent_id).await?;
+    if district.kind != OrganizationKind::District || kind != OrganizationKind::School {
+        return Err(Error::UnprocessableEntity(
+            "Only districts can have organizations of their own, and only schools".to_string(),
+        ));
+    }
+
+    // Schools start with their district's settings
+    let TestSettings {
+        autoplay,
+        speech_rate,
+        voice,
+    } = district.settings;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO organization (id, parent_id, kind, name, autoplay, speech_rate, voice)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(id)
+        .bind(district.id)
+        .bind(kind)
+        .bind(name.trim())
+        .bind(autoplay)
+        .bind(speech_rate)
+        .bind(voice)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// List the teachers of an organization the logged in teacher administers, and the ones invited
+/// to it who haven't accepted yet.
+#[utoipa::path(
+    get,
+    path = "/v1/organization/{organization_id}/members",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The organization's teachers by name, then the ones invited to it", body = [Member]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_members(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<Member>>> {
+    administered(&db, &teacher, organization_id).await?;
+
+    let mut members = with_pool!(db, pool => {
+        sqlx::query_as::<_, Member>(
+            "SELECT id, username, email, organization_admin AS admin FROM teacher
+            WHERE organization_id = $1
+            ORDER BY username",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+    let invited = with_pool!(db, pool => {
+        sqlx::query_as::<_, Member>(
+            "SELECT teacher.id, teacher.username, teacher.email, organization_invitation.admin
+            FROM organization_invitation
+            JOIN teacher ON teacher.id = organization_invitation.teacher_id
+            WHERE organization_invitation.organization_id = $1
+            ORDER BY teacher.username",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    members.extend(invited.into_iter().map(|member| Member {
+        invited: true,
+        ..member
+    }));
+
+    Ok(Json(members))
+}
+
+/// Invite a teacher who doesn't belong to an organization yet to one the logged in teacher
+/// administers, or change whether one of its teachers administers it. Invited teachers only join
+/// once they accept.
+#[utoipa::path(
+    post,
+    path = "/v1/organization/{organization_id}/members",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    request_body = AddMember,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The teacher was invited, or their role was changed"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The teacher belongs to another organization", body = ErrorResponse),
+        (status = 422, description = "The organization or teacher doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn add_member(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+    Json(req): Json<AddMember>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    administered(&db, &teacher, organization_id).await?;
+
+    let member = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, Option<Uuid>)>(
+            "SELECT id, organization_id FROM teacher WHERE email = $1",
+        )
+        .bind(req.email.trim())
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    let (member_id, current) = member.ok_or_else(|| {
+        Error::UnprocessableEntity("No teacher is registered with this email".to_string())
+    })?;
+
+    match current {
+        Some(current) if current != organization_id => {
+            return Err(Error::Conflict(
+                "This teacher belongs to another organization".to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    if current.is_some() {
+        with_pool!(db, pool => {
+            sqlx::query("UPDATE teacher SET organization_admin = $1 WHERE id = $2")
+                .bind(req.admin)
+                .bind(member_id)
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })?;
+
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    // Inviting them again only changes whether they'll administer it
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO organization_invitation (organization_id, teacher_id, admin, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id, teacher_id) DO UPDATE SET admin = excluded.admin",
+        )
+        .bind(organization_id)
+        .bind(member_id)
+        .bind(req.admin)
+        .bind(Utc::now().timestamp())
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Remove a teacher from an organization the logged in teacher administers, or take back their
+/// invitation. Their tests stay theirs.
+#[utoipa::path(
+    delete,
+    path = "/v1/organization/{organization_id}/members/{teacher_id}",
+    tag = "organization",
+    params(
+        ("organization_id" = Uuid, Path, description = "The organization's id"),
+        ("teacher_id" = Uuid, Path, description = "The teacher's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The teacher no longer belongs to the organization"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist, or the teacher is the logged in one", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn remove_member(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((organization_id, teacher_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    administered(&db, &teacher, organization_id).await?;
+
+    if teacher_id == teacher.id {
+        return Err(Error::UnprocessableEntity(
+            "You can't remove yourself from the organization".to_string(),
+        ));
+    }
+
+    let removed = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE teacher SET organization_id = NULL, organization_admin = $1
+            WHERE id = $2 AND organization_id = $3",
+        )
+        .bind(false)
+        .bind(teacher_id)
+        .bind(organization_id)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected() > 0)
+    })?;
+
+    if removed {
+        clear_shares(&db, teacher_id).await?;
+    } else {
+        with_pool!(db, pool => {
+            sqlx::query(
+                "DELETE FROM organization_invitation WHERE organization_id = $1 AND teacher_id = $2",
+            )
+            .bind(organization_id)
+            .bind(teacher_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })?;
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// List the organizations the logged in teacher was invited to.
+#[utoipa::path(
+    get,
+    path = "/v1/organization/invitations",
+    tag = "organization",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The teacher's invitations, by organization name", body = [Invitation]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_invitations(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+) -> Result<Json<Vec<Invitation>>> {
+    let invitations = with_pool!(db, pool => {
+        sqlx::query_as::<_, Invitation>(
+            "SELECT organization.id AS organization_id, organization.name, organization.kind,
+                organization_invitation.admin
+            FROM organization_invitation
+            JOIN organization ON organization.id = organization_invitation.organization_id
+            WHERE organization_invitation.teacher_id = $1
+            ORDER BY organization.name",
+        )
+        .bind(teacher.id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    Ok(Json(invitations))
+}
+
+/// Accept an invitation, joining the organization. The logged in teacher's other invitations are
+/// declined.
+#[utoipa::path(
+    post,
+    path = "/v1/organization/invitations/{organization_id}",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The teacher belongs to the organization"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The teacher already belongs to an organization", body = ErrorResponse),
+        (status = 422, description = "The teacher wasn't invited to the organization", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn accept_invitation(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    if teacher.organization_id.is_some() {
+        return Err(Error::Conflict(
+            "You already belong to an organization".to_string(),
+        ));
+    }
+
+    let joined = with_pool!(db, pool => {
+        async {
+            let mut tx = pool.begin().await?;
+
+            let admin = sqlx::query_scalar::<_, bool>(
+                "DELETE FROM organization_invitation WHERE organization_id = $1 AND teacher_id = $2
+                RETURNING admin",
+            )
+            .bind(organization_id)
+            .bind(teacher.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some(admin) = admin else {
+                return Ok(false);
+            };
+
+            // Unless they joined another one in the meantime, which took back this invitation
+            let updated = sqlx::query(
+                "UPDATE teacher SET organization_id = $1, organization_admin = $2
+                WHERE id = $3 AND organization_id IS NULL",
+            )
+            .bind(organization_id)
+            .bind(admin)
+            .bind(teacher.id)
+            .execute(&mut *tx)
+            .await?;
+            if updated.rows_affected() == 0 {
+                return Ok(false);
+            }
+
+            sqlx::query("DELETE FROM organization_invitation WHERE teacher_id = $1")
+                .bind(teacher.id)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await.map(|_| true)
+        }
+        .await
+    })?;
+
+    if !joined {
+        return Err(Error::UnprocessableEntity(
+            "You weren't invited to this organization".to_string(),
+        ));
+    }
+    clear_shares(&db, teacher.id).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Decline an invitation.
+#[utoipa::path(
+    delete,
+    path = "/v1/organization/invitations/{organization_id}",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The invitation was declined"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn decline_invitation(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    with_pool!(db, pool => {
+        sqlx::query(
+            "DELETE FROM organization_invitation WHERE organization_id = $1 AND teacher_id = $2",
+        )
+        .bind(organization_id)
+        .bind(teacher.id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Change what the tests the organization's teachers create start with. Tests that already exist
+/// keep their settings.
+#[utoipa::path(
+    post,
+    path = "/v1/organization/{organization_id}/settings",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    request_body = TestSettings,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The settings were saved"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn update_settings(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+    Json(req): Json<TestSettings>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    administered(&db, &teacher, organization_id).await?;
+
+    let TestSettings {
+        autoplay,
+        speech_rate,
+        voice,
+    } = req;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE organization SET autoplay = $1, speech_rate = $2, voice = $3 WHERE id = $4",
+        )
+        .bind(autoplay)
+        .bind(speech_rate)
+        .bind(voice.filter(|voice| !voice.is_empty()))
+        .bind(organization_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// The settings the tests `teacher` creates start with, their organization's if they belong to one.
+pub(crate) async fn test_settings(db: &Db, teacher: &Teacher) -> Result<TestSettings> {
+    let Some(organization_id) = teacher.organization_id else {
+        return Ok(TestSettings::default());
+    };
+
+    let organization = find_organization(db, organization_id).await?;
+
+    Ok(organization
+        .map(|organization| organization.settings)
+        .unwrap_or_default())
+}
+
+/// Get how the classes of every teacher of an organization the logged in teacher administers did,
+/// including the teachers of its schools for districts. Only counts are given, never students.
+#[utoipa::path(
+    get,
+    path = "/v1/organization/{organization_id}/summary",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "How the organization's classes did", body = OrganizationSummary),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_summary(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<OrganizationSummary>> {
+    administered(&db, &teacher, organization_id).await?;
+
+    let teachers = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, String, Uuid)>(
+            "SELECT id, username, organization_id FROM teacher
+            WHERE organization_id = $1
+                OR organization_id IN (SELECT id FROM organization WHERE parent_id = $1)
+            ORDER BY username",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let tests = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, i64)>(
+            "SELECT test.teacher_id, COUNT(*) FROM test
+            JOIN teacher ON teacher.id = test.teacher_id
+            WHERE teacher.organization_id = $1
+                OR teacher.organization_id IN (SELECT id FROM organization WHERE parent_id = $1)
+            GROUP BY test.teacher_id",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+    let tests: HashMap<Uuid, i64> = tests.into_iter().collect();
+
+    let results = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, i32, bool)>(
+            "SELECT test.teacher_id, result.level, result.finished FROM result
+            JOIN test ON test.id = result.test_id
+            JOIN teacher ON teacher.id = test.teacher_id
+            WHERE teacher.organization_id = $1
+                OR teacher.organization_id IN (SELECT id FROM organization WHERE parent_id = $1)",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let mut summary = OrganizationSummary {
+        organization_id,
+        teachers: Vec::with_capacity(teachers.len()),
+        students: 0,
+        finished: 0,
+        levels: vec![0; MAX_LEVEL as usize],
+    };
+
+    for (teacher_id, username, organization_id) in teachers {
+        let mut teacher = TeacherSummary {
+            teacher_id,
+            username,
+            organization_id,
+            tests: tests.get(&teacher_id).copied().unwrap_or_default(),
+            students: 0,
+            finished: 0,
+            levels: vec![0; MAX_LEVEL as usize],
+        };
+
+        for (_, level, finished) in results.iter().filter(|(id, ..)| *id == teacher_id) {
+            teacher.students += 1;
+            teacher.finished += i64::from(*finished);
+            if let Some(count) = usize::try_from(level - 1)
+                .ok()
+                .and_then(|level| teacher.levels.get_mut(level))
+            {
+                *count += 1;
+            }
+        }
+
+        summary.students += teacher.students;
+        summary.finished += teacher.finished;
+        for (total, count) in summary.levels.iter_mut().zip(&teacher.levels) {
+            *total += count;
+        }
+        summary.teachers.push(teacher);
+    }
+
+    Ok(Json(summary))
+}
+
+/// List the questions an organization the logged in teacher belongs to or administers added of its
+/// own.
+#[utoipa::path(
+    get,
+    path = "/v1/organization/{organization_id}/questions",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The organization's questions, ordered by level", body = [Question]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_questions(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<Question>>> {
+    if teacher.organization_id != Some(organization_id) {
+        administered(&db, &teacher, organization_id).await?;
+    }
+
+    let questions = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "SELECT * FROM question WHERE organization_id = $1 ORDER BY level",
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    Ok(Json(questions))
+}
+
+/// Add a question to an organization the logged in teacher administers. Its teachers' tests ask
+/// the organization's questions of a level before the shared bank's, and a school's before its
+/// district's.
+#[utoipa::path(
+    post,
+    path = "/v1/organization/{organization_id}/questions",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    request_body = CreateQuestion,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The question was added"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_question(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+    Json(req): Json<CreateQuestion>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    administered(&db, &teacher, organization_id).await?;
+
+    let CreateQuestion {
+        level,
+        question,
+        word,
+        phonemes,
+    } = req;
+    let word = word
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty());
+    let phonemes = phonemes
+        .map(|phonemes| phonemes.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|phonemes| !phonemes.is_empty() && word.is_some());
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO question (id, level, question, word, phonemes, organization_id)
+            VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(level)
+        .bind(question.trim())
+        .bind(word)
+        .bind(phonemes)
+        .bind(organization_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Remove one of the questions of an organization the logged in teacher administers, unless a
+/// student has already answered it.
+#[utoipa::path(
+    delete,
+    path = "/v1/organization/{organization_id}/questions/{question_id}",
+    tag = "organization",
+    params(
+        ("organization_id" = Uuid, Path, description = "The organization's id"),
+        ("question_id" = Uuid, Path, description = "The question's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The question was removed"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The question has already been answered", body = ErrorResponse),
+        (status = 422, description = "The organization or question doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn remove_question(
+    Extension(db): Extension<Db>,
+    Extension(media): Extension<Media>,
+    Extension(teacher): Extension<Teacher>,
+    Path((organization_id, question_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    administered(&db, &teacher, organization_id).await?;
+
+    let removed = with_pool!(db, pool => {
+        async {
+            let mut tx = pool.begin().await?;
+
+            sqlx::query(
+                "DELETE FROM question_translation WHERE question_id IN (
+                    SELECT id FROM question WHERE id = $1 AND organization_id = $2
+                )",
+            )
+            .bind(question_id)
+            .bind(organization_id)
+            .execute(&mut *tx)
+            .await?;
+
+            let removed = sqlx::query_as::<_, Question>(
+                "DELETE FROM question WHERE id = $1 AND organization_id = $2 RETURNING *",
+            )
+            .bind(question_id)
+            .bind(organization_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            // Otherwise it was not the organization's question, and the transaction is rolled back
+            if removed.is_some() {
+                tx.commit().await?;
+            }
+            Ok(removed)
+        }
+        .await
+    })
+    .map_err(|e| match e {
+        sqlx::Error::Database(dbe) if dbe.is_foreign_key_violation() => Error::Conflict(
+            "This question has already been answered, so it can't be removed".to_string(),
+        ),
+        _ => e.into(),
+    })?;
+
+    // Not one of the organization's questions, like those of the shared bank
+    let Some(removed) = removed else {
+        return Err(Error::UnprocessableEntity(
+            "This question ID is not valid".to_string(),
+        ));
+    };
+
+    if let Some(path) = removed.audio_path {
+        if let Err(e) = media.remove(&path).await {
+            tracing::warn!("Failed to remove a removed question's recording: {e:?}");
+        }
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
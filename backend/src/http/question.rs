@@ -1,34 +1,338 @@
-use crate::http::Result;
-use axum::extract::Path;
-use axum::{routing::get, Extension, Json, Router};
-use serde::Serialize;
-use sqlx::PgPool;
+use crate::http::auth::teacher_auth;
+use crate::http::organization::administered;
+use crate::http::teacher::Teacher;
+use crate::http::{Admins, Error, Media, Result};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path};
+use axum::http::{header::CONTENT_TYPE, HeaderMap};
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use shared::question::{Question, QuestionPlan, QuestionTranslation, ATTEMPTS_PER_LEVEL};
+use shared::test::Test;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+
+/// Recordings of a question being read out are short, this leaves room for a minute of WAV.
+const MAX_RECORDING_SIZE: usize = 10 * 1024 * 1024;
 
 pub fn router() -> Router {
-    Router::new().route("/v1/question/:question_level", get(get_questions))
+    Router::new()
+        .route("/v1/question", get(get_all_questions))
+        .route("/v1/question/:question_level", get(get_questions))
+        .route(
+            "/v1/question/:question_id/audio",
+            post(upload_audio)
+                .delete(remove_audio)
+                .route_layer(middleware::from_fn(teacher_auth))
+                .layer(DefaultBodyLimit::max(MAX_RECORDING_SIZE)),
+        )
+        .route("/v1/test/:test_id/questions", get(get_plan))
 }
 
-#[derive(Serialize, sqlx::FromRow)]
-pub struct Question {
-    id: uuid::Uuid,
-    level: i32,
-    question: String,
-    image_path: Option<String>,
+/// Get every question in the shared question bank.
+#[utoipa::path(
+    get,
+    path = "/v1/question",
+    tag = "question",
+    responses(
+        (status = 200, description = "Every question, ordered by level", body = [Question]),
+        (status = 500, description = "The questions couldn't be fetched", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_all_questions(Extension(db): Extension<Db>) -> Result<Json<Vec<Question>>> {
+    let questions = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "SELECT * FROM question WHERE organization_id IS NULL ORDER BY level",
+        )
+        .fetch_all(pool)
+        .await
+    })?;
+
+    Ok(Json(questions))
 }
 
-async fn get_questions(
-    Extension(db): Extension<PgPool>,
+/// Get the shared question bank's questions asked at a given level.
+#[utoipa::path(
+    get,
+    path = "/v1/question/{question_level}",
+    tag = "question",
+    params(("question_level" = i32, Path, description = "The level to fetch questions for")),
+    responses(
+        (status = 200, description = "Up to three questions for the level", body = [Question]),
+        (status = 500, description = "The questions couldn't be fetched", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_questions(
+    Extension(db): Extension<Db>,
     Path(question_level): Path<i32>,
 ) -> Result<Json<Vec<Question>>> {
     // Make some check here preventing question level queries above a certain number
 
-    let questions = sqlx::query_as!(
-        Question,
-        "SELECT * FROM question WHERE level = $1 LIMIT 3",
-        question_level
-    )
-    .fetch_all(&db)
-    .await?;
+    let questions = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "SELECT * FROM question WHERE level = $1 AND organization_id IS NULL LIMIT 3",
+        )
+        .bind(question_level)
+        .fetch_all(pool)
+        .await
+    })?;
 
     Ok(Json(questions))
 }
+
+/// Get every question that can be asked during a test, so it can be preloaded in one request.
+///
+/// The questions of the school and district the test's teacher belongs to are asked before the
+/// shared bank's.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/questions",
+    tag = "question",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    responses(
+        (status = 200, description = "The test's questions", body = QuestionPlan),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_plan(
+    Extension(db): Extension<Db>,
+    Path(test_id): Path<Uuid>,
+) -> Result<Json<QuestionPlan>> {
+    let test = with_pool!(db, pool => {
+        sqlx::query_as::<_, Test>("SELECT * FROM test WHERE id = $1")
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    let Some(test) = test else {
+        return Err(Error::UnprocessableEntity(
+            "This test ID is not valid".to_string(),
+        ));
+    };
+
+    let (organization_id, district_id) = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Option<Uuid>, Option<Uuid>)>(
+            "SELECT teacher.organization_id, organization.parent_id FROM teacher
+            LEFT JOIN organization ON organization.id = teacher.organization_id
+            WHERE teacher.id = $1",
+        )
+        .bind(test.teacher_id)
+        .fetch_optional(pool)
+        .await
+    })?
+    .unwrap_or_default();
+
+    let mut questions = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "SELECT * FROM question
+            WHERE organization_id IS NULL OR organization_id = $1 OR organization_id = $2
+            ORDER BY level",
+        )
+        .bind(organization_id)
+        .bind(district_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let rank = |question: &Question| match question.organization_id {
+        Some(id) if Some(id) == organization_id => 0,
+        Some(_) => 1,
+        None => 2,
+    };
+    questions.sort_by_key(|question| (question.level, rank(question)));
+
+    // Keep one question per attempt at each level
+    let mut per_level = HashMap::new();
+    questions.retain(|question| {
+        let count = per_level.entry(question.level).or_insert(0);
+        *count += 1;
+        *count <= ATTEMPTS_PER_LEVEL
+    });
+
+    // Only for the questions that are asked, so no other organization's questions get out. Every
+    // language is sent, since students may take the test in another one than it's given in.
+    let placeholders: Vec<String> = (1..=questions.len()).map(|i| format!("${i}")).collect();
+    let translations = match questions.is_empty() {
+        true => Vec::new(),
+        false => with_pool!(db, pool => {
+            let sql = format!(
+                "SELECT * FROM question_translation WHERE question_id IN ({})",
+                placeholders.join(", ")
+            );
+            let mut query = sqlx::query_as::<_, QuestionTranslation>(&sql);
+            for question in &questions {
+                query = query.bind(question.id);
+            }
+            query.fetch_all(pool).await
+        })?,
+    };
+
+    Ok(Json(QuestionPlan {
+        language: test.language,
+        questions,
+        translations,
+        settings: test.settings,
+        needs_pin: test.pin.is_some(),
+    }))
+}
+
+/// Upload a recording of a question being read out, replacing any previous one.
+///
+/// The body is the audio file itself, described by its `Content-Type`.
+#[utoipa::path(
+    post,
+    path = "/v1/question/{question_id}/audio",
+    tag = "question",
+    params(("question_id" = Uuid, Path, description = "The question's id")),
+    request_body(content = Vec<u8>, content_type = "audio/mpeg"),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The recording was stored", body = Question),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher may not record this question", body = ErrorResponse),
+        (status = 413, description = "The recording is larger than 10MiB"),
+        (status = 422, description = "The question doesn't exist or the file isn't audio", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn upload_audio(
+    Extension(db): Extension<Db>,
+    Extension(media): Extension<Media>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+    Path(question_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Question>> {
+    let extension = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(audio_extension)
+        .filter(|_| !body.is_empty())
+        .ok_or_else(|| {
+            Error::UnprocessableEntity(
+                "The recording must be an MP3, Ogg, WAV, WebM or MP4 audio file".to_string(),
+            )
+        })?;
+
+    let previous = find_question(&db, &admins, &teacher, question_id).await?;
+
+    // A new name every time, so browsers holding on to the old recording don't keep playing it
+    let name = format!(
+        "audio/{question_id}-{}.{extension}",
+        Uuid::new_v4().simple()
+    );
+    let audio_path = media.save(&name, &body).await?;
+
+    let question = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "UPDATE question SET audio_path = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(&audio_path)
+        .bind(question_id)
+        .fetch_one(pool)
+        .await
+    })?;
+
+    if let Some(path) = previous.audio_path {
+        if let Err(e) = media.remove(&path).await {
+            tracing::warn!("Failed to remove a replaced recording: {e:?}");
+        }
+    }
+
+    Ok(Json(question))
+}
+
+/// Remove a question's recording, so it's read out by speech synthesis again.
+#[utoipa::path(
+    delete,
+    path = "/v1/question/{question_id}/audio",
+    tag = "question",
+    params(("question_id" = Uuid, Path, description = "The question's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The recording was removed", body = Question),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "The teacher may not record this question", body = ErrorResponse),
+        (status = 422, description = "The question doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn remove_audio(
+    Extension(db): Extension<Db>,
+    Extension(media): Extension<Media>,
+    Extension(admins): Extension<Admins>,
+    Extension(teacher): Extension<Teacher>,
+    Path(question_id): Path<Uuid>,
+) -> Result<Json<Question>> {
+    let previous = find_question(&db, &admins, &teacher, question_id).await?;
+
+    let question = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>(
+            "UPDATE question SET audio_path = NULL WHERE id = $1 RETURNING *",
+        )
+        .bind(question_id)
+        .fetch_one(pool)
+        .await
+    })?;
+
+    if let Some(path) = previous.audio_path {
+        media.remove(&path).await?;
+    }
+
+    Ok(Json(question))
+}
+
+/// The question, if `teacher` may record it: the shared bank's questions are the server admins'
+/// to record, an organization's questions are its admins'.
+async fn find_question(
+    db: &Db,
+    admins: &Admins,
+    teacher: &Teacher,
+    question_id: Uuid,
+) -> Result<Question> {
+    let question = with_pool!(db, pool => {
+        sqlx::query_as::<_, Question>("SELECT * FROM question WHERE id = $1")
+            .bind(question_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    let Some(question) = question else {
+        return Err(Error::UnprocessableEntity(
+            "This question ID is not valid".to_string(),
+        ));
+    };
+
+    match question.organization_id {
+        Some(organization_id) => {
+            administered(db, teacher, organization_id)
+                .await
+                .map_err(|_| {
+                    Error::Forbidden(
+                        "Only the organization's admins can record its questions".to_string(),
+                    )
+                })?;
+        }
+        None => admins.check(
+            teacher,
+            "Only the server's admins can record the shared bank's questions",
+        )?,
+    }
+
+    Ok(question)
+}
+
+/// The file extension recordings of this MIME type are stored with, if they're a supported format.
+fn audio_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim();
+
+    match mime.to_ascii_lowercase().as_str() {
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/ogg" => Some("ogg"),
+        "audio/wav" | "audio/wave" | "audio/x-wav" => Some("wav"),
+        "audio/webm" => Some("webm"),
+        "audio/mp4" | "audio/x-m4a" => Some("m4a"),
+        _ => None,
+    }
+}
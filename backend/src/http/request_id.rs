@@ -0,0 +1,32 @@
+use axum::{
+    body::Body,
+    http::{HeaderName, Request},
+    middleware::Next,
+    response::IntoResponse,
+};
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Makes the id given to this request by `SetRequestIdLayer` available to `current`.
+pub async fn scope(req: Request<Body>, next: Next) -> impl IntoResponse {
+    let request_id = req
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|id| id.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+
+    REQUEST_ID.scope(request_id, next.run(req)).await
+}
+
+/// The id of the request currently being handled, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID
+        .try_with(|id| id.clone())
+        .ok()
+        .filter(|id| !id.is_empty())
+}
@@ -0,0 +1,228 @@
+use axum::extract::Path;
+use axum::{middleware, routing::get, Extension, Json, Router};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::organization::administered;
+use crate::http::teacher::Teacher;
+use crate::http::test::accessible_test;
+use crate::http::Result;
+use crate::retention::{self, Tests};
+use shared::retention::{Purge, RetentionPolicy, RetentionReport};
+use shared::share::Permission;
+use validator::Validate;
+
+/// How many of the latest purges a report lists.
+const PURGES: i64 = 50;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/retention",
+            get(get_test_retention)
+                .post(set_test_retention)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/organization/:organization_id/retention",
+            get(get_organization_retention)
+                .post(set_organization_retention)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+async fn purges(db: &Db, tests: Tests) -> Result<Vec<Purge>> {
+    const SELECT: &str = "SELECT retention_purge.test_id, retention_purge.purged_at,
+            retention_purge.drawings, retention_purge.names, retention_purge.roster_entries,
+            retention_purge.transcriptions
+        FROM retention_purge";
+
+    let purges = match tests {
+        Tests::All => with_pool!(db, pool => {
+            sqlx::query_as::<_, Purge>(&format!("{SELECT} ORDER BY purged_at DESC LIMIT $1"))
+                .bind(PURGES)
+                .fetch_all(pool)
+                .await
+        })?,
+        Tests::One(test_id) => with_pool!(db, pool => {
+            sqlx::query_as::<_, Purge>(&format!(
+                "{SELECT} WHERE test_id = $1 ORDER BY purged_at DESC LIMIT $2"
+            ))
+            .bind(test_id)
+            .bind(PURGES)
+            .fetch_all(pool)
+            .await
+        })?,
+        Tests::Organization(organization_id) => with_pool!(db, pool => {
+            sqlx::query_as::<_, Purge>(&format!(
+                "{SELECT}
+                JOIN test ON test.id = retention_purge.test_id
+                JOIN teacher ON teacher.id = test.teacher_id
+                JOIN organization ON organization.id = teacher.organization_id
+                WHERE organization.id = $1 OR organization.parent_id = $1
+                ORDER BY retention_purge.purged_at DESC LIMIT $2"
+            ))
+            .bind(organization_id)
+            .bind(PURGES)
+            .fetch_all(pool)
+            .await
+        })?,
+    };
+
+    Ok(purges)
+}
+
+/// Get the retention policy of one of the teacher's tests, what enforcing it would remove right
+/// now, and what it removed before.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/retention",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The test's retention policy and what it would remove", body = RetentionReport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_test_retention(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+) -> Result<Json<RetentionReport>> {
+    accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let tests = retention::report(&db, Tests::One(test_id)).await?;
+    let policy = tests.first().map(|test| test.policy).unwrap_or_default();
+
+    Ok(Json(RetentionReport {
+        policy,
+        tests,
+        purges: purges(&db, Tests::One(test_id)).await?,
+    }))
+}
+
+/// Set how long the drawings and names of the students who take one of the teacher's tests are
+/// kept. The strictest of the test's and its organization's policies is the one enforced.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/retention",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = RetentionPolicy,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The test's new retention policy and what it would remove", body = RetentionReport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_test_retention(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<RetentionPolicy>,
+) -> Result<Json<RetentionReport>> {
+    req.validate()?;
+
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE test SET drawings_retention_days = $1, names_retention_days = $2 WHERE id = $3",
+        )
+        .bind(req.drawings_days)
+        .bind(req.names_days)
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    get_test_retention(Extension(db), Extension(teacher), Path(test_id)).await
+}
+
+/// Get the retention policy of an organization the logged in teacher administers, what enforcing
+/// it would remove from each of its teachers' tests right now, and what it removed before.
+#[utoipa::path(
+    get,
+    path = "/v1/organization/{organization_id}/retention",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The organization's retention policy and what it would remove", body = RetentionReport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist or the teacher doesn't administer it", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_organization_retention(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<RetentionReport>> {
+    administered(&db, &teacher, organization_id).await?;
+
+    let (drawings_days, names_days) = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Option<i32>, Option<i32>)>(
+            "SELECT drawings_retention_days, names_retention_days FROM organization WHERE id = $1",
+        )
+        .bind(organization_id)
+        .fetch_one(pool)
+        .await
+    })?;
+
+    let tests = Tests::Organization(organization_id);
+
+    Ok(Json(RetentionReport {
+        policy: RetentionPolicy {
+            drawings_days,
+            names_days,
+        },
+        tests: retention::report(&db, tests).await?,
+        purges: purges(&db, tests).await?,
+    }))
+}
+
+/// Set how long the drawings and names of the students who take the tests of an organization's
+/// teachers, and of its schools' for districts, are kept.
+#[utoipa::path(
+    post,
+    path = "/v1/organization/{organization_id}/retention",
+    tag = "organization",
+    params(("organization_id" = Uuid, Path, description = "The organization's id")),
+    request_body = RetentionPolicy,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The organization's new retention policy and what it would remove", body = RetentionReport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The organization doesn't exist, the teacher doesn't administer it or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_organization_retention(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(organization_id): Path<Uuid>,
+    Json(req): Json<RetentionPolicy>,
+) -> Result<Json<RetentionReport>> {
+    req.validate()?;
+
+    administered(&db, &teacher, organization_id).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE organization SET drawings_retention_days = $1, names_retention_days = $2
+            WHERE id = $3",
+        )
+        .bind(req.drawings_days)
+        .bind(req.names_days)
+        .bind(organization_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    get_organization_retention(Extension(db), Extension(teacher), Path(organization_id)).await
+}
@@ -4,12 +4,14 @@ use axum::response::IntoResponse;
 use axum::{routing::post, Extension, Json, Router};
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use rand::Rng;
-use sqlx::PgPool;
 use std::time::Duration;
+use uuid::Uuid;
 
-use crate::http::{Error, Result};
+use crate::db::{with_pool, Db};
+use crate::http::{telemetry, Error, Result};
 use crate::util::Config;
 use serde::{Deserialize, Serialize};
+use shared::teacher::{LoginTeacher, RegisterTeacher};
 use validator::Validate;
 
 use super::auth::teacher_auth;
@@ -24,48 +26,32 @@ pub fn router() -> Router {
         )
 }
 
-#[derive(Deserialize, Validate)]
-pub struct RegisterTeacher {
-    #[validate(
-        length(min = 1, message = "An email is required"),
-        email(message = "The email you entered is invalid")
-    )]
-    email: String,
-    #[validate(length(min = 3, message = "Your username must be atleast 3 characters long"))]
-    username: String,
-    #[validate(length(
-        min = 8,
-        max = 40,
-        message = "Your password must be between 8 and 40 characters long"
-    ))]
-    password: String,
-}
-
-#[derive(Deserialize, Validate)]
-pub struct LoginTeacher {
-    #[validate(
-        length(min = 1, message = "An email is required"),
-        email(message = "The email you entered is invalid")
-    )]
-    email: String,
-    #[validate(length(
-        min = 8,
-        max = 40,
-        message = "Your password must be between 8 and 40 characters long"
-    ))]
-    password: String,
-}
-
 #[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
 pub struct Teacher {
     pub id: uuid::Uuid,
     pub username: String,
     pub email: String,
     pub password: String,
+    /// The school or district the teacher belongs to, if any.
+    pub organization_id: Option<Uuid>,
+    /// Whether they administer it, and its schools if it's a district.
+    pub organization_admin: bool,
 }
 
-async fn register_teacher(
-    Extension(db): Extension<PgPool>,
+/// Create a new teacher account.
+#[utoipa::path(
+    post,
+    path = "/v1/teacher/register",
+    tag = "teacher",
+    request_body = RegisterTeacher,
+    responses(
+        (status = 202, description = "The account was created"),
+        (status = 409, description = "The account already exists", body = ErrorResponse),
+        (status = 422, description = "The request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn register_teacher(
+    Extension(db): Extension<Db>,
     Json(req): Json<RegisterTeacher>,
 ) -> Result<StatusCode> {
     req.validate()?;
@@ -78,17 +64,21 @@ async fn register_teacher(
 
     let password_hash = crate::util::hash(password).await?;
 
-    sqlx::query!(
-        "INSERT INTO teacher (username, email, password) VALUES ($1, $2, $3)",
-        username,
-        email,
-        password_hash
-    )
-    .execute(&db)
-    .await
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO teacher (id, username, email, password) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(username)
+        .bind(email)
+        .bind(password_hash)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })
     .map_err(|e| match e {
-        sqlx::Error::Database(dbe) if dbe.constraint() == Some("teacher_username_key") => {
-            Error::Conflict("This username is already taken.".to_string())
+        sqlx::Error::Database(dbe) if dbe.is_unique_violation() => {
+            Error::Conflict("This email is already registered.".to_string())
         }
         _ => e.into(),
     })?;
@@ -96,8 +86,21 @@ async fn register_teacher(
     Ok(StatusCode::ACCEPTED)
 }
 
-async fn login_teacher(
-    Extension(db): Extension<PgPool>,
+/// Log in as a teacher, setting the `TEACHER_TOKEN` cookie.
+#[utoipa::path(
+    post,
+    path = "/v1/teacher/login",
+    tag = "teacher",
+    request_body = LoginTeacher,
+    responses(
+        (status = 202, description = "Logged in", headers(
+            ("set-cookie" = String, description = "The `TEACHER_TOKEN` session cookie")
+        )),
+        (status = 422, description = "Invalid credentials or request body", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn login_teacher(
+    Extension(db): Extension<Db>,
     Extension(cfg): Extension<Config>,
     Json(req): Json<LoginTeacher>,
 ) -> Result<impl IntoResponse> {
@@ -105,9 +108,12 @@ async fn login_teacher(
 
     let LoginTeacher { email, password } = req;
 
-    let teacher = sqlx::query_as!(Teacher, "SELECT * FROM teacher WHERE email = $1", email)
-        .fetch_optional(&db)
-        .await?;
+    let teacher = with_pool!(db, pool => {
+        sqlx::query_as::<_, Teacher>("SELECT * FROM teacher WHERE email = $1")
+            .bind(email)
+            .fetch_optional(pool)
+            .await
+    })?;
 
     if let Some(teacher) = teacher {
         let verified = crate::util::verify(password, teacher.password).await?;
@@ -122,6 +128,8 @@ async fn login_teacher(
         }
     }
 
+    metrics::counter!(telemetry::LOGIN_FAILURES).increment(1);
+
     // sleep to prevent timing attacks
     let sleep_duration =
         rand::thread_rng().gen_range(Duration::from_millis(100)..=Duration::from_millis(500));
@@ -132,7 +140,18 @@ async fn login_teacher(
     ))
 }
 
-async fn logout_teacher() -> Result<impl IntoResponse> {
+/// Log out, clearing the `TEACHER_TOKEN` cookie.
+#[utoipa::path(
+    post,
+    path = "/v1/teacher/logout",
+    tag = "teacher",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "Logged out"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn logout_teacher() -> Result<impl IntoResponse> {
     let cookie = Cookie::build(("TEACHER_TOKEN", ""))
         .path("/")
         .max_age(time::Duration::hours(-1))
@@ -0,0 +1,108 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::{
+    body::Body, extract::MatchedPath, http::Request, middleware::Next, response::IntoResponse,
+    routing::get, Extension, Router,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::db::Db;
+
+pub const HTTP_REQUESTS: &str = "fnk_http_requests_total";
+pub const HTTP_REQUEST_DURATION: &str = "fnk_http_request_duration_seconds";
+pub const DB_POOL_CONNECTIONS: &str = "fnk_db_pool_connections";
+pub const DB_POOL_IDLE_CONNECTIONS: &str = "fnk_db_pool_idle_connections";
+pub const DB_POOL_MAX_CONNECTIONS: &str = "fnk_db_pool_max_connections";
+pub const LOGIN_FAILURES: &str = "fnk_login_failures_total";
+pub const SESSIONS_STARTED: &str = "fnk_sessions_started_total";
+pub const SESSIONS_COMPLETED: &str = "fnk_sessions_completed_total";
+pub const UPLOADS_STORED: &str = "fnk_uploads_stored_total";
+
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Installs the global Prometheus recorder the first time it's called.
+fn recorder() -> PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+    HANDLE
+        .get_or_init(|| {
+            let handle = PrometheusBuilder::new()
+                .set_buckets_for_metric(
+                    Matcher::Full(HTTP_REQUEST_DURATION.to_string()),
+                    LATENCY_BUCKETS,
+                )
+                .expect("The latency buckets must not be empty")
+                .install_recorder()
+                .expect("Failed to install the metrics recorder");
+
+            describe();
+            handle
+        })
+        .clone()
+}
+
+fn describe() {
+    metrics::describe_counter!(HTTP_REQUESTS, "HTTP requests handled, by route and status");
+    metrics::describe_histogram!(
+        HTTP_REQUEST_DURATION,
+        metrics::Unit::Seconds,
+        "Time taken to handle HTTP requests, by route and status"
+    );
+    metrics::describe_gauge!(DB_POOL_CONNECTIONS, "Open database connections");
+    metrics::describe_gauge!(DB_POOL_IDLE_CONNECTIONS, "Idle database connections");
+    metrics::describe_gauge!(DB_POOL_MAX_CONNECTIONS, "Maximum database connections");
+    metrics::describe_counter!(LOGIN_FAILURES, "Failed teacher logins");
+    metrics::describe_counter!(SESSIONS_STARTED, "Students registered for a test");
+    metrics::describe_counter!(
+        SESSIONS_COMPLETED,
+        "Students that submitted their final level"
+    );
+    metrics::describe_counter!(UPLOADS_STORED, "Files uploaded and stored by the backend");
+}
+
+pub fn router() -> Router {
+    let handle = recorder();
+
+    Router::new().route(
+        "/metrics",
+        get(move |Extension(db): Extension<Db>| {
+            let handle = handle.clone();
+            async move {
+                metrics::gauge!(DB_POOL_CONNECTIONS).set(db.size() as f64);
+                metrics::gauge!(DB_POOL_IDLE_CONNECTIONS).set(db.num_idle() as f64);
+                metrics::gauge!(DB_POOL_MAX_CONNECTIONS).set(db.max_connections() as f64);
+
+                handle.render()
+            }
+        }),
+    )
+}
+
+/// Records the count and latency of every request, labelled by the route it matched.
+pub async fn track(req: Request<Body>, next: Next) -> impl IntoResponse {
+    let start = Instant::now();
+
+    // Anything that didn't match a route is a static file, which would explode the label set.
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| "static".to_owned());
+    let method = req.method().to_string();
+
+    let response = next.run(req).await;
+
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", response.status().as_u16().to_string()),
+    ];
+
+    metrics::counter!(HTTP_REQUESTS, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION, &labels).record(start.elapsed().as_secs_f64());
+
+    response
+}
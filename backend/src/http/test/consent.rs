@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
+use crate::http::auth::teacher_auth;
+use crate::http::language;
+use crate::http::teacher::Teacher;
+use crate::http::test::{accessible_test, share};
+use crate::http::{Error, Result};
+use shared::consent::{
+    anonymous_name, pseudonym, Consent, RecordConsent, ResearchExport, ResearchResponse,
+    ResearchStudent,
+};
+use shared::phonetics::Stage;
+use shared::share::Permission;
+use shared::test::{StudentResult, Test};
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/students/:result_id/consent",
+            post(record_consent).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/export",
+            get(research_export).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// Whether whoever has `test` may see who `student` is and what they drew. Only the test's owner
+/// may, unless the student's parents agreed to research use.
+pub(crate) fn identifiable(test: &Test, student: &StudentResult) -> bool {
+    test.permission == Permission::Owner || student.consent == Consent::Research
+}
+
+/// Replaces the student's name with their pseudonym, unless whoever has `test` may see it and it
+/// wasn't removed under the retention policy.
+pub(crate) fn deidentify(test: &Test, student: &mut StudentResult) {
+    if !identifiable(test, student) || student.name_purged_at.is_some() {
+        student.name = anonymous_name(student.id, language::current());
+    }
+}
+
+/// Record the consent the parents of a student who took one of the teacher's tests signed, or that
+/// they withdrew it, along with when and by whom.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/students/{result_id}/consent",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("result_id" = Uuid, Path, description = "The id of the student's result"),
+    ),
+    request_body = RecordConsent,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The consent was recorded"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or student doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn record_consent(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, result_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<RecordConsent>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    let updated = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE result SET consent = $1, consent_recorded_at = $2, consent_recorded_by = $3
+            WHERE id = $4 AND test_id = $5",
+        )
+        .bind(req.consent)
+        .bind(share::now())
+        .bind(&teacher.username)
+        .bind(result_id)
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+    })?;
+
+    if updated == 0 {
+        return Err(Error::UnprocessableEntity(
+            "This student ID is not valid".to_string(),
+        ));
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(sqlx::FromRow)]
+struct ExportedResponse {
+    id: Uuid,
+    result_id: Uuid,
+    question_id: Uuid,
+    level: i32,
+    attempt: i32,
+    drawing: Vec<u8>,
+    drawing_key_id: Option<String>,
+    drawing_purged_at: Option<i64>,
+    transcription: Option<String>,
+    score: Option<f64>,
+    phonetic_accuracy: Option<f64>,
+    stage: Option<Stage>,
+}
+
+/// Export the answers of the students of one of the teacher's tests whose parents agreed to
+/// research use, with their drawings. Students are only known by their pseudonym, and the ones
+/// without research consent are left out altogether.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/export",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The students with research consent, by pseudonym", body = ResearchExport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn research_export(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+) -> Result<Json<ResearchExport>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let students = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>(
+            "SELECT * FROM result WHERE test_id = $1 AND consent = $2 ORDER BY id",
+        )
+        .bind(test_id)
+        .bind(Consent::Research)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let responses = with_pool!(db, pool => {
+        sqlx::query_as::<_, ExportedResponse>(
+            "SELECT response.id, response.result_id, response.question_id, response.level,
+                response.attempt, response.drawing, response.drawing_key_id,
+                response.drawing_purged_at, response.transcription, response.score,
+                response.phonetic_accuracy, response.stage
+            FROM response
+            JOIN result ON result.id = response.result_id
+            WHERE result.test_id = $1 AND result.consent = $2
+            ORDER BY response.level, response.attempt",
+        )
+        .bind(test_id)
+        .bind(Consent::Research)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let mut answers: HashMap<Uuid, Vec<ResearchResponse>> = HashMap::new();
+    for response in responses {
+        let drawing = match response.drawing_purged_at {
+            Some(_) => None,
+            None => Some(STANDARD.encode(keys.open(
+                Field::Drawing,
+                response.id,
+                response.drawing_key_id.as_deref(),
+                response.drawing,
+            )?)),
+        };
+
+        answers
+            .entry(response.result_id)
+            .or_default()
+            .push(ResearchResponse {
+                question_id: response.question_id,
+                level: response.level,
+                attempt: response.attempt,
+                drawing,
+                transcription: response.transcription,
+                score: response.score,
+                phonetic_accuracy: response.phonetic_accuracy,
+                stage: response.stage,
+            });
+    }
+
+    let students = students
+        .into_iter()
+        .map(|student| ResearchStudent {
+            pseudonym: pseudonym(student.id),
+            level: student.level,
+            language: student.language,
+            finished: student.finished,
+            responses: answers.remove(&student.id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(Json(ResearchExport {
+        test_id,
+        language: test.language,
+        students,
+    }))
+}
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::Keys;
+use crate::http::auth::{student_auth, teacher_auth};
+use crate::http::teacher::Teacher;
+use crate::http::test::{accessible_test, live::Live};
+use crate::http::{telemetry, Error, Result};
+use crate::shutdown::Shutdown;
+use shared::live::{Command, SendCommand, SessionControl};
+use shared::share::Permission;
+use shared::test::StudentResult;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/control",
+            get(poll_control).route_layer(middleware::from_fn(student_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/students/control",
+            post(control_all).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/students/:result_id/control",
+            post(control_student).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// How long a device's request for the next change is held open, kept below the idle timeouts of
+/// common proxies.
+const POLL_TIMEOUT: Duration = Duration::from_secs(25);
+
+#[derive(Deserialize)]
+pub(crate) struct PollParams {
+    /// The version the device already has.
+    version: Option<u64>,
+}
+
+/// Get what the teacher wants the registered student's device to do. When the device already has
+/// the given `version`, the response is held back until it changes or a while has passed.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/control",
+    tag = "student",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("version" = Option<u64>, Query, description = "Wait for a version other than this one"),
+    ),
+    security(("student_token" = [])),
+    responses(
+        (status = 200, description = "The state the teacher put the session in", body = SessionControl),
+        (status = 401, description = "Not registered for a test", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn poll_control(
+    Extension(live): Extension<Live>,
+    Extension(shutdown): Extension<Shutdown>,
+    Extension(student): Extension<StudentResult>,
+    Query(params): Query<PollParams>,
+) -> Result<Json<SessionControl>> {
+    let mut control = live.control(&student);
+
+    if Some(control.borrow().version) == params.version {
+        tokio::select! {
+            _ = tokio::time::timeout(POLL_TIMEOUT, control.changed()) => {},
+            _ = shutdown.triggered() => {},
+        }
+    }
+
+    let current = control.borrow().clone();
+    Ok(Json(current))
+}
+
+/// Pause, resume, end or restart the session of one of the students taking the teacher's test.
+/// Ending it finishes the test at the level the student was last seen on. Restarting throws away the answers they gave so far, so it's only for teachers who can edit
+/// the test.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/students/{result_id}/control",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("result_id" = Uuid, Path, description = "The id of the student's result"),
+    ),
+    request_body = SendCommand,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The student's device will carry out the command"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The student already finished the test", body = ErrorResponse),
+        (status = 422, description = "The test or student doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn control_student(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Extension(live): Extension<Live>,
+    Path((test_id, result_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<SendCommand>,
+) -> Result<StatusCode> {
+    let permission = match req.command {
+        Command::Restart => Permission::Editor,
+        _ => Permission::Proctor,
+    };
+    accessible_test(&db, &teacher, test_id, permission).await?;
+
+    let student = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1 AND test_id = $2")
+            .bind(result_id)
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    let Some(mut student) = student else {
+        return Err(Error::UnprocessableEntity(
+            "This student ID is not valid".to_string(),
+        ));
+    };
+    keys.open_name(&mut student)?;
+
+    if student.finished {
+        return Err(Error::Conflict(
+            "This student has already finished the test".to_string(),
+        ));
+    }
+
+    match req.command {
+        // Either the student starts over from nothing, or keeps everything
+        Command::Restart => with_pool!(db, pool => {
+            async {
+                let mut tx = pool.begin().await?;
+
+                sqlx::query("DELETE FROM response WHERE result_id = $1")
+                    .bind(student.id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("UPDATE result SET level = $1 WHERE id = $2")
+                    .bind(1)
+                    .bind(student.id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                tx.commit().await
+            }
+            .await
+        })?,
+        // Stored rather than only sent to the device, so the session can't go on from a token
+        // that was kept, not even after the server restarts
+        Command::End => {
+            with_pool!(db, pool => {
+                sqlx::query("UPDATE result SET level = $1, finished = $2 WHERE id = $3")
+                    .bind(live.level(&student))
+                    .bind(true)
+                    .bind(student.id)
+                    .execute(pool)
+                    .await
+                    .map(|_| ())
+            })?;
+            metrics::counter!(telemetry::SESSIONS_COMPLETED).increment(1);
+        }
+        Command::Pause | Command::Resume => {}
+    }
+
+    live.command(&student, req.command);
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Pause or resume every student taking the teacher's test who hasn't finished it yet. Students
+/// who register while the test is paused start out paused.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/students/control",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = SendCommand,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The students' devices will carry out the command"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the command can't be sent to everyone", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn control_all(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Extension(live): Extension<Live>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<SendCommand>,
+) -> Result<StatusCode> {
+    accessible_test(&db, &teacher, test_id, Permission::Proctor).await?;
+
+    let paused = match req.command {
+        Command::Pause => true,
+        Command::Resume => false,
+        Command::End | Command::Restart => {
+            return Err(Error::UnprocessableEntity(
+                "Only pausing and resuming apply to every student at once".to_string(),
+            ))
+        }
+    };
+
+    let mut students = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>(
+            "SELECT * FROM result WHERE test_id = $1 AND finished = $2",
+        )
+        .bind(test_id)
+        .bind(false)
+        .fetch_all(pool)
+        .await
+    })?;
+    keys.open_names(&mut students)?;
+
+    live.pause_all(test_id, &students, paused);
+
+    Ok(StatusCode::ACCEPTED)
+}
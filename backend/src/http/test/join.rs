@@ -0,0 +1,109 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use rand::seq::SliceRandom;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::test::accessible_test;
+use crate::http::{Error, Result};
+use shared::share::Permission;
+use shared::test::{normalize_join_code, JoinTest, SetPin, Test};
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/pin",
+            post(set_pin).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route("/v1/join/:code", get(find_test))
+}
+
+/// Without the letters and digits that are easily mistaken for one another, like O and 0. The
+/// migration that introduced join codes gave existing tests codes of the same letters.
+const JOIN_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+const JOIN_CODE_LENGTH: usize = 6;
+
+/// How many codes are tried before giving up on creating a test.
+pub(super) const MAX_CODE_ATTEMPTS: usize = 5;
+
+pub(super) fn new_join_code() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..JOIN_CODE_LENGTH)
+        .map(|_| *JOIN_CODE_ALPHABET.choose(&mut rng).unwrap() as char)
+        .collect()
+}
+
+/// Find the test a join code belongs to.
+#[utoipa::path(
+    get,
+    path = "/v1/join/{code}",
+    tag = "student",
+    params(("code" = String, Path, description = "The test's join code, in any case")),
+    responses(
+        (status = 200, description = "The test the code belongs to", body = JoinTest),
+        (status = 422, description = "No test has this code", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn find_test(
+    Extension(db): Extension<Db>,
+    Path(code): Path<String>,
+) -> Result<Json<JoinTest>> {
+    let test = with_pool!(db, pool => {
+        sqlx::query_as::<_, Test>("SELECT * FROM test WHERE join_code = $1")
+            .bind(normalize_join_code(&code))
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    let Some(test) = test else {
+        return Err(Error::UnprocessableEntity(
+            "This join code is not valid".to_string(),
+        ));
+    };
+
+    Ok(Json(JoinTest {
+        test_id: test.id,
+        needs_pin: test.pin.is_some(),
+    }))
+}
+
+/// Set or remove the PIN students have to enter to register for one of the teacher's tests.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/pin",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = SetPin,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The PIN was changed"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_pin(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<SetPin>,
+) -> Result<StatusCode> {
+    req.validate()?;
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query("UPDATE test SET pin = $1 WHERE id = $2")
+            .bind(req.pin)
+            .bind(test_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{middleware, routing::delete, routing::get, routing::post, Extension, Json, Router};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
+use crate::http::auth::{create_token, teacher_auth};
+use crate::http::language;
+use crate::http::teacher::Teacher;
+use crate::http::test::{
+    accessible_test,
+    consent::{deidentify, identifiable},
+    live::Live,
+    student::start_session,
+};
+use crate::http::{Error, Result};
+use crate::util::Config;
+use shared::consent::anonymous_name;
+use shared::share::Permission;
+use shared::test::{KioskSession, RegisterStudent, RosterEntry, StudentResult};
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/roster",
+            get(get_roster)
+                .post(add_to_roster)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/roster/:entry_id",
+            delete(remove_from_roster).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/roster/:entry_id/kiosk",
+            post(start_roster_session).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/kiosk",
+            post(start_kiosk_session).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/kiosk/:result_id",
+            post(renew_kiosk_session).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// List the students expected to take one of the teacher's tests, and the results of those who
+/// did. Only the test's owner sees the names of the students without research consent, the others
+/// see the pseudonym of their result, or of their entry until they start the test.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/roster",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The test's roster, by name", body = [RosterEntry]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_roster(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+) -> Result<Json<Vec<RosterEntry>>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    // A student's result has the same digest as their entry, or the same name where neither is
+    // encrypted
+    let mut roster = with_pool!(db, pool => {
+        sqlx::query_as::<_, RosterEntry>(
+            "SELECT roster.*, (
+                SELECT result.id FROM result
+                WHERE result.test_id = roster.test_id
+                    AND (result.name_digest = roster.name_digest
+                        OR (result.name_key_id IS NULL AND roster.name_key_id IS NULL
+                            AND result.name = roster.name))
+                LIMIT 1
+            ) AS result_id
+            FROM roster WHERE roster.test_id = $1",
+        )
+        .bind(test_id)
+        .fetch_all(pool)
+        .await
+    })?;
+    let results: HashMap<_, _> = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+            .bind(test_id)
+            .fetch_all(pool)
+            .await
+    })?
+    .into_iter()
+    .map(|result| (result.id, result))
+    .collect();
+    keys.open_roster(&mut roster)?;
+
+    for entry in &mut roster {
+        let result = entry.result_id.and_then(|id| results.get(&id));
+        if test.permission != Permission::Owner
+            && !result.is_some_and(|result| identifiable(&test, result))
+        {
+            entry.name = anonymous_name(entry.result_id.unwrap_or(entry.id), language::current());
+        }
+    }
+    // Sorted once decrypted
+    roster.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Json(roster))
+}
+
+/// Add a student to the roster of one of the teacher's tests. Adding a name twice has no effect.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/roster",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = RegisterStudent,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The student is on the roster"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn add_to_roster(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<RegisterStudent>,
+) -> Result<StatusCode> {
+    req.validate()?;
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    let RegisterStudent { name, language, .. } = req;
+    let name = name.trim();
+    let id = Uuid::new_v4();
+    let sealed = keys.seal_text(Field::RosterName, id, name)?;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO roster (id, test_id, name, name_key_id, name_digest, language)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (test_id, name_digest) DO NOTHING",
+        )
+        .bind(id)
+        .bind(test_id)
+        .bind(sealed.value)
+        .bind(sealed.key_id)
+        .bind(keys.digest_name(test_id, name))
+        .bind(language)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Take a student off the roster of one of the teacher's tests.
+#[utoipa::path(
+    delete,
+    path = "/v1/test/{test_id}/roster/{entry_id}",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("entry_id" = Uuid, Path, description = "The roster entry's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The student is no longer on the roster"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn remove_from_roster(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query("DELETE FROM roster WHERE id = $1 AND test_id = $2")
+            .bind(entry_id)
+            .bind(test_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Start a student's session on a device the teacher is proctoring.
+///
+/// Unlike registering through the student link this works on closed tests, and returns the
+/// session's token instead of setting a cookie.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/kiosk",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = RegisterStudent,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The student's session was started", body = KioskSession),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The name is taken", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn start_kiosk_session(
+    Extension(db): Extension<Db>,
+    Extension(cfg): Extension<Config>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Extension(live): Extension<Live>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<RegisterStudent>,
+) -> Result<Json<KioskSession>> {
+    req.validate()?;
+    let test = accessible_test(&db, &teacher, test_id, Permission::Proctor).await?;
+
+    let mut result = start_session(&db, &keys, &live, test_id, req).await?;
+    let token = create_token(result.id, &cfg);
+    deidentify(&test, &mut result);
+
+    Ok(Json(KioskSession { token, result }))
+}
+
+/// Start the session of a student on the roster of one of the teacher's tests, on a device the
+/// teacher is proctoring, like [`start_kiosk_session`]. The student is picked by their entry, so
+/// that teachers who only see their pseudonym can start it too.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/roster/{entry_id}/kiosk",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("entry_id" = Uuid, Path, description = "The roster entry's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The student's session was started", body = KioskSession),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "The student already started the test", body = ErrorResponse),
+        (status = 422, description = "The test or roster entry doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn start_roster_session(
+    Extension(db): Extension<Db>,
+    Extension(cfg): Extension<Config>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Extension(live): Extension<Live>,
+    Path((test_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<KioskSession>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Proctor).await?;
+
+    let entry = with_pool!(db, pool => {
+        sqlx::query_as::<_, RosterEntry>("SELECT * FROM roster WHERE id = $1 AND test_id = $2")
+            .bind(entry_id)
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?
+    .ok_or_else(|| Error::UnprocessableEntity("This roster entry ID is not valid".to_string()))?;
+    let mut entries = [entry];
+    keys.open_roster(&mut entries)?;
+    let [entry] = entries;
+
+    let req = RegisterStudent {
+        name: entry.name,
+        language: entry.language,
+        pin: None,
+    };
+    let mut result = start_session(&db, &keys, &live, test_id, req).await?;
+    let token = create_token(result.id, &cfg);
+    deidentify(&test, &mut result);
+
+    Ok(Json(KioskSession { token, result }))
+}
+
+/// Get a new token for a student's session on a device the teacher is proctoring, once the one it
+/// was started with expired, so the answers still waiting on the device can be sent.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/kiosk/{result_id}",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("result_id" = Uuid, Path, description = "The id of the student's result"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The student's session, with a new token", body = KioskSession),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or student doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn renew_kiosk_session(
+    Extension(db): Extension<Db>,
+    Extension(cfg): Extension<Config>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, result_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<KioskSession>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Proctor).await?;
+
+    let mut result = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1 AND test_id = $2")
+            .bind(result_id)
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?
+    .ok_or_else(|| Error::UnprocessableEntity("This student ID is not valid".to_string()))?;
+    keys.open_name(&mut result)?;
+    deidentify(&test, &mut result);
+
+    let token = create_token(result.id, &cfg);
+
+    Ok(Json(KioskSession { token, result }))
+}
@@ -0,0 +1,430 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::Keys;
+use crate::http::auth::{student_auth, teacher_auth};
+use crate::http::language;
+use crate::http::teacher::Teacher;
+use crate::http::test::{
+    accessible_test, consent, student::decode_drawing, student::ensure_taking,
+};
+use crate::http::Result;
+use crate::shutdown::Shutdown;
+use shared::consent::anonymous_name;
+use shared::live::{
+    CanvasFrame, Command, ReportProgress, SessionControl, StudentProgress, CANVAS_EVENT,
+    PROGRESS_EVENT,
+};
+use shared::share::Permission;
+use shared::test::StudentResult;
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/live",
+            get(watch_test).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/progress",
+            post(report_progress).route_layer(middleware::from_fn(student_auth)),
+        )
+}
+
+/// How many updates a teacher's view may fall behind by before it starts skipping them.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Students who haven't done anything for this long are assumed to have gone home.
+const FORGET_AFTER: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// What the students taking each test are doing right now, as reported by their devices, and
+/// what the teacher wants them to do. Nothing here is stored, so after a restart students show up
+/// again as soon as they do something.
+#[derive(Clone, Default)]
+pub struct Live {
+    tests: Arc<Mutex<HashMap<Uuid, Channel>>>,
+}
+
+struct Channel {
+    updates: broadcast::Sender<Update>,
+    students: HashMap<Uuid, Student>,
+    /// Set while every student is paused, so the ones who register meanwhile start out paused.
+    paused: bool,
+}
+
+struct Student {
+    progress: StudentProgress,
+    /// When the student last did something, if they have since the server started.
+    last_active: Option<Instant>,
+    /// When the student or their teacher last had anything to do with them.
+    last_seen: Instant,
+    control: watch::Sender<SessionControl>,
+}
+
+#[derive(Clone)]
+enum Update {
+    Progress(StudentProgress),
+    Canvas(CanvasFrame),
+}
+
+impl Live {
+    /// Records that `student` just did something, and tells whoever is watching their test. The
+    /// attempt they were last seen on is kept if it isn't known.
+    pub fn publish(&self, student: &StudentResult, attempt: Option<i32>) {
+        self.with_channel(student.test_id, |channel| {
+            let seen = channel.student(student);
+            seen.progress.level = student.level;
+            seen.progress.finished = student.finished;
+            seen.progress.attempt = attempt.or(seen.progress.attempt);
+            seen.last_active = Some(Instant::now());
+
+            channel.announce(student.id);
+        });
+    }
+
+    /// Passes the canvas a student is drawing on to whoever is watching their test.
+    fn mirror(&self, student: &StudentResult, drawing: String) {
+        if let Some(channel) = self.tests.lock().unwrap().get(&student.test_id) {
+            // Nobody may be watching, which is fine
+            let _ = channel.updates.send(Update::Canvas(CanvasFrame {
+                id: student.id,
+                drawing,
+            }));
+        }
+    }
+
+    /// Starts listening to a test, along with where its students were last seen.
+    fn subscribe(
+        &self,
+        test_id: Uuid,
+    ) -> (HashMap<Uuid, StudentProgress>, broadcast::Receiver<Update>) {
+        self.with_channel(test_id, |channel| {
+            let seen = channel
+                .students
+                .iter()
+                .map(|(id, student)| (*id, student.progress()))
+                .collect();
+
+            (seen, channel.updates.subscribe())
+        })
+    }
+
+    /// Follows what the teacher wants `student`'s device to do.
+    pub(super) fn control(&self, student: &StudentResult) -> watch::Receiver<SessionControl> {
+        self.with_channel(student.test_id, |channel| {
+            channel.student(student).control.subscribe()
+        })
+    }
+
+    /// The level `student` was last seen on, or the one they're stored with if they haven't been
+    /// seen since the server started.
+    pub(super) fn level(&self, student: &StudentResult) -> i32 {
+        self.with_channel(student.test_id, |channel| {
+            channel.student(student).progress.level
+        })
+    }
+
+    /// Whether the teacher paused `student`'s session, on its own or along with everyone else's.
+    pub(super) fn paused(&self, student: &StudentResult) -> bool {
+        self.with_channel(student.test_id, |channel| {
+            channel.student(student).control.borrow().paused
+        })
+    }
+
+    /// Has `student`'s device carry out the command, whenever it next checks in.
+    pub(super) fn command(&self, student: &StudentResult, command: Command) {
+        self.with_channel(student.test_id, |channel| {
+            let seen = channel.student(student);
+            seen.control.send_modify(|control| apply(control, command));
+
+            match command {
+                Command::End => seen.progress.finished = true,
+                Command::Restart => {
+                    seen.progress.level = 1;
+                    seen.progress.attempt = None;
+                }
+                Command::Pause | Command::Resume => {}
+            }
+
+            channel.announce(student.id);
+        });
+    }
+
+    /// Pauses or resumes every one of `students`, and anyone who registers for the test while
+    /// it's paused.
+    pub(super) fn pause_all(&self, test_id: Uuid, students: &[StudentResult], paused: bool) {
+        let command = if paused {
+            Command::Pause
+        } else {
+            Command::Resume
+        };
+
+        self.with_channel(test_id, |channel| {
+            channel.paused = paused;
+
+            for student in students {
+                channel
+                    .student(student)
+                    .control
+                    .send_modify(|control| apply(control, command));
+                channel.announce(student.id);
+            }
+        });
+    }
+
+    /// Runs `f` on the test's channel, then forgets about the students who are long gone.
+    fn with_channel<T>(&self, test_id: Uuid, f: impl FnOnce(&mut Channel) -> T) -> T {
+        let mut tests = self.tests.lock().unwrap();
+        let res = f(tests.entry(test_id).or_insert_with(Channel::new));
+
+        for channel in tests.values_mut() {
+            channel.students.retain(|_, student| {
+                student.last_seen.elapsed() < FORGET_AFTER || student.control.receiver_count() > 0
+            });
+        }
+        tests.retain(|_, channel| {
+            !channel.students.is_empty() || channel.updates.receiver_count() > 0 || channel.paused
+        });
+
+        res
+    }
+}
+
+impl Channel {
+    fn new() -> Self {
+        Channel {
+            updates: broadcast::channel(CHANNEL_CAPACITY).0,
+            students: HashMap::new(),
+            paused: false,
+        }
+    }
+
+    /// The student, who is added if they haven't been seen yet.
+    fn student(&mut self, result: &StudentResult) -> &mut Student {
+        let paused = self.paused;
+        let student = self
+            .students
+            .entry(result.id)
+            .or_insert_with(|| Student::new(result, paused));
+
+        student.last_seen = Instant::now();
+        student
+    }
+
+    /// Tells whoever is watching the test where the student is now.
+    fn announce(&self, id: Uuid) {
+        if let Some(student) = self.students.get(&id) {
+            let _ = self.updates.send(Update::Progress(student.progress()));
+        }
+    }
+}
+
+impl Student {
+    fn new(result: &StudentResult, paused: bool) -> Self {
+        Student {
+            progress: StudentProgress {
+                id: result.id,
+                name: result.name.clone(),
+                language: result.language,
+                level: result.level,
+                attempt: None,
+                idle_secs: None,
+                finished: result.finished,
+                paused,
+            },
+            last_active: None,
+            last_seen: Instant::now(),
+            control: watch::channel(SessionControl {
+                paused,
+                ..SessionControl::default()
+            })
+            .0,
+        }
+    }
+
+    fn progress(&self) -> StudentProgress {
+        StudentProgress {
+            idle_secs: self.last_active.map(|at| at.elapsed().as_secs()),
+            paused: self.control.borrow().paused,
+            ..self.progress.clone()
+        }
+    }
+}
+
+fn apply(control: &mut SessionControl, command: Command) {
+    match command {
+        Command::Pause => control.paused = true,
+        Command::Resume => control.paused = false,
+        Command::End => {
+            control.paused = false;
+            control.ended = true;
+        }
+        Command::Restart => {
+            control.paused = false;
+            control.ended = false;
+            control.restarts += 1;
+        }
+    }
+    control.version += 1;
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WatchParams {
+    /// The result id of the student whose canvas should be mirrored.
+    watch: Option<Uuid>,
+}
+
+/// Follow the students taking one of the teacher's tests as server-sent events. Every student is
+/// sent as a `progress` event when the stream starts, and again whenever they do something. Only
+/// the test's owner sees the names and canvases of students without research consent.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/live",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("watch" = Option<Uuid>, Query, description = "Also send `canvas` events with this student's drawing"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "`progress` events carrying a StudentProgress, and `canvas` events carrying a CanvasFrame", content_type = "text/event-stream"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn watch_test(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Extension(live): Extension<Live>,
+    Extension(shutdown): Extension<Shutdown>,
+    Path(test_id): Path<Uuid>,
+    Query(params): Query<WatchParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    // Subscribe first, so nothing that happens while the results load is missed
+    let (mut seen, updates) = live.subscribe(test_id);
+
+    let mut results = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+            .bind(test_id)
+            .fetch_all(pool)
+            .await
+    })?;
+    keys.open_names(&mut results)?;
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Students who register later haven't had their consent recorded yet
+    let identifiable: HashSet<Uuid> = results
+        .iter()
+        .filter(|result| consent::identifiable(&test, result))
+        .map(|result| result.id)
+        .collect();
+    let owner = test.permission == Permission::Owner;
+    // The stream outlives the request, and the language it was made in with it
+    let language = language::current();
+    let shown = move |id: Uuid| owner || identifiable.contains(&id);
+
+    let students: Vec<Update> = results
+        .into_iter()
+        .map(|result| {
+            let progress = seen
+                .remove(&result.id)
+                .unwrap_or_else(|| Student::new(&result, false).progress());
+            Update::Progress(progress)
+        })
+        .collect();
+
+    let updates = stream::unfold(updates, |mut updates| async move {
+        loop {
+            match updates.recv().await {
+                Ok(update) => return Some((update, updates)),
+                // A slow connection only misses some updates, the next ones bring it up to date
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::iter(students)
+        .chain(updates)
+        .filter_map(move |update| {
+            let event = match update {
+                Update::Progress(mut progress) => {
+                    if !shown(progress.id) {
+                        progress.name = anonymous_name(progress.id, language);
+                    }
+                    Event::default()
+                        .event(PROGRESS_EVENT)
+                        .json_data(progress)
+                        .ok()
+                }
+                Update::Canvas(frame) if Some(frame.id) == params.watch && shown(frame.id) => {
+                    Event::default().event(CANVAS_EVENT).json_data(frame).ok()
+                }
+                Update::Canvas(_) => None,
+            };
+            async move { event.map(Ok) }
+        })
+        // Otherwise the stream would hold up the graceful shutdown until it times out
+        .take_until(shutdown.triggered());
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Let the teacher know where the registered student is in the test, and what they're drawing.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/progress",
+    tag = "student",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = ReportProgress,
+    security(("student_token" = [])),
+    responses(
+        (status = 202, description = "The progress was passed on"),
+        (status = 401, description = "Not registered for a test", body = ErrorResponse),
+        (status = 409, description = "The test is already over or paused", body = ErrorResponse),
+        (status = 422, description = "The request body or drawing was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn report_progress(
+    Extension(live): Extension<Live>,
+    Extension(student): Extension<StudentResult>,
+    Json(req): Json<ReportProgress>,
+) -> Result<StatusCode> {
+    req.validate()?;
+    ensure_taking(&live, &student)?;
+
+    let ReportProgress {
+        level,
+        attempt,
+        drawing,
+    } = req;
+
+    if let Some(drawing) = &drawing {
+        decode_drawing(drawing)?;
+    }
+
+    let student = StudentResult { level, ..student };
+    live.publish(&student, Some(attempt));
+
+    if let Some(drawing) = drawing {
+        live.mirror(&student, drawing);
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
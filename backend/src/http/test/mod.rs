@@ -2,16 +2,26 @@ use axum::http::StatusCode;
 use axum::{
     extract::Path, middleware, response::IntoResponse, routing::post, Extension, Json, Router,
 };
-use sqlx::PgPool;
 use uuid::Uuid;
 
-use self::student::StudentResult;
+use crate::db::{with_pool, Db};
+use crate::encryption::Keys;
 use crate::http::auth::teacher_auth;
+use crate::http::organization;
 use crate::http::teacher::Teacher;
-use crate::http::Result;
-use serde::{Deserialize, Serialize};
+use crate::http::{Error, Result};
+use shared::share::Permission;
+use shared::test::{CreateTest, StudentResult, Test, TestSettings};
 use validator::Validate;
 
+pub mod consent;
+pub mod control;
+pub mod join;
+pub mod kiosk;
+pub mod live;
+pub mod report;
+pub mod score;
+pub mod share;
 pub mod student;
 
 pub fn router() -> Router {
@@ -28,89 +38,259 @@ pub fn router() -> Router {
                 .get(get_results)
                 .route_layer(middleware::from_fn(teacher_auth)),
         )
+        .route(
+            "/v1/test/:test_id/settings",
+            post(update_settings).route_layer(middleware::from_fn(teacher_auth)),
+        )
         .merge(student::router())
+        .merge(kiosk::router())
+        .merge(live::router())
+        .merge(control::router())
+        .merge(join::router())
+        .merge(report::router())
+        .merge(score::router())
+        .merge(share::router())
+        .merge(consent::router())
 }
 
-#[derive(Deserialize, Serialize, sqlx::FromRow, Clone)]
-pub struct Test {
-    pub id: Uuid,
-    pub teacher_id: Uuid,
-    pub name: String,
-    pub closed: bool,
-}
-
-#[derive(Deserialize, Validate)]
-pub struct CreateTest {
-    #[validate(length(
-        min = 3,
-        max = 40,
-        message = "The test's name must be between 3 and 40 characters long"
-    ))]
-    name: String,
-}
-
-async fn create_test(
-    Extension(db): Extension<PgPool>,
+/// Create a new test owned by the logged in teacher, with their organization's settings.
+#[utoipa::path(
+    post,
+    path = "/v1/test",
+    tag = "test",
+    request_body = CreateTest,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The test was created"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_test(
+    Extension(db): Extension<Db>,
     Extension(teacher): Extension<Teacher>,
     Json(req): Json<CreateTest>,
 ) -> Result<impl IntoResponse> {
     req.validate()?;
 
-    let CreateTest { name } = req;
+    let CreateTest { name, language } = req;
+    let TestSettings {
+        autoplay,
+        speech_rate,
+        voice,
+    } = organization::test_settings(&db, &teacher).await?;
 
-    let _ = sqlx::query!(
-        "INSERT INTO test (teacher_id, name) VALUES ($1, $2)",
-        teacher.id,
-        name
-    )
-    .execute(&db)
-    .await?;
+    // Codes are short enough to occasionally collide, in which case another one is tried
+    let mut attempts = 0;
+    loop {
+        let created = with_pool!(db, pool => {
+            sqlx::query(
+                "INSERT INTO test
+                (id, teacher_id, name, language, join_code, autoplay, speech_rate, voice)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(teacher.id)
+            .bind(&name)
+            .bind(language)
+            .bind(join::new_join_code())
+            .bind(autoplay)
+            .bind(speech_rate)
+            .bind(&voice)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        });
+
+        attempts += 1;
+        match created {
+            Err(sqlx::Error::Database(dbe))
+                if dbe.is_unique_violation() && attempts < join::MAX_CODE_ATTEMPTS =>
+            {
+                continue
+            }
+            created => break created?,
+        }
+    }
 
     Ok(StatusCode::ACCEPTED)
 }
 
-async fn get_tests(
-    Extension(db): Extension<PgPool>,
+/// The test, if it exists and `teacher` may do at least what `permission` allows with it, either
+/// as its owner or through a share that hasn't expired yet.
+pub(crate) async fn accessible_test(
+    db: &Db,
+    teacher: &Teacher,
+    test_id: Uuid,
+    permission: Permission,
+) -> Result<Test> {
+    let test = with_pool!(db, pool => {
+        sqlx::query_as::<_, Test>(
+            "SELECT test.*, 'owner' AS permission FROM test
+            WHERE test.id = $1 AND test.teacher_id = $2
+            UNION ALL
+            SELECT test.*, test_share.permission FROM test
+            JOIN test_share ON test_share.test_id = test.id
+            WHERE test.id = $1 AND test_share.teacher_id = $2
+                AND (test_share.expires_at IS NULL OR test_share.expires_at > $3)",
+        )
+        .bind(test_id)
+        .bind(teacher.id)
+        .bind(share::now())
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    // Teachers who can see a test but not do this with it aren't told it exists either
+    test.filter(|test| test.permission >= permission)
+        .ok_or_else(|| Error::UnprocessableEntity("This test ID is not valid".to_string()))
+}
+
+/// List every test the logged in teacher owns or has been shared.
+#[utoipa::path(
+    get,
+    path = "/v1/test",
+    tag = "test",
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The teacher's tests, with what they may do with each", body = [Test]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_tests(
+    Extension(db): Extension<Db>,
     Extension(teacher): Extension<Teacher>,
 ) -> Result<Json<Vec<Test>>> {
-    let tests = sqlx::query_as!(Test, "SELECT * FROM test WHERE teacher_id = $1", teacher.id)
-        .fetch_all(&db)
-        .await?;
+    let tests = with_pool!(db, pool => {
+        sqlx::query_as::<_, Test>(
+            "SELECT test.*, 'owner' AS permission FROM test
+            WHERE test.teacher_id = $1
+            UNION ALL
+            SELECT test.*, test_share.permission FROM test
+            JOIN test_share ON test_share.test_id = test.id
+            WHERE test_share.teacher_id = $1
+                AND (test_share.expires_at IS NULL OR test_share.expires_at > $2)",
+        )
+        .bind(teacher.id)
+        .bind(share::now())
+        .fetch_all(pool)
+        .await
+    })?;
 
     Ok(Json(tests))
 }
 
-async fn get_results(
-    Extension(db): Extension<PgPool>,
+/// List every student result for one of the tests the teacher can see. Only the test's owner sees
+/// the names of the students without research consent.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/manage",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The test's results", body = [StudentResult]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_results(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
     Extension(teacher): Extension<Teacher>,
     Path(test_id): Path<Uuid>,
 ) -> Result<Json<Vec<StudentResult>>> {
-    let results = sqlx::query_as!(
-        StudentResult,
-        "SELECT result.* FROM result
-        JOIN test ON result.test_id = test.id
-        WHERE result.test_id = $1 AND test.teacher_id = $2",
-        test_id,
-        teacher.id,
-    )
-    .fetch_all(&db)
-    .await?;
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let mut results = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+            .bind(test_id)
+            .fetch_all(pool)
+            .await
+    })?;
+    keys.open_names(&mut results)?;
+
+    for result in &mut results {
+        consent::deidentify(&test, result);
+    }
 
     Ok(Json(results))
 }
 
-async fn inverse_closed(
-    Extension(db): Extension<PgPool>,
+/// Open a closed test to new registrations, or close an open one.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/manage",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The test was toggled"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn inverse_closed(
+    Extension(db): Extension<Db>,
     Extension(teacher): Extension<Teacher>,
     Path(test_id): Path<Uuid>,
 ) -> Result<StatusCode> {
-    let _ = sqlx::query!(
-        "UPDATE test SET closed = NOT closed WHERE id = $1 AND teacher_id = $2",
-        test_id,
-        teacher.id,
+    accessible_test(&db, &teacher, test_id, Permission::Proctor).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query("UPDATE test SET closed = NOT closed WHERE id = $1")
+            .bind(test_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Change how the questions of one of the teacher's tests are read out.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/settings",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = TestSettings,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The settings were saved"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist or the request body was invalid", body = ErrorResponse),
     )
-    .execute(&db)
-    .await?;
+)]
+pub(crate) async fn update_settings(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<TestSettings>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    let TestSettings {
+        autoplay,
+        speech_rate,
+        voice,
+    } = req;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE test SET autoplay = $1, speech_rate = $2, voice = $3
+            WHERE id = $4",
+        )
+        .bind(autoplay)
+        .bind(speech_rate)
+        .bind(voice.filter(|voice| !voice.is_empty()))
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
 
     Ok(StatusCode::ACCEPTED)
 }
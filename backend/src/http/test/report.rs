@@ -0,0 +1,241 @@
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::IntoResponse;
+use axum::{middleware, routing::get, Extension, Router};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::test::accessible_test;
+use crate::http::test::consent::{deidentify, identifiable};
+use crate::http::{language, Error, Result};
+use crate::report::{self, ClassReport, ClassStudent, Drawing, StudentReport};
+use shared::phonetics::{typical_stage, Stage};
+use shared::share::Permission;
+use shared::test::StudentResult;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/report",
+            get(class_report).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/students/:result_id/report",
+            get(student_report).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// Download a printable report of how the students of one of the teacher's tests did: how many
+/// reached each level, and a table of every student. Written in the `Accept-Language` language.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/report",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The report", content_type = "application/pdf", body = Vec<u8>),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn class_report(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let mut results = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+            .bind(test_id)
+            .fetch_all(pool)
+            .await
+    })?;
+    // Names are encrypted, so they can only be put in order once they're decrypted
+    keys.open_names(&mut results)?;
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let first_answers = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
+            "SELECT result.id, MIN(response.created_at) FROM result
+            JOIN response ON response.result_id = result.id
+            WHERE result.test_id = $1
+            GROUP BY result.id",
+        )
+        .bind(test_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let stages = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, Stage)>(
+            "SELECT result.id, response.stage FROM result
+            JOIN response ON response.result_id = result.id
+            WHERE result.test_id = $1 AND response.stage IS NOT NULL",
+        )
+        .bind(test_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let students = results
+        .into_iter()
+        .map(|mut student| {
+            deidentify(&test, &mut student);
+            let taken_on = first_answers
+                .iter()
+                .find(|(id, _)| *id == student.id)
+                .map(|(_, at)| at.date_naive());
+            let stage = typical_stage(
+                stages
+                    .iter()
+                    .filter(|(id, _)| *id == student.id)
+                    .map(|(_, stage)| *stage),
+            );
+            ClassStudent {
+                result: student,
+                taken_on,
+                stage,
+            }
+        })
+        .collect();
+
+    let report = ClassReport {
+        test_name: test.name,
+        students,
+    };
+
+    let language = language::current();
+    let pdf = render(move || report::class_report(&report, language)).await?;
+
+    Ok(pdf_download(&report_name(&[&test.join_code]), pdf))
+}
+
+/// Download a printable report of one of the students who took the teacher's test: the level they
+/// reached and every drawing they made. Written in the `Accept-Language` language. Only the test's
+/// owner sees who students without research consent are and what they drew.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/students/{result_id}/report",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("result_id" = Uuid, Path, description = "The id of the student's result"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The report", content_type = "application/pdf", body = Vec<u8>),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or student doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn student_report(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, result_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let student = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1 AND test_id = $2")
+            .bind(result_id)
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+
+    let Some(mut student) = student else {
+        return Err(Error::UnprocessableEntity(
+            "This student ID is not valid".to_string(),
+        ));
+    };
+    keys.open_name(&mut student)?;
+
+    let mut drawings = with_pool!(db, pool => {
+        sqlx::query_as::<_, Drawing>(
+            "SELECT id, level, attempt, drawing, drawing_key_id, created_at, transcription,
+                phonetic_accuracy, stage
+            FROM response
+            WHERE result_id = $1
+            ORDER BY level, attempt",
+        )
+        .bind(student.id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    // Without research consent, only the test's owner sees who the student is and what they drew.
+    // The captions stay, and the drawings' frames are left empty.
+    deidentify(&test, &mut student);
+    let identifiable = identifiable(&test, &student);
+    for drawing in &mut drawings {
+        let stored = std::mem::take(&mut drawing.drawing);
+        let key_id = drawing.drawing_key_id.take();
+        if identifiable {
+            drawing.drawing = keys.open(Field::Drawing, drawing.id, key_id.as_deref(), stored)?;
+        }
+    }
+
+    let filename = report_name(&[&student.name, &test.join_code]);
+    let report = StudentReport {
+        test_name: test.name,
+        student,
+        drawings,
+    };
+
+    let language = language::current();
+    let pdf = render(move || report::student_report(&report, language)).await?;
+
+    Ok(pdf_download(&filename, pdf))
+}
+
+/// Lays a report out away from the async runtime, since drawings take a while to scale down.
+async fn render(
+    layout: impl FnOnce() -> anyhow::Result<Vec<u8>> + Send + 'static,
+) -> Result<Vec<u8>> {
+    let pdf = tokio::task::spawn_blocking(layout)
+        .await
+        .map_err(anyhow::Error::from)??;
+
+    Ok(pdf)
+}
+
+/// A file name made of `parts`, with anything that could trip up a browser or file system left out.
+fn report_name(parts: &[&str]) -> String {
+    let name = parts
+        .iter()
+        .map(|part| {
+            part.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == ' ')
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!("report-{name}.pdf")
+}
+
+fn pdf_download(filename: &str, pdf: Vec<u8>) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/pdf"),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+
+    (headers, pdf)
+}
@@ -0,0 +1,254 @@
+use axum::extract::Path;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::IntoResponse;
+use axum::{middleware, routing::get, routing::post, Extension, Json, Router};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::test::accessible_test;
+use crate::http::test::consent::identifiable;
+use crate::http::{Error, Result};
+use shared::phonetics::analyze;
+use shared::score::{spelling_score, ScoreResponse, ScoredResponse};
+use shared::share::Permission;
+use shared::test::StudentResult;
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/students/:result_id/responses",
+            get(get_responses).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/responses/:response_id/drawing",
+            get(get_drawing).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/responses/:response_id/score",
+            post(score_response).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// List a student's answers to the questions of one of the teacher's tests, with the recognizer's
+/// suggestions, the transcriptions the teacher entered and what they say about the student's
+/// spelling. Like their drawings, only the test's owner sees what students without research
+/// consent wrote on the questions that don't ask for a word, like their name.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/students/{result_id}/responses",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("result_id" = Uuid, Path, description = "The id of the student's result"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The student's answers, ordered by level and attempt", body = [ScoredResponse]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or student doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_responses(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, result_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<ScoredResponse>>> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let student = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1 AND test_id = $2")
+            .bind(result_id)
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?;
+    let Some(student) = student else {
+        return Err(Error::UnprocessableEntity(
+            "This student ID is not valid".to_string(),
+        ));
+    };
+
+    let mut responses = with_pool!(db, pool => {
+        sqlx::query_as::<_, ScoredResponse>(
+            "SELECT response.id, response.level, response.attempt, question.word,
+                question.phonemes, response.suggested_transcription, response.suggested_score,
+                response.transcription, response.score, response.phonetic_accuracy, response.stage
+            FROM response
+            JOIN question ON question.id = response.question_id
+            JOIN result ON result.id = response.result_id
+            WHERE response.result_id = $1 AND result.test_id = $2
+            ORDER BY response.level, response.attempt",
+        )
+        .bind(result_id)
+        .bind(test_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    if !identifiable(&test, &student) {
+        for response in responses
+            .iter_mut()
+            .filter(|response| response.word.is_none())
+        {
+            response.suggested_transcription = None;
+            response.transcription = None;
+        }
+    }
+
+    Ok(Json(responses))
+}
+
+/// Get the drawing a student made for one of their answers, as a PNG image. Only the test's owner
+/// can see the drawings of students without research consent.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/responses/{response_id}/drawing",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("response_id" = Uuid, Path, description = "The response's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The drawing", content_type = "image/png", body = Vec<u8>),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or response doesn't exist, the drawing can't be seen without research consent, or it was deleted under the retention policy", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_drawing(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, response_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let test = accessible_test(&db, &teacher, test_id, Permission::Viewer).await?;
+
+    let drawing = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Vec<u8>, Option<String>, Uuid)>(
+            "SELECT response.drawing, response.drawing_key_id, response.result_id FROM response
+            JOIN result ON result.id = response.result_id
+            WHERE response.id = $1 AND result.test_id = $2",
+        )
+        .bind(response_id)
+        .bind(test_id)
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    let Some((drawing, key_id, result_id)) = drawing else {
+        return Err(Error::UnprocessableEntity(
+            "This response ID is not valid".to_string(),
+        ));
+    };
+
+    let student = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1")
+            .bind(result_id)
+            .fetch_one(pool)
+            .await
+    })?;
+
+    if !identifiable(&test, &student) {
+        return Err(Error::UnprocessableEntity(
+            "Only the test's owner can see this student's drawings without research consent"
+                .to_string(),
+        ));
+    }
+
+    if drawing.is_empty() {
+        return Err(Error::UnprocessableEntity(
+            "This drawing was deleted under the retention policy".to_string(),
+        ));
+    }
+
+    let drawing = keys.open(Field::Drawing, response_id, key_id.as_deref(), drawing)?;
+
+    Ok((
+        [(header::CONTENT_TYPE, HeaderValue::from_static("image/png"))],
+        drawing,
+    ))
+}
+
+/// Set what a student wrote for one of their answers, accepting or overriding the recognizer's
+/// suggestion. On the spelling questions, the transcription is scored against the word unless a
+/// score is given, and its phonemes are used to work out the student's spelling stage.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/responses/{response_id}/score",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("response_id" = Uuid, Path, description = "The response's id"),
+    ),
+    request_body = ScoreResponse,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The transcription was saved"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or response doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn score_response(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, response_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<ScoreResponse>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    accessible_test(&db, &teacher, test_id, Permission::Editor).await?;
+
+    let question = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            "SELECT question.word, question.phonemes FROM response
+            JOIN question ON question.id = response.question_id
+            JOIN result ON result.id = response.result_id
+            WHERE response.id = $1 AND result.test_id = $2",
+        )
+        .bind(response_id)
+        .bind(test_id)
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    let Some((word, phonemes)) = question else {
+        return Err(Error::UnprocessableEntity(
+            "This response ID is not valid".to_string(),
+        ));
+    };
+
+    let ScoreResponse {
+        transcription,
+        score,
+    } = req;
+    let transcription = transcription.trim().to_string();
+
+    let score = word
+        .as_deref()
+        .map(|word| score.unwrap_or_else(|| spelling_score(word, &transcription)));
+    let analysis = word
+        .as_deref()
+        .zip(phonemes.as_deref())
+        .map(|(word, phonemes)| analyze(word, phonemes, &transcription));
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE response SET transcription = $1, score = $2, phonetic_accuracy = $3, stage = $4
+            WHERE id = $5",
+        )
+        .bind(transcription)
+        .bind(score)
+        .bind(analysis.map(|analysis| analysis.accuracy))
+        .bind(analysis.map(|analysis| analysis.stage))
+        .bind(response_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
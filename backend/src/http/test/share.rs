@@ -0,0 +1,257 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{middleware, routing::delete, routing::get, routing::post, Extension, Json, Router};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::http::auth::teacher_auth;
+use crate::http::teacher::Teacher;
+use crate::http::test::accessible_test;
+use crate::http::{Error, Result};
+use shared::share::{Permission, Share, ShareTest, TransferTests};
+use validator::Validate;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/v1/test/:test_id/shares",
+            get(get_shares)
+                .post(share_test)
+                .route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/test/:test_id/shares/:teacher_id",
+            delete(unshare_test).route_layer(middleware::from_fn(teacher_auth)),
+        )
+        .route(
+            "/v1/teacher/transfer",
+            post(transfer_tests).route_layer(middleware::from_fn(teacher_auth)),
+        )
+}
+
+/// The time shares expire against, in seconds since the Unix epoch.
+pub(crate) fn now() -> i64 {
+    Utc::now().timestamp()
+}
+
+/// The id of the teacher who registered with `email`, if they belong to the same organization as
+/// `teacher`. Tests never leave an organization, and whoever is outside of it isn't told who's in.
+async fn teacher_by_email(db: &Db, teacher: &Teacher, email: &str) -> Result<Uuid> {
+    let other = with_pool!(db, pool => {
+        sqlx::query_as::<_, (Uuid, Option<Uuid>)>(
+            "SELECT id, organization_id FROM teacher WHERE email = $1",
+        )
+        .bind(email.trim())
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    other
+        .filter(|(_, organization_id)| *organization_id == teacher.organization_id)
+        .map(|(id, _)| id)
+        .ok_or_else(|| {
+            Error::UnprocessableEntity("No teacher is registered with this email".to_string())
+        })
+}
+
+/// List the other teachers one of the teacher's own tests is shared with, including the ones
+/// whose access has expired.
+#[utoipa::path(
+    get,
+    path = "/v1/test/{test_id}/shares",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 200, description = "The teachers the test is shared with, by name", body = [Share]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_shares(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+) -> Result<Json<Vec<Share>>> {
+    accessible_test(&db, &teacher, test_id, Permission::Owner).await?;
+
+    let shares = with_pool!(db, pool => {
+        sqlx::query_as::<_, Share>(
+            "SELECT test_share.teacher_id, teacher.username, teacher.email,
+                test_share.permission, test_share.expires_at
+            FROM test_share
+            JOIN teacher ON teacher.id = test_share.teacher_id
+            WHERE test_share.test_id = $1
+            ORDER BY teacher.username",
+        )
+        .bind(test_id)
+        .fetch_all(pool)
+        .await
+    })?;
+
+    Ok(Json(shares))
+}
+
+/// Share one of the teacher's own tests with another teacher, or change what they may do with it.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/shares",
+    tag = "test",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = ShareTest,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The test was shared"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test or teacher doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn share_test(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path(test_id): Path<Uuid>,
+    Json(req): Json<ShareTest>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    let ShareTest {
+        email,
+        permission,
+        hours,
+    } = req;
+
+    match (permission, hours) {
+        (Permission::Owner, _) => {
+            return Err(Error::UnprocessableEntity(
+                "Tests have to be transferred to change their owner".to_string(),
+            ))
+        }
+        (Permission::Proctor, None) => {
+            return Err(Error::UnprocessableEntity(
+                "Proctors can only be given access for a while".to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    accessible_test(&db, &teacher, test_id, Permission::Owner).await?;
+
+    let teacher_id = teacher_by_email(&db, &teacher, &email).await?;
+    if teacher_id == teacher.id {
+        return Err(Error::UnprocessableEntity(
+            "You already own this test".to_string(),
+        ));
+    }
+
+    let expires_at = hours.map(|hours| now() + i64::from(hours) * 60 * 60);
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO test_share (test_id, teacher_id, permission, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (test_id, teacher_id)
+            DO UPDATE SET permission = excluded.permission, expires_at = excluded.expires_at",
+        )
+        .bind(test_id)
+        .bind(teacher_id)
+        .bind(permission)
+        .bind(expires_at)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Stop sharing one of the teacher's own tests with another teacher.
+#[utoipa::path(
+    delete,
+    path = "/v1/test/{test_id}/shares/{teacher_id}",
+    tag = "test",
+    params(
+        ("test_id" = Uuid, Path, description = "The test's id"),
+        ("teacher_id" = Uuid, Path, description = "The other teacher's id"),
+    ),
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The test is no longer shared with the teacher"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn unshare_test(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Path((test_id, teacher_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    accessible_test(&db, &teacher, test_id, Permission::Owner).await?;
+
+    with_pool!(db, pool => {
+        sqlx::query("DELETE FROM test_share WHERE test_id = $1 AND teacher_id = $2")
+            .bind(test_id)
+            .bind(teacher_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Hand every test the logged in teacher owns over to another teacher, with their results and
+/// shares, for when they leave the school. The logged in teacher loses access to all of them.
+#[utoipa::path(
+    post,
+    path = "/v1/teacher/transfer",
+    tag = "teacher",
+    request_body = TransferTests,
+    security(("teacher_token" = [])),
+    responses(
+        (status = 202, description = "The tests were transferred"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 422, description = "The teacher doesn't exist or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn transfer_tests(
+    Extension(db): Extension<Db>,
+    Extension(teacher): Extension<Teacher>,
+    Json(req): Json<TransferTests>,
+) -> Result<StatusCode> {
+    req.validate()?;
+
+    let new_owner = teacher_by_email(&db, &teacher, &req.email).await?;
+    if new_owner == teacher.id {
+        return Err(Error::UnprocessableEntity(
+            "You already own these tests".to_string(),
+        ));
+    }
+
+    with_pool!(db, pool => {
+        async {
+            let mut tx = pool.begin().await?;
+
+            // The new owner doesn't need the tests shared with them anymore
+            sqlx::query(
+                "DELETE FROM test_share WHERE teacher_id = $1
+                AND test_id IN (SELECT id FROM test WHERE teacher_id = $2)",
+            )
+            .bind(new_owner)
+            .bind(teacher.id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("UPDATE test SET teacher_id = $1 WHERE teacher_id = $2")
+                .bind(new_owner)
+                .bind(teacher.id)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await
+        }
+        .await
+    })?;
+
+    Ok(StatusCode::ACCEPTED)
+}
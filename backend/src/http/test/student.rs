@@ -1,17 +1,23 @@
+use std::time::Duration;
+
 use axum::extract::Path;
 use axum::http::{header, HeaderMap, StatusCode};
 use axum::middleware;
 use axum::response::IntoResponse;
 use axum::{routing::post, Extension, Json, Router};
 use axum_extra::extract::cookie::{Cookie, SameSite};
-use sqlx::PgPool;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use uuid::Uuid;
 
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
 use crate::http::auth::student_auth;
-use crate::http::test::Test;
-use crate::http::{Error, Result};
+use crate::http::test::{live::Live, share};
+use crate::http::{telemetry, Error, Result};
+use crate::scoring::Scoring;
+use crate::shutdown::Shutdown;
 use crate::util::Config;
-use serde::{Deserialize, Serialize};
+use shared::test::{RegisterStudent, SetLevel, StudentResult, SubmitResponse, Test};
 use validator::Validate;
 
 pub fn router() -> Router {
@@ -21,115 +27,191 @@ pub fn router() -> Router {
             "/v1/test/:test_id",
             post(set_score).route_layer(middleware::from_fn(student_auth)),
         )
+        .route(
+            "/v1/test/:test_id/response",
+            post(submit_response).route_layer(middleware::from_fn(student_auth)),
+        )
 }
 
-#[derive(Deserialize, Validate)]
-pub struct RegisterStudent {
-    #[validate(length(
-        min = 3,
-        max = 40,
-        message = "Your name must be between 3 and 40 characters long"
-    ))]
-    name: String,
-}
-
-#[derive(Deserialize, Validate)]
-pub struct SetLevel {
-    #[validate(range(min = 1, max = 8, message = "Invalid level range"))]
-    level: i32,
-}
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
 
-#[derive(Deserialize, sqlx::FromRow, Serialize, Clone)]
-pub struct StudentResult {
-    pub id: Uuid,
-    pub test_id: Uuid,
-    pub name: String,
-    pub level: i32,
-}
+const WRONG_PIN_DELAY: Duration = Duration::from_secs(1);
 
-async fn register_student(
-    Extension(db): Extension<PgPool>,
+/// Register a student for a test, setting the `STUDENT_TOKEN` cookie.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/register",
+    tag = "student",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = RegisterStudent,
+    responses(
+        (status = 202, description = "The student was registered", headers(
+            ("set-cookie" = String, description = "The `STUDENT_TOKEN` session cookie")
+        )),
+        (status = 409, description = "The test is closed or the name is taken", body = ErrorResponse),
+        (status = 422, description = "The test doesn't exist, the PIN is wrong or the request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn register_student(
+    Extension(db): Extension<Db>,
     Extension(cfg): Extension<Config>,
+    Extension(keys): Extension<Keys>,
+    Extension(live): Extension<Live>,
     Path(test_id): Path<Uuid>,
     Json(req): Json<RegisterStudent>,
 ) -> Result<impl IntoResponse> {
     req.validate()?;
 
-    let RegisterStudent { name } = req;
-
-    let test = sqlx::query_as!(Test, "SELECT * FROM test WHERE id = $1", test_id)
-        .fetch_optional(&db)
-        .await?;
+    let test = with_pool!(db, pool => {
+        sqlx::query_as::<_, Test>("SELECT * FROM test WHERE id = $1")
+            .bind(test_id)
+            .fetch_optional(pool)
+            .await
+    })?;
 
     // Does the test exist?
-    if let Some(test) = test {
-        // Is the test closed?
-        if test.closed {
-            return Err(Error::Conflict(
-                "This test is closed to new registration".to_string(),
-            ));
-        }
+    let Some(test) = test else {
+        return Err(Error::UnprocessableEntity(
+            "This test ID is not valid".to_string(),
+        ));
+    };
 
-        let existing_result = sqlx::query_as!(
-            StudentResult,
-            "SELECT * FROM result WHERE (test_id, name) = ($1, $2)",
-            test_id,
-            name
-        )
-        .fetch_optional(&db)
-        .await?;
+    // Is the test closed?
+    if test.closed {
+        return Err(Error::Conflict(
+            "This test is closed to new registration".to_string(),
+        ));
+    }
 
-        if existing_result.is_some() {
-            return Err(Error::Conflict("This name is already taken".to_string()));
-        }
+    // Tests with a PIN only let in the students who were told it
+    if test.pin.is_some() && test.pin != req.pin {
+        // Slows down anyone trying to guess it
+        tokio::time::sleep(WRONG_PIN_DELAY).await;
+        return Err(Error::UnprocessableEntity(
+            "The PIN is not correct".to_string(),
+        ));
+    }
+
+    let res = start_session(&db, &keys, &live, test_id, req).await?;
+
+    let cookie = crate::http::auth::create_cookie("STUDENT_TOKEN", res.id, cfg).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::SET_COOKIE, cookie.parse().unwrap());
 
-        let res = sqlx::query_as!(
-            StudentResult,
-            "INSERT INTO result (test_id, name) VALUES ($1, $2) RETURNING *",
-            test_id,
-            name
+    Ok((StatusCode::ACCEPTED, headers))
+}
+
+/// Creates the result a student's answers are recorded under, unless someone of the same name
+/// already took the test. Names are encrypted, so that's down to their digests being unique.
+pub(super) async fn start_session(
+    db: &Db,
+    keys: &Keys,
+    live: &Live,
+    test_id: Uuid,
+    req: RegisterStudent,
+) -> Result<StudentResult> {
+    let RegisterStudent { name, language, .. } = req;
+    // Trimmed like the names on the roster, or the same student wouldn't get the same digest
+    let name = name.trim();
+
+    let id = Uuid::new_v4();
+    let sealed = keys.seal_text(Field::Name, id, name)?;
+
+    let mut res = with_pool!(db, pool => {
+        sqlx::query_as::<_, StudentResult>(
+            "INSERT INTO result (id, test_id, name, name_key_id, name_digest, language, registered_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *",
         )
-        .fetch_one(&db)
+        .bind(id)
+        .bind(test_id)
+        .bind(sealed.value)
+        .bind(sealed.key_id)
+        .bind(keys.digest_name(test_id, name))
+        .bind(language)
+        .bind(share::now())
+        .fetch_one(pool)
         .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(dbe) if dbe.constraint() == Some("result_name_key") => {
-                Error::Conflict("This name is already taken.".to_string())
-            }
-            _ => e.into(),
-        })?;
+    })
+    .map_err(|e| match e {
+        sqlx::Error::Database(dbe) if dbe.is_unique_violation() => {
+            Error::Conflict("This name is already taken.".to_string())
+        }
+        _ => e.into(),
+    })?;
+    keys.open_name(&mut res)?;
+
+    metrics::counter!(telemetry::SESSIONS_STARTED).increment(1);
+    live.publish(&res, None);
 
-        let cookie = crate::http::auth::create_cookie("STUDENT_TOKEN", res.id, cfg).await;
+    Ok(res)
+}
 
-        let mut headers = HeaderMap::new();
-        headers.insert(header::SET_COOKIE, cookie.parse().unwrap());
+/// Refuses anything more from a student whose test is over, and, until it's resumed, from one whose
+/// teacher paused it.
+pub(super) fn ensure_taking(live: &Live, student: &StudentResult) -> Result<()> {
+    if student.finished {
+        return Err(Error::Conflict(
+            "This student has already finished the test".to_string(),
+        ));
+    }
 
-        return Ok((StatusCode::ACCEPTED, headers));
+    if live.paused(student) {
+        return Err(Error::Paused("The teacher paused the test".to_string()));
     }
 
-    Err(Error::UnprocessableEntity(
-        "This test ID is not valid".to_string(),
-    ))
+    Ok(())
 }
 
-async fn set_score(
-    Extension(db): Extension<PgPool>,
+/// Submit the final level the registered student reached.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}",
+    tag = "student",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = SetLevel,
+    security(("student_token" = [])),
+    responses(
+        (status = 202, description = "The level was saved"),
+        (status = 401, description = "Not registered for a test", body = ErrorResponse),
+        (status = 409, description = "The test is already over or paused", body = ErrorResponse),
+        (status = 422, description = "The request body was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_score(
+    Extension(db): Extension<Db>,
+    Extension(live): Extension<Live>,
     Extension(student): Extension<StudentResult>,
     Json(req): Json<SetLevel>,
 ) -> Result<impl IntoResponse> {
     req.validate()?;
+    ensure_taking(&live, &student)?;
 
     let SetLevel { level } = req;
 
-    sqlx::query_as!(
-        StudentResult,
-        "UPDATE result SET level = $1 WHERE id = $2",
-        level,
-        student.id
-    )
-    .execute(&db)
-    .await?;
+    with_pool!(db, pool => {
+        sqlx::query("UPDATE result SET level = $1, finished = $2 WHERE id = $3")
+            .bind(level)
+            .bind(true)
+            .bind(student.id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    })?;
 
-    let cookie = Cookie::build(("TEACHER_TOKEN", ""))
+    metrics::counter!(telemetry::SESSIONS_COMPLETED).increment(1);
+    live.publish(
+        &StudentResult {
+            level,
+            finished: true,
+            ..student
+        },
+        None,
+    );
+
+    // The student is done, so their session ends here
+    let cookie = Cookie::build(("STUDENT_TOKEN", ""))
         .path("/")
         .max_age(time::Duration::hours(-1))
         .same_site(SameSite::Lax)
@@ -140,3 +222,403 @@ async fn set_score(
 
     Ok((StatusCode::ACCEPTED, headers))
 }
+
+/// Store the registered student's drawing for one question. Resubmitting a response with the same
+/// id is accepted but has no effect, so clients can safely retry. Answers to spelling questions are
+/// then read by the recognizer, if the server has one, in the background.
+#[utoipa::path(
+    post,
+    path = "/v1/test/{test_id}/response",
+    tag = "student",
+    params(("test_id" = Uuid, Path, description = "The test's id")),
+    request_body = SubmitResponse,
+    security(("student_token" = [])),
+    responses(
+        (status = 202, description = "The response was stored"),
+        (status = 401, description = "Not registered for a test", body = ErrorResponse),
+        (status = 409, description = "The test is already over or paused", body = ErrorResponse),
+        (status = 422, description = "The request body or drawing was invalid", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn submit_response(
+    Extension(db): Extension<Db>,
+    Extension(keys): Extension<Keys>,
+    Extension(live): Extension<Live>,
+    Extension(scoring): Extension<Scoring>,
+    Extension(shutdown): Extension<Shutdown>,
+    Extension(student): Extension<StudentResult>,
+    Json(req): Json<SubmitResponse>,
+) -> Result<StatusCode> {
+    req.validate()?;
+    ensure_taking(&live, &student)?;
+
+    let SubmitResponse {
+        id,
+        question_id,
+        level,
+        attempt,
+        drawing,
+        strokes,
+    } = req;
+
+    let drawing = decode_drawing(&drawing)?;
+    let strokes = match strokes.is_empty() {
+        true => None,
+        false => Some(serde_json::to_string(&strokes).map_err(anyhow::Error::from)?),
+    };
+
+    // The strokes are encrypted with the same key as the drawing
+    let drawing = keys.seal(Field::Drawing, id, &drawing)?;
+    let strokes = strokes
+        .map(|strokes| keys.seal_text(Field::Strokes, id, &strokes))
+        .transpose()?
+        .map(|sealed| sealed.value);
+
+    let stored = with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO response
+                (id, result_id, question_id, level, attempt, drawing, strokes, drawing_key_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(student.id)
+        .bind(question_id)
+        .bind(level)
+        .bind(attempt)
+        .bind(drawing.value)
+        .bind(strokes)
+        .bind(drawing.key_id)
+        .execute(pool)
+        .await
+        .map(|res| res.rows_affected())
+    })?;
+
+    if stored > 0 {
+        metrics::counter!(telemetry::UPLOADS_STORED).increment(1);
+
+        shutdown.spawn(async move {
+            if let Err(e) = scoring.suggest(&db, &keys, id).await {
+                tracing::warn!("Failed to suggest a score for response {id}: {e:#}");
+            }
+        });
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// The PNG image a drawing was sent as, base64 encoded.
+pub(super) fn decode_drawing(drawing: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(drawing)
+        .ok()
+        .filter(|drawing| drawing.starts_with(PNG_SIGNATURE))
+        .ok_or_else(|| Error::UnprocessableEntity("The drawing must be a PNG image".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::teacher::Teacher;
+    use crate::http::test::control::control_student;
+    use crate::http::test::score::{get_responses, score_response};
+    use shared::live::{Command, SendCommand};
+    use shared::phonetics::Stage;
+    use shared::score::{spelling_score, Point, ScoreResponse};
+
+    fn keys() -> Keys {
+        let key = STANDARD.encode([7; 32]);
+        Keys::parse(&format!("k1:{key}"), &key).unwrap()
+    }
+
+    fn config() -> Config {
+        Config {
+            db_url: "sqlite::memory:".to_string(),
+            jwt_secret: "secret".to_string(),
+            jwt_expires_in: "60m".to_string(),
+            jwt_maxage: 60,
+        }
+    }
+
+    /// A teacher, and the id of a test of theirs.
+    async fn teacher_with_test(db: &Db) -> (Teacher, Uuid) {
+        let (teacher_id, test_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        with_pool!(db, pool => {
+            sqlx::query("INSERT INTO teacher (id, username, email, password) VALUES ($1, $2, $3, $4)")
+                .bind(teacher_id)
+                .bind("teacher")
+                .bind("teacher@example.com")
+                .bind("")
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })
+        .unwrap();
+        with_pool!(db, pool => {
+            sqlx::query("INSERT INTO test (id, teacher_id, name, join_code) VALUES ($1, $2, $3, $4)")
+                .bind(test_id)
+                .bind(teacher_id)
+                .bind("Fall")
+                .bind("ABCDEF")
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })
+        .unwrap();
+
+        let teacher = with_pool!(db, pool => {
+            sqlx::query_as::<_, Teacher>("SELECT * FROM teacher WHERE id = $1")
+                .bind(teacher_id)
+                .fetch_one(pool)
+                .await
+        })
+        .unwrap();
+
+        (teacher, test_id)
+    }
+
+    async fn register(db: &Db, keys: &Keys, test_id: Uuid, name: &str) -> Result<StatusCode> {
+        let req = RegisterStudent {
+            name: name.to_string(),
+            language: None,
+            pin: None,
+        };
+        let response = register_student(
+            Extension(db.clone()),
+            Extension(config()),
+            Extension(keys.clone()),
+            Extension(Live::default()),
+            Path(test_id),
+            Json(req),
+        )
+        .await?;
+
+        Ok(response.into_response().status())
+    }
+
+    async fn submit(
+        db: &Db,
+        keys: &Keys,
+        student: &StudentResult,
+        req: SubmitResponse,
+    ) -> Result<StatusCode> {
+        submit_response(
+            Extension(db.clone()),
+            Extension(keys.clone()),
+            Extension(Live::default()),
+            Extension(Scoring::default()),
+            Extension(Shutdown::new()),
+            Extension(student.clone()),
+            Json(req),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn registers_submits_and_scores() {
+        let db = Db::memory().await;
+        let keys = keys();
+        let (teacher, test_id) = teacher_with_test(&db).await;
+
+        assert_eq!(
+            register(&db, &keys, test_id, "Ada").await.unwrap(),
+            StatusCode::ACCEPTED
+        );
+        for taken in ["Ada", " Ada "] {
+            assert!(matches!(
+                register(&db, &keys, test_id, taken).await,
+                Err(Error::Conflict(_))
+            ));
+        }
+
+        // Stored encrypted, and only once
+        let mut students = with_pool!(db, pool => {
+            sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+                .bind(test_id)
+                .fetch_all(pool)
+                .await
+        })
+        .unwrap();
+        assert_eq!(students.len(), 1);
+        assert_eq!(students[0].name_key_id.as_deref(), Some("k1"));
+        assert_ne!(students[0].name, "Ada");
+        keys.open_names(&mut students).unwrap();
+        let student = students.remove(0);
+        assert_eq!(student.name, "Ada");
+
+        let question_id = with_pool!(db, pool => {
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM question WHERE word = $1 LIMIT 1")
+                .bind("cat")
+                .fetch_one(pool)
+                .await
+        })
+        .unwrap();
+        let drawing = [PNG_SIGNATURE, b"pixels"].concat();
+        let req = SubmitResponse {
+            id: Uuid::new_v4(),
+            question_id,
+            level: 3,
+            attempt: 1,
+            drawing: STANDARD.encode(&drawing),
+            strokes: vec![vec![Point { x: 1.0, y: 2.0 }]],
+        };
+
+        // Sent again after a dropped connection
+        for _ in 0..2 {
+            let status = submit(&db, &keys, &student, req.clone()).await.unwrap();
+            assert_eq!(status, StatusCode::ACCEPTED);
+        }
+
+        let stored = with_pool!(db, pool => {
+            sqlx::query_as::<_, (Vec<u8>, Option<String>)>(
+                "SELECT drawing, drawing_key_id FROM response WHERE result_id = $1",
+            )
+            .bind(student.id)
+            .fetch_all(pool)
+            .await
+        })
+        .unwrap();
+        assert_eq!(stored.len(), 1);
+        let (stored, key_id) = stored.into_iter().next().unwrap();
+        assert_eq!(key_id.as_deref(), Some("k1"));
+        let opened = keys
+            .open(Field::Drawing, req.id, key_id.as_deref(), stored)
+            .unwrap();
+        assert_eq!(opened, drawing);
+
+        let status = score_response(
+            Extension(db.clone()),
+            Extension(teacher.clone()),
+            Path((test_id, req.id)),
+            Json(ScoreResponse {
+                transcription: " kat ".to_string(),
+                score: None,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let Json(responses) = get_responses(
+            Extension(db.clone()),
+            Extension(teacher),
+            Path((test_id, student.id)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(responses.len(), 1);
+        let scored = &responses[0];
+        assert_eq!(scored.transcription.as_deref(), Some("kat"));
+        assert_eq!(scored.score, Some(spelling_score("cat", "kat")));
+        assert_eq!(scored.phonetic_accuracy, Some(1.0));
+        assert_eq!(scored.stage, Some(Stage::Transitional));
+    }
+
+    #[tokio::test]
+    async fn rejects_what_isnt_a_png() {
+        let db = Db::memory().await;
+        let keys = Keys::default();
+        let (_, test_id) = teacher_with_test(&db).await;
+
+        register(&db, &keys, test_id, "Ada").await.unwrap();
+        let student = with_pool!(db, pool => {
+            sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE test_id = $1")
+                .bind(test_id)
+                .fetch_one(pool)
+                .await
+        })
+        .unwrap();
+        // Without keys, names are stored as they are
+        assert_eq!(student.name, "Ada");
+        assert_eq!(student.name_key_id, None);
+
+        let req = SubmitResponse {
+            id: Uuid::new_v4(),
+            question_id: Uuid::new_v4(),
+            level: 1,
+            attempt: 1,
+            drawing: STANDARD.encode(b"GIF89a"),
+            strokes: Vec::new(),
+        };
+        assert!(matches!(
+            submit(&db, &keys, &student, req).await,
+            Err(Error::UnprocessableEntity(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn ended_students_cant_submit() {
+        let db = Db::memory().await;
+        let keys = keys();
+        let (teacher, test_id) = teacher_with_test(&db).await;
+
+        register(&db, &keys, test_id, "Ada").await.unwrap();
+        let student_id = with_pool!(db, pool => {
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM result WHERE test_id = $1")
+                .bind(test_id)
+                .fetch_one(pool)
+                .await
+        })
+        .unwrap();
+
+        let status = control_student(
+            Extension(db.clone()),
+            Extension(keys.clone()),
+            Extension(teacher),
+            Extension(Live::default()),
+            Path((test_id, student_id)),
+            Json(SendCommand {
+                command: Command::End,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        // Loaded again for every request, as the student's token is
+        let student = with_pool!(db, pool => {
+            sqlx::query_as::<_, StudentResult>("SELECT * FROM result WHERE id = $1")
+                .bind(student_id)
+                .fetch_one(pool)
+                .await
+        })
+        .unwrap();
+        assert!(student.finished);
+
+        let req = SubmitResponse {
+            id: Uuid::new_v4(),
+            question_id: Uuid::new_v4(),
+            level: 1,
+            attempt: 1,
+            drawing: STANDARD.encode(PNG_SIGNATURE),
+            strokes: Vec::new(),
+        };
+        assert!(matches!(
+            submit(&db, &keys, &student, req).await,
+            Err(Error::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn closed_tests_turn_students_away() {
+        let db = Db::memory().await;
+        let keys = keys();
+        let (_, test_id) = teacher_with_test(&db).await;
+
+        with_pool!(db, pool => {
+            sqlx::query("UPDATE test SET closed = $1 WHERE id = $2")
+                .bind(true)
+                .bind(test_id)
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            register(&db, &keys, test_id, "Ada").await,
+            Err(Error::Conflict(_))
+        ));
+    }
+}
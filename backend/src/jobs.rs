@@ -0,0 +1,297 @@
+//! Durable background jobs, for the work that isn't tied to a request. Jobs are kept in the `job`
+//! table until they're done, so they survive restarts and can be run by any of the servers sharing
+//! the database. A job that fails is retried with an exponential backoff, and given up on after
+//! [`MAX_ATTEMPTS`] attempts.
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::job::{Job, JobStatus};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{self, Keys};
+use crate::retention::{self, Tests};
+use crate::shutdown::Shutdown;
+
+/// How often the runner looks for jobs that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many times a job is attempted before it's given up on.
+const MAX_ATTEMPTS: i32 = 5;
+
+/// How many seconds the first retry waits, doubled for every retry after it up to
+/// [`MAX_BACKOFF`].
+const BACKOFF: i64 = 30;
+const MAX_BACKOFF: i64 = 60 * 60;
+
+/// How many seconds a job may run before the server running it is assumed to have died, and the
+/// job is retried.
+const STALE_AFTER: i64 = 60 * 60;
+
+/// What a job does, stored as JSON with everything it needs to run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Task {
+    /// Removes whatever is past its retention period from every test.
+    PurgeRetention,
+    /// Re-encrypts the student data that isn't encrypted with the active key yet.
+    RotateKeys,
+}
+
+impl Task {
+    /// The name the task is stored under, the same as its JSON `kind`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Task::PurgeRetention => "purge_retention",
+            Task::RotateKeys => "rotate_keys",
+        }
+    }
+
+    async fn run(self, db: Db, keys: Keys) -> anyhow::Result<()> {
+        match self {
+            Task::PurgeRetention => retention::purge(&db, Tests::All).await.map(|_| ()),
+            Task::RotateKeys => encryption::rotate(&db, &keys).await.map(|_| ()),
+        }
+    }
+}
+
+/// A task that's run again every `every`, counted from when its latest run finished.
+pub struct Recurring {
+    pub task: Task,
+    pub every: Duration,
+}
+
+fn now() -> i64 {
+    Utc::now().timestamp()
+}
+
+/// Runs `task` in the background once it's `run_at`, in seconds since the Unix epoch.
+pub async fn enqueue(db: &Db, task: &Task, run_at: i64) -> anyhow::Result<Uuid> {
+    let id = Uuid::new_v4();
+    let payload = serde_json::to_string(task)?;
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "INSERT INTO job (id, kind, payload, status, max_attempts, run_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(id)
+        .bind(task.kind())
+        .bind(&payload)
+        .bind(JobStatus::Pending)
+        .bind(MAX_ATTEMPTS)
+        .bind(run_at)
+        .bind(now())
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(id)
+}
+
+/// Runs a job that was given up on again, from its first attempt. False if the job doesn't exist
+/// or hasn't failed.
+pub async fn retry(db: &Db, job_id: Uuid) -> anyhow::Result<bool> {
+    let updated = with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE job SET status = $1, attempts = 0, run_at = $2, started_at = NULL,
+                finished_at = NULL
+            WHERE id = $3 AND status = $4",
+        )
+        .bind(JobStatus::Pending)
+        .bind(now())
+        .bind(job_id)
+        .bind(JobStatus::Failed)
+        .execute(pool)
+        .await
+        .map(|done| done.rows_affected())
+    })?;
+
+    Ok(updated > 0)
+}
+
+/// Enqueues the next run of a recurring task, unless one is already waiting or running.
+async fn schedule(db: &Db, recurring: &Recurring) -> anyhow::Result<()> {
+    let kind = recurring.task.kind();
+
+    let (waiting, last_finished) = with_pool!(db, pool => {
+        sqlx::query_as::<_, (i64, Option<i64>)>(
+            "SELECT
+                (SELECT COUNT(*) FROM job WHERE kind = $1 AND status IN ($2, $3)),
+                (SELECT MAX(finished_at) FROM job WHERE kind = $1)",
+        )
+        .bind(kind)
+        .bind(JobStatus::Pending)
+        .bind(JobStatus::Running)
+        .fetch_one(pool)
+        .await
+    })?;
+
+    if waiting == 0 {
+        let every = recurring.every.as_secs() as i64;
+        let run_at = last_finished.map_or_else(now, |finished| finished + every);
+        enqueue(db, &recurring.task, run_at).await?;
+    }
+
+    Ok(())
+}
+
+/// Takes the job that's been due the longest, if any, making sure no other server takes it too.
+async fn claim(db: &Db) -> anyhow::Result<Option<Job>> {
+    let job = with_pool!(db, pool => {
+        sqlx::query_as::<_, Job>(
+            "UPDATE job SET status = $1, started_at = $2, attempts = attempts + 1
+            WHERE status = $3 AND id = (
+                SELECT id FROM job WHERE status = $3 AND run_at <= $2 ORDER BY run_at LIMIT 1
+            )
+            RETURNING *",
+        )
+        .bind(JobStatus::Running)
+        .bind(now())
+        .bind(JobStatus::Pending)
+        .fetch_optional(pool)
+        .await
+    })?;
+
+    Ok(job)
+}
+
+/// Gives the jobs whose server died while running them another attempt, or gives up on them if
+/// they're out of attempts.
+async fn requeue_stale(db: &Db) -> anyhow::Result<()> {
+    let now = now();
+    let cutoff = now - STALE_AFTER;
+    let error = "The server stopped while running the job";
+
+    with_pool!(db, pool => {
+        async {
+            let mut tx = pool.begin().await?;
+
+            sqlx::query(
+                "UPDATE job SET status = $1, finished_at = $2, last_error = $3
+                WHERE status = $4 AND started_at < $5 AND attempts >= max_attempts",
+            )
+            .bind(JobStatus::Failed)
+            .bind(now)
+            .bind(error)
+            .bind(JobStatus::Running)
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE job SET status = $1, run_at = $2, last_error = $3
+                WHERE status = $4 AND started_at < $5",
+            )
+            .bind(JobStatus::Pending)
+            .bind(now)
+            .bind(error)
+            .bind(JobStatus::Running)
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await
+        }
+        .await
+    })?;
+
+    Ok(())
+}
+
+/// How many seconds to wait before the attempt after `attempts` failed ones.
+fn backoff(attempts: i32) -> i64 {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+
+    (BACKOFF << doublings).min(MAX_BACKOFF)
+}
+
+/// Runs a claimed job, recording whether it succeeded or when it'll be retried.
+async fn execute(db: &Db, keys: &Keys, job: Job) -> anyhow::Result<()> {
+    tracing::info!(job_id = %job.id, kind = %job.kind, attempt = job.attempts, "Running job");
+
+    let res = match serde_json::from_str::<Task>(&job.payload) {
+        // In a task of its own, so a panic fails the job instead of the runner
+        Ok(task) => tokio::spawn(task.run(db.clone(), keys.clone()))
+            .await
+            .unwrap_or_else(|e| Err(anyhow!("The job panicked: {e}"))),
+        Err(e) => Err(anyhow!(e).context("Unknown task")),
+    };
+
+    let now = now();
+    let (status, run_at, finished_at, error) = match res {
+        Ok(()) => (JobStatus::Succeeded, job.run_at, Some(now), None),
+        Err(e) if job.attempts >= job.max_attempts => {
+            tracing::error!(job_id = %job.id, kind = %job.kind, "Job failed, giving up: {e:?}");
+            (
+                JobStatus::Failed,
+                job.run_at,
+                Some(now),
+                Some(format!("{e:#}")),
+            )
+        }
+        Err(e) => {
+            let run_at = now + backoff(job.attempts);
+            tracing::warn!(job_id = %job.id, kind = %job.kind, run_at, "Job failed, retrying: {e:?}");
+            (JobStatus::Pending, run_at, None, Some(format!("{e:#}")))
+        }
+    };
+
+    with_pool!(db, pool => {
+        sqlx::query(
+            "UPDATE job SET status = $1, run_at = $2, finished_at = $3, last_error = $4
+            WHERE id = $5",
+        )
+        .bind(status)
+        .bind(run_at)
+        .bind(finished_at)
+        .bind(error)
+        .bind(job.id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })?;
+
+    Ok(())
+}
+
+/// Schedules the recurring tasks and works through every job that's due.
+async fn tick(
+    db: &Db,
+    keys: &Keys,
+    recurring: &[Recurring],
+    shutdown: &Shutdown,
+) -> anyhow::Result<()> {
+    requeue_stale(db).await?;
+
+    for recurring in recurring {
+        schedule(db, recurring).await?;
+    }
+
+    while !shutdown.is_triggered() {
+        let Some(job) = claim(db).await? else {
+            break;
+        };
+        execute(db, keys, job).await?;
+    }
+
+    Ok(())
+}
+
+/// Runs the jobs as they come due until the shutdown, letting the one that's running finish.
+pub async fn run(db: Db, keys: Keys, recurring: Vec<Recurring>, shutdown: Shutdown) {
+    loop {
+        if let Err(e) = tick(&db, &keys, &recurring, &shutdown).await {
+            tracing::error!("Failed to run the background jobs: {e:?}");
+        }
+
+        tokio::select! {
+            _ = shutdown.triggered() => return,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+}
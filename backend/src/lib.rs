@@ -1,2 +1,9 @@
+mod db;
+mod encryption;
 pub mod http;
+pub mod jobs;
+mod report;
+mod retention;
+mod scoring;
+mod shutdown;
 mod util;
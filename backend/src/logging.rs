@@ -0,0 +1,61 @@
+use anyhow::Context;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+use crate::util::{LogFormat, Opt};
+
+/// Sets up the global subscriber, filtered by `RUST_LOG` and written in the format chosen by `Opt`.
+pub fn init(opt: &Opt) -> anyhow::Result<()> {
+    let fmt = match opt.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(fmt);
+
+    #[cfg(feature = "otel")]
+    if let Some(endpoint) = &opt.otlp_endpoint {
+        return registry
+            .with(tracing_opentelemetry::layer().with_tracer(otel::tracer(endpoint)?))
+            .try_init()
+            .context("Failed to initialize logging");
+    }
+
+    registry.try_init().context("Failed to initialize logging")
+}
+
+/// Flushes any traces that haven't been exported yet.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use anyhow::Context;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+
+    pub fn tracer(endpoint: &str) -> anyhow::Result<trace::Tracer> {
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(
+                trace::config()
+                    .with_resource(Resource::new([KeyValue::new("service.name", "fnk")])),
+            )
+            .install_batch(runtime::Tokio)
+            .context("Failed to set up the OpenTelemetry exporter")
+    }
+}
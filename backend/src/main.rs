@@ -1,10 +1,21 @@
 use anyhow::Context;
 use axum_server::tls_rustls::RustlsConfig;
 use clap::Parser;
+use db::Db;
 use dotenv::dotenv;
-use sqlx::postgres::PgPoolOptions;
+use encryption::Keys;
+use shutdown::Shutdown;
+use std::time::Duration;
 use util::*;
+mod db;
+mod encryption;
 mod http;
+mod jobs;
+mod logging;
+mod report;
+mod retention;
+mod scoring;
+mod shutdown;
 mod util;
 
 #[tokio::main]
@@ -17,22 +28,50 @@ async fn main() -> anyhow::Result<()> {
 
     dotenv().ok();
 
-    tracing_subscriber::fmt::init();
+    logging::init(&opt)?;
 
     let config = Config::init();
 
+    let keys = Keys::from_env()?;
+    if keys.active_id().is_none() {
+        tracing::warn!(
+            "No ENCRYPTION_KEYS are set, so student names and drawings are stored unencrypted"
+        );
+    }
+
     let tls = RustlsConfig::from_pem_file("../ssl/cert.pem", "../ssl/key.pem")
         .await
         .context("Missing certifications")?;
 
-    let db = PgPoolOptions::new()
-        .max_connections(20)
-        .connect(&config.db_url)
+    let db = Db::connect(&config.db_url)
         .await
         .context("Failed to establish a connection to the database")?;
 
-    sqlx::migrate!().run(&db).await?;
+    db.migrate().await?;
+    // Before students can register again under a name stored before names were digested
+    keys.digest_legacy_names(&db).await?;
 
     tracing::info!("Successfully established a connection to the database!");
-    http::serve(opt, db, config, tls).await
+    let timeout = Duration::from_secs(opt.shutdown_timeout);
+    let shutdown = Shutdown::new();
+
+    let recurring = vec![jobs::Recurring {
+        task: jobs::Task::PurgeRetention,
+        every: Duration::from_secs(opt.retention_interval * 60 * 60),
+    }];
+    shutdown.spawn(jobs::run(
+        db.clone(),
+        keys.clone(),
+        recurring,
+        shutdown.clone(),
+    ));
+
+    let res = http::serve(opt, db.clone(), config, keys, tls, shutdown.clone()).await;
+
+    shutdown.wait_for_tasks(timeout).await;
+    db.close().await;
+    tracing::info!("Closed the connection to the database, exiting");
+
+    logging::shutdown();
+    res
 }
@@ -0,0 +1,442 @@
+//! Printable PDF reports of test results, for parent-teacher conferences and the like.
+//!
+//! Reports only use the fonts every PDF reader has built in, so they stay small, but that limits
+//! them to the characters of Windows-1252. That covers every language fnk is translated into.
+
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use printpdf::image_crate::{self, imageops::FilterType, DynamicImage, ImageFormat, Rgb, RgbImage};
+use printpdf::path::PaintMode;
+use printpdf::{
+    BuiltinFont, Color, Greyscale, Image, ImageTransform, IndirectFontRef, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Rect,
+};
+use shared::i18n::{translate, Language};
+use shared::phonetics::{typical_stage, Stage};
+use shared::question::{level_description, MAX_LEVEL};
+use shared::test::StudentResult;
+use uuid::Uuid;
+
+// US Letter, which is what the schools fnk is used in print on.
+const PAGE_WIDTH: f32 = 215.9;
+const PAGE_HEIGHT: f32 = 279.4;
+const MARGIN: f32 = 20.0;
+
+/// How many drawings fit next to one another on a student report.
+const DRAWINGS_PER_ROW: usize = 3;
+const DRAWING_WIDTH: f32 = 52.0;
+const DRAWING_HEIGHT: f32 = 39.0;
+const DRAWING_GAP: f32 = (PAGE_WIDTH - 2.0 * MARGIN - DRAWINGS_PER_ROW as f32 * DRAWING_WIDTH)
+    / (DRAWINGS_PER_ROW as f32 - 1.0);
+
+/// Drawings are scaled down to this resolution, which is plenty for a thumbnail.
+const DRAWING_DPI: f32 = 150.0;
+
+/// One of a student's drawings, as it was stored.
+#[derive(sqlx::FromRow)]
+pub struct Drawing {
+    /// The id of the response it was drawn for.
+    pub id: Uuid,
+    pub level: i32,
+    pub attempt: i32,
+    /// A PNG image, once it's decrypted.
+    pub drawing: Vec<u8>,
+    /// The id of the key the stored drawing is encrypted with, none once it's been decrypted.
+    pub drawing_key_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// What the teacher read in it, if they've transcribed it yet.
+    pub transcription: Option<String>,
+    /// Only set for transcribed answers to the spelling questions.
+    pub phonetic_accuracy: Option<f64>,
+    pub stage: Option<Stage>,
+}
+
+/// A line of a class's report.
+pub struct ClassStudent {
+    pub result: StudentResult,
+    /// The day they took the test on, if they got as far as answering a question.
+    pub taken_on: Option<NaiveDate>,
+    /// The spelling stage most of their transcribed answers are at.
+    pub stage: Option<Stage>,
+}
+
+/// Everything on a student's report.
+pub struct StudentReport {
+    pub test_name: String,
+    pub student: StudentResult,
+    /// Ordered by level and attempt.
+    pub drawings: Vec<Drawing>,
+}
+
+/// Everything on a class's report.
+pub struct ClassReport {
+    pub test_name: String,
+    /// Every student who registered for the test.
+    pub students: Vec<ClassStudent>,
+}
+
+/// The day a student took the test on, going by their first answer.
+pub fn taken_on(drawings: &[Drawing]) -> Option<NaiveDate> {
+    drawings
+        .iter()
+        .map(|drawing| drawing.created_at.date_naive())
+        .min()
+}
+
+pub fn student_report(report: &StudentReport, language: Language) -> anyhow::Result<Vec<u8>> {
+    let t = |message: &'static str| translate(language, message);
+    let student = &report.student;
+    let mut pdf = Writer::new(&format!("{} - {}", student.name, report.test_name))?;
+
+    pdf.heading(t("Student report"), 20.0);
+    pdf.field(t("Name"), &student.name);
+    pdf.field(t("Test"), &report.test_name);
+    let date = taken_on(&report.drawings).map(format_date);
+    pdf.field(t("Taken on"), date.as_deref().unwrap_or(t("Not taken yet")));
+    pdf.skip(4.0);
+
+    pdf.heading(t("Level reached"), 14.0);
+    pdf.line(
+        &format!("{} {} / {MAX_LEVEL}", t("Level"), student.level),
+        12.0,
+        true,
+    );
+    pdf.line(t(level_description(student.level)), 11.0, false);
+    if !student.finished {
+        pdf.line(t("The student hasn't finished the test yet."), 11.0, false);
+    }
+    pdf.skip(4.0);
+
+    let stage = typical_stage(report.drawings.iter().filter_map(|drawing| drawing.stage));
+    if let Some(stage) = stage {
+        let accuracies: Vec<f64> = report
+            .drawings
+            .iter()
+            .filter_map(|drawing| drawing.phonetic_accuracy)
+            .collect();
+        let accuracy = accuracies.iter().sum::<f64>() / accuracies.len().max(1) as f64;
+
+        pdf.heading(t("Spelling"), 14.0);
+        pdf.field(t("Stage"), t(stage.name()));
+        pdf.field(t("Sounds spelled"), &percent(accuracy));
+        pdf.skip(4.0);
+    }
+
+    pdf.heading(t("Drawings"), 14.0);
+    if report.drawings.is_empty() {
+        pdf.line(t("No drawings yet."), 11.0, false);
+    }
+
+    for row in report.drawings.chunks(DRAWINGS_PER_ROW) {
+        pdf.make_room(DRAWING_HEIGHT + 12.0);
+
+        for (column, drawing) in row.iter().enumerate() {
+            let x = MARGIN + column as f32 * (DRAWING_WIDTH + DRAWING_GAP);
+            pdf.drawing(&drawing.drawing, x);
+            pdf.text(
+                &format!(
+                    "{} {}, {} {}",
+                    t("Level"),
+                    drawing.level,
+                    t("attempt"),
+                    drawing.attempt
+                ),
+                9.0,
+                x,
+                pdf.y + DRAWING_HEIGHT + 4.5,
+                false,
+            );
+
+            if let Some(transcription) = &drawing.transcription {
+                let caption = match drawing.stage {
+                    Some(stage) => format!("\"{transcription}\" - {}", t(stage.name())),
+                    None => format!("\"{transcription}\""),
+                };
+                pdf.text(&caption, 9.0, x, pdf.y + DRAWING_HEIGHT + 8.5, false);
+            }
+        }
+
+        pdf.skip(DRAWING_HEIGHT + 14.0);
+    }
+
+    pdf.finish()
+}
+
+pub fn class_report(report: &ClassReport, language: Language) -> anyhow::Result<Vec<u8>> {
+    let t = |message: &'static str| translate(language, message);
+    let mut pdf = Writer::new(&report.test_name)?;
+
+    pdf.heading(t("Class report"), 20.0);
+    pdf.field(t("Test"), &report.test_name);
+    pdf.field(t("Printed on"), &format_date(Utc::now().date_naive()));
+    pdf.field(t("Students"), &report.students.len().to_string());
+    pdf.skip(4.0);
+
+    // Students still taking the test would only skew the levels towards the first ones
+    let mut counts = [0; MAX_LEVEL as usize];
+    let mut unfinished = 0;
+    for ClassStudent { result: student, .. } in &report.students {
+        match usize::try_from(student.level - 1) {
+            Ok(level) if student.finished && level < counts.len() => counts[level] += 1,
+            _ => unfinished += 1,
+        }
+    }
+    let most = counts.iter().copied().max().unwrap_or_default().max(1);
+
+    pdf.heading(t("Levels reached"), 14.0);
+    for (level, count) in (1..=MAX_LEVEL).zip(counts) {
+        pdf.make_room(11.0);
+        pdf.text(
+            &format!("{} {level}", t("Level")),
+            11.0,
+            MARGIN,
+            pdf.y + 4.0,
+            true,
+        );
+        pdf.bar(MARGIN + 25.0, 100.0 * count as f32 / most as f32);
+        pdf.text(&count.to_string(), 11.0, MARGIN + 130.0, pdf.y + 4.0, false);
+        pdf.layer.set_fill_color(GREY);
+        pdf.text(t(level_description(level)), 8.0, MARGIN, pdf.y + 8.5, false);
+        pdf.layer.set_fill_color(BLACK);
+        pdf.skip(11.0);
+    }
+    if unfinished > 0 {
+        pdf.line(
+            &format!("{} {unfinished}", t("Still taking the test:")),
+            11.0,
+            false,
+        );
+    }
+    pdf.skip(4.0);
+
+    pdf.heading(t("Students"), 14.0);
+    let columns = [
+        (t("Name"), MARGIN),
+        (t("Level"), MARGIN + 70.0),
+        (t("Status"), MARGIN + 88.0),
+        (t("Stage"), MARGIN + 118.0),
+        (t("Taken on"), MARGIN + 150.0),
+    ];
+    let header = |pdf: &mut Writer| {
+        for (title, x) in columns {
+            pdf.text(title, 10.0, x, pdf.y + 4.0, true);
+        }
+        pdf.skip(7.0);
+    };
+
+    header(&mut pdf);
+    for ClassStudent {
+        result: student,
+        taken_on,
+        stage,
+    } in &report.students
+    {
+        if pdf.make_room(6.0) {
+            header(&mut pdf);
+        }
+
+        let status = match student.finished {
+            true => t("Finished"),
+            false => t("In progress"),
+        };
+        let stage = stage.map(|stage| t(stage.name())).unwrap_or_default();
+        let date = taken_on.map(format_date).unwrap_or_default();
+        let cells = [
+            student.name.as_str(),
+            &student.level.to_string(),
+            status,
+            stage,
+            &date,
+        ];
+        for (cell, (_, x)) in cells.into_iter().zip(columns) {
+            pdf.text(cell, 10.0, x, pdf.y + 4.0, false);
+        }
+        pdf.skip(6.0);
+    }
+
+    pdf.finish()
+}
+
+fn percent(share: f64) -> String {
+    format!("{:.0}%", share * 100.0)
+}
+
+/// Dates are written the same way in every language, so nobody mistakes the day for the month.
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+const BLACK: Color = Color::Greyscale(Greyscale {
+    percent: 0.0,
+    icc_profile: None,
+});
+const GREY: Color = Color::Greyscale(Greyscale {
+    percent: 0.45,
+    icc_profile: None,
+});
+const LIGHT_GREY: Color = Color::Greyscale(Greyscale {
+    percent: 0.8,
+    icc_profile: None,
+});
+const BAR: Color = Color::Rgb(printpdf::Rgb {
+    r: 0.31,
+    g: 0.27,
+    b: 0.9,
+    icc_profile: None,
+});
+
+/// Lays a report out from the top of the page down, starting new pages as they fill up.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// How far down the current page has been written, in millimeters from its top.
+    y: f32,
+}
+
+impl Writer {
+    fn new(title: &str) -> anyhow::Result<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "");
+        let layer = doc.get_page(page).get_layer(layer);
+        let regular = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .context("Failed to add the report's font")?;
+        let bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .context("Failed to add the report's font")?;
+
+        Ok(Writer {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: MARGIN,
+        })
+    }
+
+    /// Starts a new page unless `height` still fits on this one, and says whether it did.
+    fn make_room(&mut self, height: f32) -> bool {
+        if self.y + height <= PAGE_HEIGHT - MARGIN {
+            return false;
+        }
+
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = MARGIN;
+        true
+    }
+
+    fn skip(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    /// Writes `text` with its baseline `y` millimeters from the top of the page.
+    fn text(&self, text: &str, size: f32, x: f32, y: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(text, size, Mm(x), Mm(PAGE_HEIGHT - y), font);
+    }
+
+    /// Writes a line of text at the left margin, below the last one.
+    fn line(&mut self, text: &str, size: f32, bold: bool) {
+        let height = size * 0.5;
+        self.make_room(height);
+        self.text(text, size, MARGIN, self.y + height * 0.8, bold);
+        self.skip(height);
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.make_room(size);
+        self.line(text, size, true);
+        self.skip(2.0);
+    }
+
+    fn field(&mut self, name: &str, value: &str) {
+        self.make_room(6.0);
+        self.text(name, 11.0, MARGIN, self.y + 4.0, true);
+        self.text(value, 11.0, MARGIN + 30.0, self.y + 4.0, false);
+        self.skip(6.0);
+    }
+
+    /// A horizontal bar `width` millimeters long, on the current line.
+    fn bar(&self, x: f32, width: f32) {
+        if width <= 0.0 {
+            return;
+        }
+
+        self.layer.set_fill_color(BAR);
+        self.layer.add_rect(Rect::new(
+            Mm(x),
+            Mm(PAGE_HEIGHT - self.y - 4.5),
+            Mm(x + width),
+            Mm(PAGE_HEIGHT - self.y - 0.5),
+        ));
+        self.layer.set_fill_color(BLACK);
+    }
+
+    /// A drawing fit into a frame on the current line. Drawings that can't be read leave the
+    /// frame empty rather than failing the whole report.
+    fn drawing(&self, png: &[u8], x: f32) {
+        let top = PAGE_HEIGHT - self.y;
+
+        self.layer.set_outline_color(LIGHT_GREY);
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_rect(
+            Rect::new(
+                Mm(x),
+                Mm(top - DRAWING_HEIGHT),
+                Mm(x + DRAWING_WIDTH),
+                Mm(top),
+            )
+            .with_mode(PaintMode::Stroke),
+        );
+
+        let Some(image) = thumbnail(png) else {
+            return;
+        };
+
+        let width = image.width() as f32 * 25.4 / DRAWING_DPI;
+        let height = image.height() as f32 * 25.4 / DRAWING_DPI;
+        Image::from_dynamic_image(&DynamicImage::ImageRgb8(image)).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(x + (DRAWING_WIDTH - width) / 2.0)),
+                translate_y: Some(Mm(top - DRAWING_HEIGHT + (DRAWING_HEIGHT - height) / 2.0)),
+                dpi: Some(DRAWING_DPI),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn finish(self) -> anyhow::Result<Vec<u8>> {
+        self.doc
+            .save_to_bytes()
+            .context("Failed to write out the report")
+    }
+}
+
+/// A drawing scaled down to fit its frame, with the transparent canvas it was drawn on turned
+/// white so it prints the way it looked.
+fn thumbnail(png: &[u8]) -> Option<RgbImage> {
+    let image = image_crate::load_from_memory_with_format(png, ImageFormat::Png)
+        .ok()?
+        .into_rgba8();
+
+    let max_width = (DRAWING_WIDTH / 25.4 * DRAWING_DPI) as u32;
+    let max_height = (DRAWING_HEIGHT / 25.4 * DRAWING_DPI) as u32;
+    let scale = f32::min(
+        max_width as f32 / image.width() as f32,
+        max_height as f32 / image.height() as f32,
+    )
+    .min(1.0);
+    let width = ((image.width() as f32 * scale) as u32).max(1);
+    let height = ((image.height() as f32 * scale) as u32).max(1);
+    let image = image_crate::imageops::resize(&image, width, height, FilterType::Triangle);
+
+    Some(RgbImage::from_fn(width, height, |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+        let over_white = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        Rgb([over_white(r), over_white(g), over_white(b)])
+    }))
+}
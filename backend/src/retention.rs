@@ -0,0 +1,322 @@
+//! Enforcing the retention policies of tests and organizations: deleting students' drawings and
+//! removing their names once they've been kept for as long as the strictest policy allows. Along
+//! with a student's name go their entry on the test's roster and the transcriptions of their
+//! answers, which can spell it out.
+//!
+//! A background job does it every `--retention-interval` hours, and records what it removed in
+//! `retention_purge`. Admins can see what would be removed beforehand through the API.
+
+use chrono::Utc;
+use shared::retention::{Purge, RetentionPolicy, TestRetention};
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// The roster entries of test `$1` for the students who registered before `$2` and still have
+/// their name, matched by the digest of their name, or by the name itself where neither is
+/// encrypted.
+const ROSTER_ENTRIES: &str = "SELECT roster.id FROM roster
+    JOIN result ON result.test_id = roster.test_id
+    WHERE roster.test_id = $1 AND result.registered_at < $2 AND result.name_purged_at IS NULL
+        AND (result.name_digest = roster.name_digest
+            OR (result.name_key_id IS NULL AND roster.name_key_id IS NULL
+                AND result.name = roster.name))";
+
+/// The answers to name questions of the students of test `$1` who registered before `$2` that
+/// have a transcription. Spelling words don't say who wrote them, so those are kept.
+const TRANSCRIBED: &str = "FROM response
+    JOIN result ON result.id = response.result_id
+    JOIN question ON question.id = response.question_id
+    WHERE result.test_id = $1 AND result.registered_at < $2 AND question.word IS NULL
+        AND (response.transcription IS NOT NULL OR response.suggested_transcription IS NOT NULL)";
+
+/// The tests a retention report or run covers.
+#[derive(Clone, Copy)]
+pub enum Tests {
+    All,
+    One(Uuid),
+    /// The tests of an organization's teachers, and of its schools' for districts.
+    Organization(Uuid),
+}
+
+#[derive(sqlx::FromRow)]
+struct Scope {
+    test_id: Uuid,
+    test_name: String,
+    teacher: String,
+    test_drawings: Option<i32>,
+    test_names: Option<i32>,
+    organization_drawings: Option<i32>,
+    organization_names: Option<i32>,
+    district_drawings: Option<i32>,
+    district_names: Option<i32>,
+}
+
+impl Scope {
+    fn policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            drawings_days: self.test_drawings,
+            names_days: self.test_names,
+        }
+    }
+
+    fn effective(&self) -> RetentionPolicy {
+        RetentionPolicy::strictest([
+            self.policy(),
+            RetentionPolicy {
+                drawings_days: self.organization_drawings,
+                names_days: self.organization_names,
+            },
+            RetentionPolicy {
+                drawings_days: self.district_drawings,
+                names_days: self.district_names,
+            },
+        ])
+    }
+}
+
+/// When students have to have registered before for their drawings and names to be removed now,
+/// if ever, in seconds since the Unix epoch.
+struct Cutoffs {
+    drawings: Option<i64>,
+    names: Option<i64>,
+}
+
+impl Cutoffs {
+    fn new(policy: RetentionPolicy, now: i64) -> Self {
+        let cutoff = |days: Option<i32>| days.map(|days| now - i64::from(days) * SECONDS_PER_DAY);
+
+        Cutoffs {
+            drawings: cutoff(policy.drawings_days),
+            names: cutoff(policy.names_days),
+        }
+    }
+}
+
+async fn scopes(db: &Db, tests: Tests) -> anyhow::Result<Vec<Scope>> {
+    const SELECT: &str = "SELECT test.id AS test_id, test.name AS test_name,
+            teacher.username AS teacher,
+            test.drawings_retention_days AS test_drawings,
+            test.names_retention_days AS test_names,
+            organization.drawings_retention_days AS organization_drawings,
+            organization.names_retention_days AS organization_names,
+            district.drawings_retention_days AS district_drawings,
+            district.names_retention_days AS district_names
+        FROM test
+        JOIN teacher ON teacher.id = test.teacher_id
+        LEFT JOIN organization ON organization.id = teacher.organization_id
+        LEFT JOIN organization district ON district.id = organization.parent_id";
+
+    let scopes = match tests {
+        Tests::All => with_pool!(db, pool => {
+            sqlx::query_as::<_, Scope>(&format!("{SELECT} ORDER BY test.name"))
+                .fetch_all(pool)
+                .await
+        })?,
+        Tests::One(test_id) => with_pool!(db, pool => {
+            sqlx::query_as::<_, Scope>(&format!("{SELECT} WHERE test.id = $1"))
+                .bind(test_id)
+                .fetch_all(pool)
+                .await
+        })?,
+        Tests::Organization(organization_id) => with_pool!(db, pool => {
+            sqlx::query_as::<_, Scope>(&format!(
+                "{SELECT} WHERE organization.id = $1 OR organization.parent_id = $1
+                ORDER BY teacher.username, test.name"
+            ))
+            .bind(organization_id)
+            .fetch_all(pool)
+            .await
+        })?,
+    };
+
+    Ok(scopes)
+}
+
+/// What enforcing the retention policies of `tests` would remove right now, without removing it.
+pub async fn report(db: &Db, tests: Tests) -> anyhow::Result<Vec<TestRetention>> {
+    let now = Utc::now().timestamp();
+    let mut report = Vec::new();
+
+    for scope in scopes(db, tests).await? {
+        let effective = scope.effective();
+        let cutoffs = Cutoffs::new(effective, now);
+
+        let drawings_due = match cutoffs.drawings {
+            Some(cutoff) => with_pool!(db, pool => {
+                sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM response
+                    JOIN result ON result.id = response.result_id
+                    WHERE result.test_id = $1 AND result.registered_at < $2
+                        AND response.drawing_purged_at IS NULL",
+                )
+                .bind(scope.test_id)
+                .bind(cutoff)
+                .fetch_one(pool)
+                .await
+            })?,
+            None => 0,
+        };
+
+        let (names_due, roster_entries_due, transcriptions_due) = match cutoffs.names {
+            Some(cutoff) => with_pool!(db, pool => {
+                sqlx::query_as::<_, (i64, i64, i64)>(&format!(
+                    "SELECT
+                        (SELECT COUNT(*) FROM result
+                        WHERE test_id = $1 AND registered_at < $2 AND name_purged_at IS NULL),
+                        (SELECT COUNT(*) FROM roster WHERE id IN ({ROSTER_ENTRIES})),
+                        (SELECT COUNT(*) {TRANSCRIBED})"
+                ))
+                .bind(scope.test_id)
+                .bind(cutoff)
+                .fetch_one(pool)
+                .await
+            })?,
+            None => (0, 0, 0),
+        };
+
+        report.push(TestRetention {
+            test_id: scope.test_id,
+            policy: scope.policy(),
+            test_name: scope.test_name,
+            teacher: scope.teacher,
+            effective,
+            drawings_due,
+            names_due,
+            roster_entries_due,
+            transcriptions_due,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Removes whatever is past its retention period from `tests`, recording and logging what was
+/// removed from each of them.
+pub async fn purge(db: &Db, tests: Tests) -> anyhow::Result<Vec<Purge>> {
+    let now = Utc::now().timestamp();
+    let mut purges = Vec::new();
+
+    for scope in scopes(db, tests).await? {
+        let cutoffs = Cutoffs::new(scope.effective(), now);
+        if cutoffs.drawings.is_none() && cutoffs.names.is_none() {
+            continue;
+        }
+
+        let (drawings, names, roster_entries, transcriptions) = with_pool!(db, pool => {
+            async {
+                let mut tx = pool.begin().await?;
+
+                // The answers stay, with what the teacher read in them, but not what was drawn
+                let drawings = match cutoffs.drawings {
+                    Some(cutoff) => sqlx::query(
+                        "UPDATE response SET drawing = $1, strokes = NULL, drawing_key_id = NULL,
+                            drawing_purged_at = $2
+                        WHERE drawing_purged_at IS NULL AND result_id IN (
+                            SELECT id FROM result WHERE test_id = $3 AND registered_at < $4
+                        )",
+                    )
+                    .bind(Vec::<u8>::new())
+                    .bind(now)
+                    .bind(scope.test_id)
+                    .bind(cutoff)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected(),
+                    None => 0,
+                };
+
+                // The student is only known by their pseudonym from then on, so they're taken off
+                // the roster first, while it can still be told which entry was theirs
+                let (names, roster_entries, transcriptions) = match cutoffs.names {
+                    Some(cutoff) => {
+                        let roster_entries = sqlx::query(&format!(
+                            "DELETE FROM roster WHERE id IN ({ROSTER_ENTRIES})"
+                        ))
+                        .bind(scope.test_id)
+                        .bind(cutoff)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+
+                        let transcriptions = sqlx::query(&format!(
+                            "UPDATE response SET transcription = NULL, suggested_transcription = NULL
+                            WHERE id IN (SELECT response.id {TRANSCRIBED})"
+                        ))
+                        .bind(scope.test_id)
+                        .bind(cutoff)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+
+                        let names = sqlx::query(
+                            "UPDATE result
+                            SET name = $1, name_key_id = NULL, name_digest = NULL,
+                                name_purged_at = $2
+                            WHERE test_id = $3 AND registered_at < $4 AND name_purged_at IS NULL",
+                        )
+                        .bind("")
+                        .bind(now)
+                        .bind(scope.test_id)
+                        .bind(cutoff)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+
+                        (names, roster_entries, transcriptions)
+                    }
+                    None => (0, 0, 0),
+                };
+
+                let counts = (
+                    drawings as i64,
+                    names as i64,
+                    roster_entries as i64,
+                    transcriptions as i64,
+                );
+                if counts != (0, 0, 0, 0) {
+                    sqlx::query(
+                        "INSERT INTO retention_purge
+                            (id, test_id, purged_at, drawings, names, roster_entries, transcriptions)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(scope.test_id)
+                    .bind(now)
+                    .bind(counts.0)
+                    .bind(counts.1)
+                    .bind(counts.2)
+                    .bind(counts.3)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+
+                tx.commit().await.map(|_| counts)
+            }
+            .await
+        })?;
+
+        if (drawings, names, roster_entries, transcriptions) != (0, 0, 0, 0) {
+            tracing::info!(
+                test_id = %scope.test_id,
+                drawings,
+                names,
+                roster_entries,
+                transcriptions,
+                "Purged student data past its retention period"
+            );
+            purges.push(Purge {
+                test_id: scope.test_id,
+                purged_at: now,
+                drawings,
+                names,
+                roster_entries,
+                transcriptions,
+            });
+        }
+    }
+
+    Ok(purges)
+}
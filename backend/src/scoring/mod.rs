@@ -0,0 +1,129 @@
+//! Suggesting what students wrote on the spelling questions, and how close it is to the word they
+//! were asked for, for teachers to accept or override from the dashboard.
+//!
+//! Suggestions come from a [`Scorer`]. The only one so far runs a handwriting recognizer installed
+//! on the server, see [`Recognizer`].
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use futures_util::future::BoxFuture;
+use shared::score::Stroke;
+use uuid::Uuid;
+
+use crate::db::{with_pool, Db};
+use crate::encryption::{Field, Keys};
+
+mod recognizer;
+
+pub use recognizer::Recognizer;
+
+/// A student's answer to a spelling question.
+pub struct Answer {
+    /// The word the student was asked to spell.
+    pub word: String,
+    /// The drawing as a PNG image.
+    pub drawing: Vec<u8>,
+    /// How it was drawn, if the student's device recorded it.
+    pub strokes: Vec<Stroke>,
+}
+
+/// What a scorer made of an answer.
+pub struct Suggestion {
+    pub transcription: String,
+    /// From 0 for nothing like the word to 1 for spelled right.
+    pub score: f64,
+}
+
+pub trait Scorer: Send + Sync {
+    /// Suggests what `answer` says and how well it's spelled, unless nothing could be made out.
+    fn score<'a>(&'a self, answer: &'a Answer)
+        -> BoxFuture<'a, anyhow::Result<Option<Suggestion>>>;
+}
+
+/// The scorer responses are handed to as they come in, if there is one.
+#[derive(Clone, Default)]
+pub struct Scoring {
+    scorer: Option<Arc<dyn Scorer>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredAnswer {
+    word: Option<String>,
+    drawing: Vec<u8>,
+    strokes: Option<String>,
+    drawing_key_id: Option<String>,
+}
+
+impl Scoring {
+    pub fn new(scorer: impl Scorer + 'static) -> Self {
+        Scoring {
+            scorer: Some(Arc::new(scorer)),
+        }
+    }
+
+    /// Suggests a transcription and score for a stored response and saves them with it. Responses
+    /// to questions without a word to spell are left alone.
+    pub async fn suggest(&self, db: &Db, keys: &Keys, response_id: Uuid) -> anyhow::Result<()> {
+        let Some(scorer) = &self.scorer else {
+            return Ok(());
+        };
+
+        let stored = with_pool!(db, pool => {
+            sqlx::query_as::<_, StoredAnswer>(
+                "SELECT question.word, response.drawing, response.strokes, response.drawing_key_id
+                FROM response
+                JOIN question ON question.id = response.question_id
+                WHERE response.id = $1",
+            )
+            .bind(response_id)
+            .fetch_optional(pool)
+            .await
+        })?;
+
+        let Some(StoredAnswer {
+            word: Some(word),
+            drawing,
+            strokes,
+            drawing_key_id,
+        }) = stored
+        else {
+            return Ok(());
+        };
+
+        let key_id = drawing_key_id.as_deref();
+        let drawing = keys.open(Field::Drawing, response_id, key_id, drawing)?;
+        let strokes = match strokes {
+            Some(strokes) => {
+                let strokes = keys.open_text(Field::Strokes, response_id, key_id, strokes)?;
+                serde_json::from_str(&strokes).context("The stored strokes are not valid")?
+            }
+            None => Vec::new(),
+        };
+
+        let answer = Answer {
+            word,
+            drawing,
+            strokes,
+        };
+
+        let Some(suggestion) = scorer.score(&answer).await? else {
+            return Ok(());
+        };
+
+        with_pool!(db, pool => {
+            sqlx::query(
+                "UPDATE response SET suggested_transcription = $1, suggested_score = $2
+                WHERE id = $3",
+            )
+            .bind(suggestion.transcription)
+            .bind(suggestion.score)
+            .bind(response_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })?;
+
+        Ok(())
+    }
+}
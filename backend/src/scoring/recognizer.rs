@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::ErrorKind;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use shared::score::spelling_score;
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use super::{Answer, Scorer, Suggestion};
+
+/// How long the recognizer gets to read one drawing.
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The descriptor the recognizer finds the strokes open as.
+#[cfg(unix)]
+const STROKES_FD: libc::c_int = 3;
+
+/// Reads drawings with a handwriting recognizer installed on the server, so they never leave it.
+///
+/// The recognizer is any command that reads a PNG image on stdin and prints the text in it on
+/// stdout, like `tesseract stdin stdout --psm 8`. Recognizers that can make use of more are given
+/// the word the student was asked for in the `FNK_WORD` environment variable, and, when the
+/// student's device recorded them, the path of a JSON file with the strokes in `FNK_STROKES`. That
+/// file is removed before the recognizer even starts, and only reachable through the descriptor it
+/// inherits, so the strokes are never left on disk.
+pub struct Recognizer {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Recognizer {
+    /// Splits `command` into the program and its arguments on whitespace.
+    pub fn new(command: &str) -> anyhow::Result<Self> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow!("The recognizer command is empty"))?;
+
+        Ok(Recognizer {
+            program,
+            args: words.collect(),
+        })
+    }
+
+    async fn recognize(&self, answer: &Answer) -> anyhow::Result<Option<Suggestion>> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).env("FNK_WORD", &answer.word);
+
+        // Only needs to stay open until the recognizer has its own copy
+        let strokes = match answer.strokes.is_empty() {
+            true => None,
+            false => Some(strokes_file(answer).await?),
+        };
+        if let Some(strokes) = &strokes {
+            hand_over(&mut command, strokes);
+        }
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start the recognizer `{}`", self.program))?;
+        drop(strokes);
+
+        // Written while the output is read, so a recognizer that fills its stdout before reading
+        // all of the drawing can't hold it up past the timeout
+        let mut stdin = child.stdin.take().context("The recognizer has no stdin")?;
+        let write = async move {
+            match stdin.write_all(&answer.drawing).await {
+                // Recognizers that go by the strokes alone may exit without reading the drawing
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => {
+                    Err(e).context("Failed to hand the drawing to the recognizer")
+                }
+                _ => Ok(()),
+            }
+        };
+
+        let (written, output) = tokio::time::timeout(TIMEOUT, async {
+            tokio::join!(write, child.wait_with_output())
+        })
+        .await
+        .context("The recognizer took too long")?;
+        written?;
+        let output = output.context("Failed to run the recognizer")?;
+
+        if !output.status.success() {
+            bail!(
+                "The recognizer failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let transcription = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if transcription.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Suggestion {
+            score: spelling_score(&answer.word, &transcription),
+            transcription,
+        }))
+    }
+}
+
+/// Writes the strokes of an answer to a file that has no name, so it's gone as soon as it's closed.
+async fn strokes_file(answer: &Answer) -> anyhow::Result<File> {
+    let file = tempfile::tempfile().context("Failed to create a file for the strokes")?;
+    let mut file = fs::File::from_std(file);
+
+    file.write_all(&serde_json::to_vec(&answer.strokes)?)
+        .await
+        .context("Failed to write the strokes")?;
+    file.rewind().await?;
+
+    Ok(file.into_std().await)
+}
+
+/// Has the recognizer inherit `strokes` as [`STROKES_FD`], and tells it where to find them.
+#[cfg(unix)]
+fn hand_over(command: &mut Command, strokes: &File) {
+    use std::os::fd::AsRawFd;
+
+    let fd = strokes.as_raw_fd();
+    command.env("FNK_STROKES", format!("/dev/fd/{STROKES_FD}"));
+
+    // SAFETY: Only async-signal-safe calls are made between the fork and the exec. `dup2` clears
+    // close-on-exec on the copy, which has to be done by hand when the file already has the number
+    unsafe {
+        command.pre_exec(move || {
+            let copied = match fd {
+                STROKES_FD => libc::fcntl(fd, libc::F_SETFD, 0),
+                _ => libc::dup2(fd, STROKES_FD),
+            };
+
+            match copied {
+                -1 => Err(std::io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        });
+    }
+}
+
+/// Elsewhere there's no descriptor to hand the strokes over with, so the recognizer goes without.
+#[cfg(not(unix))]
+fn hand_over(_command: &mut Command, _strokes: &File) {}
+
+impl Scorer for Recognizer {
+    fn score<'a>(
+        &'a self,
+        answer: &'a Answer,
+    ) -> BoxFuture<'a, anyhow::Result<Option<Suggestion>>> {
+        self.recognize(answer).boxed()
+    }
+}
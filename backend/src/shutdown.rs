@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+use tokio_util::task::TaskTracker;
+
+/// Coordinates a graceful shutdown between the HTTP server and any background work.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tasks: TaskTracker,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Triggers the shutdown once the process receives SIGINT or SIGTERM.
+    pub fn listen(&self) {
+        let token = self.token.clone();
+        tokio::spawn(async move {
+            signal().await;
+            tracing::info!("Received shutdown signal, no longer accepting connections");
+            token.cancel();
+        });
+    }
+
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once the shutdown has been triggered.
+    pub fn triggered(&self) -> WaitForCancellationFutureOwned {
+        self.token.clone().cancelled_owned()
+    }
+
+    /// Runs `task` in the background, giving it until the shutdown timeout to finish.
+    pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        self.tasks.spawn(task);
+    }
+
+    /// Stops accepting background tasks and waits up to `timeout` for the pending ones to finish.
+    pub async fn wait_for_tasks(&self, timeout: Duration) {
+        self.tasks.close();
+
+        if !self.tasks.is_empty() {
+            tracing::info!(
+                "Waiting for {} background task(s) to finish",
+                self.tasks.len()
+            );
+        }
+
+        if tokio::time::timeout(timeout, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "{} background task(s) didn't finish within {timeout:?}, abandoning them",
+                self.tasks.len()
+            );
+        }
+    }
+}
+
+async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install the SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install the SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -3,8 +3,9 @@ use argon2::{
     password_hash, password_hash::SaltString, Argon2, PasswordHash, PasswordHasher,
     PasswordVerifier,
 };
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use tokio::task;
+use uuid::Uuid;
 
 // Setup the command line interface with clap.
 #[derive(Parser, Debug)]
@@ -14,6 +15,15 @@ pub struct Opt {
     #[clap(short = 'l', long = "log", default_value = "debug")]
     pub(crate) log_level: String,
 
+    /// set the format logs are written in
+    #[clap(long = "log-format", value_enum, default_value = "text")]
+    pub(crate) log_format: LogFormat,
+
+    /// export traces to the OpenTelemetry collector listening at this endpoint
+    #[cfg(feature = "otel")]
+    #[clap(long = "otlp-endpoint")]
+    pub(crate) otlp_endpoint: Option<String>,
+
     /// set the listen addr
     #[clap(short = 'a', long = "addr", default_value = "::1")]
     pub(crate) addr: String,
@@ -25,6 +35,44 @@ pub struct Opt {
     /// set the directory where static files are to be found
     #[clap(long = "static-dir", default_value = "../dist")]
     pub(crate) static_dir: String,
+
+    /// set the directory files uploaded through the API, like question recordings, are stored in
+    #[clap(long = "media-dir", default_value = "./media")]
+    pub(crate) media_dir: String,
+
+    /// set the public URL the frontend is served from, defaults to the page's origin
+    #[clap(long = "public-url")]
+    pub(crate) public_url: Option<String>,
+
+    /// set the base URL of the API, defaults to `<public-url>/v1`
+    #[clap(long = "api-url")]
+    pub(crate) api_url: Option<String>,
+
+    /// set the command of a local handwriting recognizer that suggests what students wrote on the
+    /// spelling questions, e.g. `tesseract stdin stdout --psm 8`
+    #[clap(long = "recognizer")]
+    pub(crate) recognizer: Option<String>,
+
+    /// set how many hours apart student data past its retention period is purged, at least 1
+    #[clap(long = "retention-interval", default_value = "24", value_parser = clap::value_parser!(u64).range(1..))]
+    pub(crate) retention_interval: u64,
+
+    /// set the id of a teacher who administers the server itself, like its background jobs, as
+    /// found in the `teacher` table; may be given more than once
+    #[clap(long = "admin")]
+    pub(crate) admins: Vec<Uuid>,
+
+    /// set how many seconds to wait for in-flight requests and background tasks when shutting down
+    #[clap(long = "shutdown-timeout", default_value = "30")]
+    pub(crate) shutdown_timeout: u64,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum LogFormat {
+    /// Human readable lines, for development
+    Text,
+    /// One JSON object per line, for log aggregators
+    Json,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,9 @@
+use super::{get, handle_response, APIError};
+use shared::audit::{AuditEntry, AuditFilter};
+
+/// The latest entries of the audit log the teacher administers, narrowed down by `filter`.
+pub async fn get_audit_log(filter: &AuditFilter) -> Result<Vec<AuditEntry>, APIError> {
+    let response = get("/audit").query(filter).send().await?;
+
+    handle_response(response).await
+}
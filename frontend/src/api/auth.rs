@@ -1,37 +1,34 @@
-use super::{handle_response_unit, APIError, LoginTeacher, RegisterTeacher, API_URL};
+use super::{handle_response_unit, post, APIError};
+use shared::teacher::{LoginTeacher, RegisterTeacher};
+use validator::Validate;
 
 pub async fn register_teacher(email: &str, username: &str, password: &str) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/teacher/register"))
-        .json(&RegisterTeacher {
-            email: email.to_string(),
-            username: username.to_string(),
-            password: password.to_string(),
-        })
-        .send()
-        .await?;
+    let req = RegisterTeacher {
+        email: email.to_string(),
+        username: username.to_string(),
+        password: password.to_string(),
+    };
+    req.validate()?;
+
+    let response = post("/teacher/register").json(&req).send().await?;
 
     handle_response_unit(response).await
 }
 
 pub async fn login_teacher(email: &str, password: &str) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/teacher/login"))
-        .json(&LoginTeacher {
-            email: email.to_string(),
-            password: password.to_string(),
-        })
-        .send()
-        .await?;
+    let req = LoginTeacher {
+        email: email.to_string(),
+        password: password.to_string(),
+    };
+    req.validate()?;
+
+    let response = post("/teacher/login").json(&req).send().await?;
 
     handle_response_unit(response).await
 }
 
 pub async fn logout_teacher() -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/teacher/logout"))
-        .send()
-        .await?;
+    let response = post("/teacher/logout").send().await?;
 
     handle_response_unit(response).await
 }
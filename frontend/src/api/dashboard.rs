@@ -1,57 +1,117 @@
-use super::{handle_response, handle_response_unit, APIError, CreateTest, API_URL};
-use serde::Deserialize;
-
-#[derive(Deserialize, PartialEq)]
-pub struct Test {
-    pub id: String,
-    pub teacher_id: String,
-    pub name: String,
-    pub closed: bool,
-}
-
-#[derive(Deserialize, PartialEq)]
-pub struct StudentResult {
-    pub id: String,
-    pub test_id: String,
-    pub name: String,
-    pub level: i32,
-}
-
-pub async fn create_test(name: &str) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/test"))
-        .json(&CreateTest {
-            name: name.to_string(),
-        })
+use super::{
+    api_url, get, handle_response, handle_response_bytes, handle_response_unit, post, APIError,
+};
+use shared::consent::{Consent, RecordConsent};
+use shared::i18n::Language;
+use shared::score::{ScoreResponse, ScoredResponse};
+use shared::test::{CreateTest, SetPin, StudentResult, Test, TestSettings};
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn create_test(name: &str, language: Language) -> Result<(), APIError> {
+    let req = CreateTest {
+        name: name.to_string(),
+        language,
+    };
+    req.validate()?;
+
+    let response = post("/test").json(&req).send().await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn inverse_closed(id: Uuid) -> Result<(), APIError> {
+    let response = post(&format!("/test/{id}/manage")).send().await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn get_results(id: Uuid) -> Result<Vec<StudentResult>, APIError> {
+    let response = get(&format!("/test/{id}/manage")).send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn get_tests() -> Result<Vec<Test>, APIError> {
+    let response = get("/test").send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn update_settings(id: Uuid, settings: &TestSettings) -> Result<(), APIError> {
+    settings.validate()?;
+
+    let response = post(&format!("/test/{id}/settings"))
+        .json(settings)
         .send()
         .await?;
 
     handle_response_unit(response).await
 }
 
-pub async fn inverse_closed(id: &str) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/test/{id}/manage"))
+/// Sets the PIN students have to enter to register, or removes it.
+pub async fn set_pin(id: Uuid, pin: Option<String>) -> Result<(), APIError> {
+    let req = SetPin { pin };
+    req.validate()?;
+
+    let response = post(&format!("/test/{id}/pin")).json(&req).send().await?;
+
+    handle_response_unit(response).await
+}
+
+/// A printable PDF report of the whole class, or of one student when `result_id` is given.
+pub async fn get_report(id: Uuid, result_id: Option<Uuid>) -> Result<Vec<u8>, APIError> {
+    let path = match result_id {
+        Some(result_id) => format!("/test/{id}/students/{result_id}/report"),
+        None => format!("/test/{id}/report"),
+    };
+    let response = get(&path).send().await?;
+
+    handle_response_bytes(response).await
+}
+
+/// Records the consent a student's parents signed.
+pub async fn record_consent(id: Uuid, result_id: Uuid, consent: Consent) -> Result<(), APIError> {
+    let response = post(&format!("/test/{id}/students/{result_id}/consent"))
+        .json(&RecordConsent { consent })
         .send()
         .await?;
 
     handle_response_unit(response).await
 }
 
-pub async fn get_results(id: &str) -> Result<Vec<StudentResult>, APIError> {
-    let response = reqwest::Client::new()
-        .get(format!("{API_URL}/test/{id}/manage"))
+/// The answers of the students with research consent, as the JSON document the server sends.
+pub async fn get_research_export(id: Uuid) -> Result<Vec<u8>, APIError> {
+    let response = get(&format!("/test/{id}/export")).send().await?;
+
+    handle_response_bytes(response).await
+}
+
+/// A student's answers to the spelling questions, with their suggested and given scores.
+pub async fn get_responses(id: Uuid, result_id: Uuid) -> Result<Vec<ScoredResponse>, APIError> {
+    let response = get(&format!("/test/{id}/students/{result_id}/responses"))
         .send()
         .await?;
 
     handle_response(response).await
 }
 
-pub async fn get_tests() -> Result<Vec<Test>, APIError> {
-    let response = reqwest::Client::new()
-        .get(format!("{API_URL}/test"))
+pub async fn score_response(
+    id: Uuid,
+    response_id: Uuid,
+    req: &ScoreResponse,
+) -> Result<(), APIError> {
+    req.validate()?;
+
+    let response = post(&format!("/test/{id}/responses/{response_id}/score"))
+        .json(req)
         .send()
         .await?;
 
-    handle_response(response).await
+    handle_response_unit(response).await
+}
+
+/// Where the drawing a student made for one of their answers is loaded from.
+pub fn drawing_url(id: Uuid, response_id: Uuid) -> String {
+    format!("{}/test/{id}/responses/{response_id}/drawing", api_url())
 }
@@ -0,0 +1,59 @@
+use super::{delete, get, handle_response, handle_response_unit, post, APIError};
+use shared::i18n::Language;
+use shared::test::{KioskSession, RegisterStudent, RosterEntry};
+use uuid::Uuid;
+use validator::Validate;
+
+pub async fn get_roster(id: Uuid) -> Result<Vec<RosterEntry>, APIError> {
+    let response = get(&format!("/test/{id}/roster")).send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn add_to_roster(
+    id: Uuid,
+    name: &str,
+    language: Option<Language>,
+) -> Result<(), APIError> {
+    let req = RegisterStudent {
+        name: name.to_string(),
+        language,
+        pin: None,
+    };
+    req.validate()?;
+
+    let response = post(&format!("/test/{id}/roster"))
+        .json(&req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn remove_from_roster(id: Uuid, entry_id: Uuid) -> Result<(), APIError> {
+    let response = delete(&format!("/test/{id}/roster/{entry_id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// Starts the session of the student on the roster as `entry_id` on this device, which the
+/// teacher is logged in on.
+pub async fn start_session(id: Uuid, entry_id: Uuid) -> Result<KioskSession, APIError> {
+    let response = post(&format!("/test/{id}/roster/{entry_id}/kiosk"))
+        .send()
+        .await?;
+
+    handle_response(response).await
+}
+
+/// Gets a new token for the session of the student whose result is `result_id`, after the one it
+/// was started with expired.
+pub async fn renew_session(id: Uuid, result_id: Uuid) -> Result<KioskSession, APIError> {
+    let response = post(&format!("/test/{id}/kiosk/{result_id}"))
+        .send()
+        .await?;
+
+    handle_response(response).await
+}
@@ -0,0 +1,125 @@
+use super::{api_url, get, handle_response, handle_response_unit, post, with_token, APIError};
+use serde::de::DeserializeOwned;
+use shared::live::{
+    CanvasFrame, Command, ReportProgress, SendCommand, SessionControl, StudentProgress,
+    CANVAS_EVENT, PROGRESS_EVENT,
+};
+use uuid::Uuid;
+use validator::Validate;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Event, EventSource, EventSourceInit, MessageEvent};
+
+pub async fn report_progress(
+    id: Uuid,
+    req: &ReportProgress,
+    token: Option<&str>,
+) -> Result<(), APIError> {
+    req.validate()?;
+
+    let response = with_token(post(&format!("/test/{id}/progress")), token)
+        .json(req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// What the teacher wants the student's device to do. If the device already has `version`, the
+/// server waits a while for it to change before answering.
+pub async fn poll_control(
+    id: Uuid,
+    version: Option<u64>,
+    token: Option<&str>,
+) -> Result<SessionControl, APIError> {
+    let mut builder = get(&format!("/test/{id}/control"));
+    if let Some(version) = version {
+        builder = builder.query(&[("version", version)]);
+    }
+
+    let response = with_token(builder, token).send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn control_student(id: Uuid, result_id: Uuid, command: Command) -> Result<(), APIError> {
+    let response = post(&format!("/test/{id}/students/{result_id}/control"))
+        .json(&SendCommand { command })
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn control_all(id: Uuid, command: Command) -> Result<(), APIError> {
+    let response = post(&format!("/test/{id}/students/control"))
+        .json(&SendCommand { command })
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// A connection to a test's live view, closed once it's dropped.
+pub struct LiveFeed {
+    source: EventSource,
+    _listeners: Vec<Closure<dyn FnMut(Event)>>,
+}
+
+impl Drop for LiveFeed {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
+/// Follows the students taking a test, and the canvas of the one being `watch`ed. The browser
+/// reconnects by itself after a dropped connection, `on_closed` is only called once it gives up.
+pub fn watch_test(
+    id: Uuid,
+    watch: Option<Uuid>,
+    on_progress: impl FnMut(StudentProgress) + 'static,
+    on_canvas: impl FnMut(CanvasFrame) + 'static,
+    mut on_closed: impl FnMut() + 'static,
+) -> Result<LiveFeed, APIError> {
+    let url = match watch {
+        Some(watch) => format!("{}/test/{id}/live?watch={watch}", api_url()),
+        None => format!("{}/test/{id}/live", api_url()),
+    };
+
+    let mut init = EventSourceInit::new();
+    init.with_credentials(true);
+    let source = EventSource::new_with_event_source_init_dict(&url, &init)
+        .map_err(|_| APIError::Internal("The live view couldn't be opened".to_string()))?;
+
+    let mut listeners = vec![listener(on_progress), listener(on_canvas)];
+    let events = [PROGRESS_EVENT, CANVAS_EVENT];
+
+    let closed = source.clone();
+    listeners.push(Closure::new(move |_: Event| {
+        if closed.ready_state() == EventSource::CLOSED {
+            on_closed();
+        }
+    }));
+
+    for (event, listener) in events.into_iter().chain(["error"]).zip(&listeners) {
+        let _ = source.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+    }
+
+    Ok(LiveFeed {
+        source,
+        _listeners: listeners,
+    })
+}
+
+fn listener<T: DeserializeOwned>(
+    mut callback: impl FnMut(T) + 'static,
+) -> Closure<dyn FnMut(Event)> {
+    Closure::new(move |event: Event| {
+        let data = event
+            .dyn_ref::<MessageEvent>()
+            .and_then(|message| message.data().as_string());
+
+        if let Some(value) = data.and_then(|data| serde_json::from_str(&data).ok()) {
+            callback(value);
+        }
+    })
+}
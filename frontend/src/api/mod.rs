@@ -1,59 +1,186 @@
+pub mod audit;
 pub mod auth;
 pub mod dashboard;
+pub mod kiosk;
+pub mod live;
+pub mod organization;
+pub mod outbox;
+pub mod question;
+pub mod retention;
+pub mod sharing;
 pub mod test;
-use reqwest::Response;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use reqwest::{header::ACCEPT_LANGUAGE, Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Deserialize};
+use shared::error::{validation_messages, ErrorCode, ErrorResponse};
+use std::cell::OnceCell;
 use thiserror::Error;
 
-// API internal types for creating / parsing JSON requests & responses
+use crate::i18n;
+use validator::ValidationErrors;
 
-const API_URL: &'static str = "http://localhost:8080/v1";
+// Where the API and the public site live, discovered once at startup.
+
+struct Endpoints {
+    api_url: String,
+    public_url: String,
+}
+
+#[derive(Deserialize, Default)]
+struct ClientConfig {
+    api_url: Option<String>,
+    public_url: Option<String>,
+}
+
+thread_local! {
+    static ENDPOINTS: OnceCell<Endpoints> = const { OnceCell::new() };
+    static CLIENT: reqwest::Client = reqwest::Client::new();
+}
+
+/// Loads `/config.json` from the origin the app was served from, falling back to that
+/// same origin for anything the config doesn't specify.
+pub async fn load_config() {
+    let origin = web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default();
+
+    let config = match CLIENT
+        .with(|client| client.clone())
+        .get(format!("{origin}/config.json"))
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => {
+            response.json::<ClientConfig>().await.unwrap_or_default()
+        }
+        _ => ClientConfig::default(),
+    };
+
+    let public_url = config.public_url.unwrap_or(origin);
+    let api_url = config.api_url.unwrap_or_else(|| format!("{public_url}/v1"));
+
+    ENDPOINTS.with(|endpoints| {
+        let _ = endpoints.set(Endpoints {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        });
+    });
+}
+
+/// The URL the app is publicly reachable at, used for links handed out to students.
+pub fn public_url() -> String {
+    ENDPOINTS.with(|endpoints| {
+        endpoints
+            .get()
+            .map(|endpoints| endpoints.public_url.clone())
+            .unwrap_or_default()
+    })
+}
+
+/// Where a question's image or recording is served from.
+pub fn media_url(path: &str) -> String {
+    format!("{}/{path}", public_url())
+}
+
+fn api_url() -> String {
+    ENDPOINTS.with(|endpoints| {
+        endpoints
+            .get()
+            .map(|endpoints| endpoints.api_url.clone())
+            .unwrap_or_else(|| "/v1".to_string())
+    })
+}
+
+// Every call goes through the same client, and always sends our cookies along.
+
+fn request(method: Method, path: &str) -> RequestBuilder {
+    let builder = CLIENT
+        .with(|client| client.request(method, format!("{}{path}", api_url())))
+        .header(ACCEPT_LANGUAGE, i18n::current().code());
+
+    #[cfg(target_arch = "wasm32")]
+    let builder = builder.fetch_credentials_include();
+
+    builder
+}
+
+fn get(path: &str) -> RequestBuilder {
+    request(Method::GET, path)
+}
+
+fn post(path: &str) -> RequestBuilder {
+    request(Method::POST, path)
+}
+
+fn delete(path: &str) -> RequestBuilder {
+    request(Method::DELETE, path)
+}
+
+// Sessions started from a kiosk are authenticated by their token rather than a cookie.
+fn with_token(builder: RequestBuilder, token: Option<&str>) -> RequestBuilder {
+    match token {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    }
+}
 
 #[derive(Debug, Error)]
 pub enum APIError {
-    #[error("An unexpected error occurred when trying to communicate with the server")]
+    #[error(
+        "{}",
+        i18n::t("An unexpected error occurred when trying to communicate with the server")
+    )]
     ClientFailure(#[from] reqwest::Error),
     #[error("{0}")]
     ServerResponse(String),
+    #[error("{0}")]
+    Internal(String),
     #[error("{0:?}")]
     Validation(Vec<String>),
     #[error("{0}")]
     Authorization(String),
+    #[error("{0}")]
+    Paused(String),
 }
 
-#[derive(Deserialize, Debug)]
-struct ErrorResponse {
-    message: String,
-    errors: Option<Vec<String>>,
-    auth_error: bool,
-}
-
-#[derive(Serialize)]
-struct RegisterTeacher {
-    email: String,
-    username: String,
-    password: String,
-}
-
-#[derive(Serialize)]
-struct LoginTeacher {
-    email: String,
-    password: String,
+// Requests are validated with the same rules as the backend before they're ever sent.
+impl From<ValidationErrors> for APIError {
+    fn from(errors: ValidationErrors) -> Self {
+        APIError::Validation(validation_messages(&errors, i18n::current()))
+    }
 }
 
-#[derive(Serialize)]
-struct RegisterStudent {
-    name: String,
+impl From<ErrorResponse> for APIError {
+    fn from(resp: ErrorResponse) -> Self {
+        match (resp.code, resp.errors) {
+            (ErrorCode::Unauthorized, _) => APIError::Authorization(resp.message),
+            (ErrorCode::Paused, _) => APIError::Paused(resp.message),
+            (ErrorCode::Validation, Some(errors)) => APIError::Validation(errors),
+            (ErrorCode::Internal, _) => {
+                APIError::Internal(with_request_id(resp.message, resp.request_id))
+            }
+            _ => APIError::ServerResponse(with_request_id(resp.message, resp.request_id)),
+        }
+    }
 }
 
-#[derive(Serialize)]
-struct CreateTest {
-    name: String,
+// Quote the request id so a report can be tied back to the server's logs
+fn with_request_id(message: String, request_id: Option<String>) -> String {
+    match request_id {
+        Some(request_id) => format!("{message} ({}: {request_id})", i18n::t("request ID")),
+        None => message,
+    }
 }
 
-#[derive(Serialize)]
-struct SetLevel {
-    level: i32,
+impl APIError {
+    /// Whether sending the same request again later might succeed, i.e. the server couldn't be
+    /// reached, failed on its end or is waiting for the teacher to resume the session, rather than
+    /// rejecting the request itself.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            APIError::ClientFailure(_) | APIError::Internal(_) | APIError::Paused(_)
+        )
+    }
 }
 
 // Utility functions to handle responses.
@@ -62,28 +189,21 @@ struct SetLevel {
 
 async fn handle_response<T: DeserializeOwned>(response: Response) -> Result<T, APIError> {
     match response.status().is_success() {
-        false => {
-            let resp = response.json::<ErrorResponse>().await?;
-            match (resp.errors, resp.auth_error) {
-                (_, true) => Err(APIError::Authorization(resp.message)),
-                (Some(errors), false) => Err(APIError::Validation(errors)),
-                (None, false) => Err(APIError::ServerResponse(resp.message)),
-            }
-        }
+        false => Err(response.json::<ErrorResponse>().await?.into()),
         _ => Ok(response.json::<T>().await?),
     }
 }
 
 async fn handle_response_unit(response: Response) -> Result<(), APIError> {
     match response.status().is_success() {
-        false => {
-            let resp = response.json::<ErrorResponse>().await?;
-            match (resp.errors, resp.auth_error) {
-                (_, true) => Err(APIError::Authorization(resp.message)),
-                (Some(errors), false) => Err(APIError::Validation(errors)),
-                (None, false) => Err(APIError::ServerResponse(resp.message)),
-            }
-        }
+        false => Err(response.json::<ErrorResponse>().await?.into()),
         _ => Ok(()),
     }
 }
+
+async fn handle_response_bytes(response: Response) -> Result<Vec<u8>, APIError> {
+    match response.status().is_success() {
+        false => Err(response.json::<ErrorResponse>().await?.into()),
+        _ => Ok(response.bytes().await?.to_vec()),
+    }
+}
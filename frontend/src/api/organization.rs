@@ -0,0 +1,134 @@
+use super::{delete, get, handle_response, handle_response_unit, post, APIError};
+use shared::organization::{
+    AddMember, CreateOrganization, CreateQuestion, Invitation, Member, Organization,
+    OrganizationKind, OrganizationSummary,
+};
+use shared::question::Question;
+use shared::test::TestSettings;
+use uuid::Uuid;
+use validator::Validate;
+
+/// The teacher's organization, followed by its schools if it's a district they administer.
+pub async fn get_organizations() -> Result<Vec<Organization>, APIError> {
+    let response = get("/organization").send().await?;
+
+    handle_response(response).await
+}
+
+/// Creates an organization the teacher becomes the admin of, or a school of the district
+/// `parent_id`.
+pub async fn create_organization(
+    name: &str,
+    kind: OrganizationKind,
+    parent_id: Option<Uuid>,
+) -> Result<(), APIError> {
+    let req = CreateOrganization {
+        name: name.trim().to_string(),
+        kind,
+        parent_id,
+    };
+    req.validate()?;
+
+    let response = post("/organization").json(&req).send().await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn get_members(id: Uuid) -> Result<Vec<Member>, APIError> {
+    let response = get(&format!("/organization/{id}/members")).send().await?;
+
+    handle_response(response).await
+}
+
+/// Invites the teacher registered with `email` to an organization, or changes whether they
+/// administer it.
+pub async fn add_member(id: Uuid, email: &str, admin: bool) -> Result<(), APIError> {
+    let req = AddMember {
+        email: email.trim().to_string(),
+        admin,
+    };
+    req.validate()?;
+
+    let response = post(&format!("/organization/{id}/members"))
+        .json(&req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn remove_member(id: Uuid, teacher_id: Uuid) -> Result<(), APIError> {
+    let response = delete(&format!("/organization/{id}/members/{teacher_id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// The organizations the teacher was invited to.
+pub async fn get_invitations() -> Result<Vec<Invitation>, APIError> {
+    let response = get("/organization/invitations").send().await?;
+
+    handle_response(response).await
+}
+
+/// Joins the organization `id`, declining the teacher's other invitations.
+pub async fn accept_invitation(id: Uuid) -> Result<(), APIError> {
+    let response = post(&format!("/organization/invitations/{id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn decline_invitation(id: Uuid) -> Result<(), APIError> {
+    let response = delete(&format!("/organization/invitations/{id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// Sets what the tests the organization's teachers create start with.
+pub async fn update_settings(id: Uuid, settings: &TestSettings) -> Result<(), APIError> {
+    settings.validate()?;
+
+    let response = post(&format!("/organization/{id}/settings"))
+        .json(settings)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn get_summary(id: Uuid) -> Result<OrganizationSummary, APIError> {
+    let response = get(&format!("/organization/{id}/summary")).send().await?;
+
+    handle_response(response).await
+}
+
+/// The organization's own questions, asked instead of the shared bank's.
+pub async fn get_questions(id: Uuid) -> Result<Vec<Question>, APIError> {
+    let response = get(&format!("/organization/{id}/questions")).send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn create_question(id: Uuid, req: CreateQuestion) -> Result<(), APIError> {
+    req.validate()?;
+
+    let response = post(&format!("/organization/{id}/questions"))
+        .json(&req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn remove_question(id: Uuid, question_id: Uuid) -> Result<(), APIError> {
+    let response = delete(&format!("/organization/{id}/questions/{question_id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
@@ -0,0 +1,216 @@
+//! Submissions that have to reach the server eventually. They're kept in local storage until they
+//! do, so a dropped connection or a reloaded page doesn't lose a child's answers.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use gloo_timers::future::sleep;
+use serde::{Deserialize, Serialize};
+use shared::test::SubmitResponse;
+use uuid::Uuid;
+
+use super::{kiosk, test, APIError};
+
+const STORAGE_KEY: &str = "fnk.outbox";
+const TICK: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Submission {
+    Response {
+        test_id: Uuid,
+        response: SubmitResponse,
+        /// The session's token when it was started from a kiosk, the cookie is used otherwise.
+        #[serde(default)]
+        token: Option<String>,
+        /// The session's result when it was started from a kiosk, to renew its token with.
+        #[serde(default)]
+        result_id: Option<Uuid>,
+    },
+    Level {
+        test_id: Uuid,
+        level: i32,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        result_id: Option<Uuid>,
+    },
+}
+
+impl Submission {
+    async fn send(&self) -> Result<(), APIError> {
+        match self {
+            Submission::Response {
+                test_id,
+                response,
+                token,
+                ..
+            } => test::submit_response(*test_id, response, token.as_deref()).await,
+            Submission::Level {
+                test_id,
+                level,
+                token,
+                ..
+            } => test::set_level(*test_id, *level, token.as_deref()).await,
+        }
+    }
+
+    /// The test, token and result of the kiosk session it was made in.
+    fn session(&self) -> (Uuid, Option<&str>, Option<Uuid>) {
+        match self {
+            Submission::Response {
+                test_id,
+                token,
+                result_id,
+                ..
+            }
+            | Submission::Level {
+                test_id,
+                token,
+                result_id,
+                ..
+            } => (*test_id, token.as_deref(), *result_id),
+        }
+    }
+
+    fn set_token(&mut self, new: String) {
+        match self {
+            Submission::Response { token, .. } | Submission::Level { token, .. } => {
+                *token = Some(new)
+            }
+        }
+    }
+}
+
+thread_local! {
+    // Loaded from local storage on first use, then written back after every change.
+    static OUTBOX: RefCell<Option<Vec<Submission>>> = const { RefCell::new(None) };
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn with_outbox<T>(f: impl FnOnce(&mut Vec<Submission>) -> T) -> T {
+    OUTBOX.with(|outbox| {
+        let mut outbox = outbox.borrow_mut();
+        let outbox = outbox.get_or_insert_with(|| {
+            storage()
+                .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default()
+        });
+
+        f(outbox)
+    })
+}
+
+fn save(outbox: &[Submission]) {
+    // Should storage be full or unavailable, the submissions are still kept in memory
+    if let (Some(storage), Ok(json)) = (storage(), serde_json::to_string(outbox)) {
+        let _ = storage.set_item(STORAGE_KEY, &json);
+    }
+}
+
+pub fn push(submission: Submission) {
+    with_outbox(|outbox| {
+        outbox.push(submission);
+        save(outbox);
+    });
+}
+
+/// The number of submissions that haven't reached the server yet.
+pub fn pending() -> usize {
+    with_outbox(|outbox| outbox.len())
+}
+
+/// Sends submissions in the order they were queued, stopping at the first one that can't be sent
+/// right now.
+async fn flush() -> Result<(), APIError> {
+    while let Some(submission) = with_outbox(|outbox| outbox.first().cloned()) {
+        match submission.send().await {
+            Err(e) if e.is_transient() => return Err(e),
+            // The session expired, which doesn't make its answers any less the student's
+            Err(e @ APIError::Authorization(_)) => {
+                if !renew(&submission).await? {
+                    return Err(e);
+                }
+            }
+            // Anything the server rejected outright would be rejected again, so it's dropped
+            _ => with_outbox(|outbox| {
+                outbox.remove(0);
+                save(outbox);
+            }),
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets a new token for the kiosk session `submission` was made in, with the teacher logged in on
+/// the device, and sends everything else from that session with it too. Submissions made with a
+/// cookie have no session to renew.
+async fn renew(submission: &Submission) -> Result<bool, APIError> {
+    let (test_id, Some(expired), Some(result_id)) = submission.session() else {
+        return Ok(false);
+    };
+
+    let session = kiosk::renew_session(test_id, result_id).await?;
+    with_outbox(|outbox| {
+        for other in outbox.iter_mut() {
+            if other.session().1 == Some(expired) {
+                other.set_token(session.token.clone());
+            }
+        }
+        save(outbox);
+    });
+
+    Ok(true)
+}
+
+fn online() -> bool {
+    web_sys::window()
+        .map(|window| window.navigator().on_line())
+        .unwrap_or(true)
+}
+
+/// Keeps sending queued submissions for as long as it's polled, backing off exponentially while
+/// the server can't be reached or a teacher has to log in again. `on_change` is called with the
+/// number of pending submissions, and whether they wait on a teacher logging in, whenever either
+/// changes.
+pub async fn run(on_change: impl Fn(usize, bool)) {
+    let mut backoff = TICK;
+    let mut retry_in = Duration::ZERO;
+    let mut unauthorized = false;
+    let mut reported = None;
+
+    loop {
+        if !online() {
+            // Try again as soon as the browser says the connection is back
+            backoff = TICK;
+            retry_in = Duration::ZERO;
+        } else if retry_in.is_zero() {
+            let flushed = flush().await;
+            unauthorized = matches!(flushed, Err(APIError::Authorization(_)));
+
+            match flushed {
+                Ok(()) => backoff = TICK,
+                Err(_) => {
+                    retry_in = backoff;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        } else {
+            retry_in = retry_in.saturating_sub(TICK);
+        }
+
+        let status = (pending(), unauthorized);
+        if reported != Some(status) {
+            on_change(status.0, status.1);
+            reported = Some(status);
+        }
+
+        sleep(TICK).await;
+    }
+}
@@ -0,0 +1,32 @@
+use super::{delete, get, handle_response, post, APIError};
+use reqwest::header::CONTENT_TYPE;
+use shared::question::Question;
+use uuid::Uuid;
+
+/// Every question in the question bank, ordered by level.
+pub async fn get_questions() -> Result<Vec<Question>, APIError> {
+    let response = get("/question").send().await?;
+
+    handle_response(response).await
+}
+
+/// Uploads a recording of the question being read out, `content_type` being its MIME type.
+pub async fn upload_audio(
+    id: Uuid,
+    recording: Vec<u8>,
+    content_type: &str,
+) -> Result<Question, APIError> {
+    let response = post(&format!("/question/{id}/audio"))
+        .header(CONTENT_TYPE, content_type)
+        .body(recording)
+        .send()
+        .await?;
+
+    handle_response(response).await
+}
+
+pub async fn remove_audio(id: Uuid) -> Result<Question, APIError> {
+    let response = delete(&format!("/question/{id}/audio")).send().await?;
+
+    handle_response(response).await
+}
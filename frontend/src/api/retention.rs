@@ -0,0 +1,39 @@
+use super::{get, handle_response, post, APIError};
+use shared::retention::{RetentionPolicy, RetentionReport};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Whose retention policy is meant: a test's, or an organization's that applies to all of its
+/// teachers' tests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retained {
+    Test(Uuid),
+    Organization(Uuid),
+}
+
+impl Retained {
+    fn path(self) -> String {
+        match self {
+            Retained::Test(id) => format!("/test/{id}/retention"),
+            Retained::Organization(id) => format!("/organization/{id}/retention"),
+        }
+    }
+}
+
+/// The retention policy, what enforcing it would remove right now and what it removed before.
+pub async fn get_retention(of: Retained) -> Result<RetentionReport, APIError> {
+    let response = get(&of.path()).send().await?;
+
+    handle_response(response).await
+}
+
+pub async fn set_retention(
+    of: Retained,
+    policy: &RetentionPolicy,
+) -> Result<RetentionReport, APIError> {
+    policy.validate()?;
+
+    let response = post(&of.path()).json(policy).send().await?;
+
+    handle_response(response).await
+}
@@ -0,0 +1,53 @@
+use super::{delete, get, handle_response, handle_response_unit, post, APIError};
+use shared::share::{Permission, Share, ShareTest, TransferTests};
+use uuid::Uuid;
+use validator::Validate;
+
+/// The other teachers one of the teacher's own tests is shared with.
+pub async fn get_shares(id: Uuid) -> Result<Vec<Share>, APIError> {
+    let response = get(&format!("/test/{id}/shares")).send().await?;
+
+    handle_response(response).await
+}
+
+/// Shares a test with the teacher registered with `email`, for good unless `hours` is given.
+pub async fn share_test(
+    id: Uuid,
+    email: &str,
+    permission: Permission,
+    hours: Option<u32>,
+) -> Result<(), APIError> {
+    let req = ShareTest {
+        email: email.trim().to_string(),
+        permission,
+        hours,
+    };
+    req.validate()?;
+
+    let response = post(&format!("/test/{id}/shares"))
+        .json(&req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn unshare_test(id: Uuid, teacher_id: Uuid) -> Result<(), APIError> {
+    let response = delete(&format!("/test/{id}/shares/{teacher_id}"))
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+/// Hands every test the teacher owns over to the teacher registered with `email`.
+pub async fn transfer_tests(email: &str) -> Result<(), APIError> {
+    let req = TransferTests {
+        email: email.trim().to_string(),
+    };
+    req.validate()?;
+
+    let response = post("/teacher/transfer").json(&req).send().await?;
+
+    handle_response_unit(response).await
+}
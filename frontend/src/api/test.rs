@@ -1,38 +1,83 @@
-use super::{handle_response, handle_response_unit, APIError, RegisterStudent, SetLevel, API_URL};
-
-#[derive(serde::Deserialize, PartialEq)]
-pub struct Question {
-    pub id: String,
-    pub level: i32,
-    pub question: String,
-    pub image_path: Option<String>,
+use super::{
+    get, handle_response, handle_response_unit, media_url, post, with_token, APIError, CLIENT,
+};
+use shared::i18n::Language;
+use shared::question::QuestionPlan;
+use shared::test::{JoinTest, RegisterStudent, SetLevel, SubmitResponse};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Fetches every question of the test up front, so it can go on without a connection.
+pub async fn get_plan(id: Uuid) -> Result<QuestionPlan, APIError> {
+    let response = get(&format!("/test/{id}/questions")).send().await?;
+
+    handle_response(response).await
 }
 
-pub async fn get_questions(level: i32) -> Result<Vec<Question>, APIError> {
-    let response = reqwest::Client::new()
-        .get(format!("{API_URL}/question/{level}"))
+/// Downloads a question's image or recording into the browser's cache, so it's there instantly
+/// later on.
+pub async fn preload(path: &str) -> Result<(), APIError> {
+    CLIENT
+        .with(|client| client.clone())
+        .get(media_url(path))
         .send()
+        .await?
+        .error_for_status()?
+        .bytes()
         .await?;
 
+    Ok(())
+}
+
+/// Finds the test a join code belongs to.
+pub async fn find_test(code: &str) -> Result<JoinTest, APIError> {
+    let response = get(&format!("/join/{code}")).send().await?;
+
     handle_response(response).await
 }
 
-pub async fn register_student(id: &str, name: &str) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/test/{id}/register"))
-        .json(&RegisterStudent {
-            name: name.to_string(),
-        })
+pub async fn register_student(
+    id: Uuid,
+    name: &str,
+    language: Language,
+    pin: Option<String>,
+) -> Result<(), APIError> {
+    let req = RegisterStudent {
+        name: name.to_string(),
+        language: Some(language),
+        pin,
+    };
+    req.validate()?;
+
+    let response = post(&format!("/test/{id}/register"))
+        .json(&req)
         .send()
         .await?;
 
     handle_response_unit(response).await
 }
 
-pub async fn set_level(id: &str, level: i32) -> Result<(), APIError> {
-    let response = reqwest::Client::new()
-        .post(format!("{API_URL}/test/{id}"))
-        .json(&SetLevel { level })
+pub async fn set_level(id: Uuid, level: i32, token: Option<&str>) -> Result<(), APIError> {
+    let req = SetLevel { level };
+    req.validate()?;
+
+    let response = with_token(post(&format!("/test/{id}")), token)
+        .json(&req)
+        .send()
+        .await?;
+
+    handle_response_unit(response).await
+}
+
+pub async fn submit_response(
+    id: Uuid,
+    req: &SubmitResponse,
+    token: Option<&str>,
+) -> Result<(), APIError> {
+    req.validate()?;
+
+    let response = with_token(post(&format!("/test/{id}/response")), token)
+        .json(req)
         .send()
         .await?;
 
@@ -0,0 +1,129 @@
+use crate::api::audit::get_audit_log;
+use crate::components::dashboard::format_time;
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use shared::audit::{AuditEntry, AuditFilter};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// What teachers changed and exported, for the server's admins and the admins of a school or
+/// district, narrowed down by who, what and when.
+pub fn AuditPage(cx: Scope) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    let filter = use_state(cx, AuditFilter::default);
+    let entries = use_future(cx, filter.get(), |filter| async move {
+        get_audit_log(&filter).await
+    });
+
+    let on_submit = move |evt: FormEvent| {
+        let text = |name: &str| {
+            Some(evt.values[name][0].trim().to_string()).filter(|text| !text.is_empty())
+        };
+        // Dates are whole days, so `until` takes in all of the day it names
+        let day = |name: &str| {
+            let ms = js_sys::Date::parse(&evt.values[name][0]);
+            (!ms.is_nan()).then_some((ms / 1000.0) as i64)
+        };
+
+        filter.set(AuditFilter {
+            actor: text("actor"),
+            action: text("action"),
+            target: text("target"),
+            since: day("since"),
+            until: day("until").map(|until| until + SECONDS_PER_DAY),
+        });
+    };
+
+    let rendered = match entries.value() {
+        Some(Ok(entries)) if entries.is_empty() => rsx! {
+            div { class: "text-sm", t("Nothing matches these filters.") }
+        },
+        Some(Ok(entries)) => rsx! {
+            table { class: "min-w-full text-sm",
+                thead {
+                    tr {
+                        th { class: "pr-4 py-2 text-left", t("When") }
+                        th { class: "pr-4 py-2 text-left", t("Teacher") }
+                        th { class: "pr-4 py-2 text-left", t("Action") }
+                        th { class: "pr-4 py-2 text-left", t("Target") }
+                        th { class: "pr-4 py-2 text-left", t("Status") }
+                        th { class: "py-2 text-left", t("IP address") }
+                    }
+                }
+                tbody {
+                    entries.iter().map(|entry| rsx! { EntryRow { key: "{entry.id}", entry: entry } })
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "text-sm", t("There was an issue fetching the audit log:") " {e}" }
+        },
+        None => rsx! { div { class: "text-sm", t("Fetching the audit log...") } },
+    };
+
+    cx.render(rsx! {
+        nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700",
+            div { class: "max-w-full flex flex-wrap items-center justify-between mx-auto p-4",
+                button { class: "flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                    onclick: move |_| {
+                        nav.push(crate::Route::Dashboard {});
+                    },
+                    t("Back to the tests")
+                }
+            }
+        }
+
+        div { class: "p-6",
+            h1 { class: "mb-4 text-xl font-semibold", t("Audit log") }
+            form { class: "mb-4 flex flex-row flex-wrap items-center text-sm",
+                onsubmit: on_submit,
+                input { class: "mr-2 mb-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "actor",
+                    placeholder: t("Teacher"),
+                }
+                input { class: "mr-2 mb-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "action",
+                    placeholder: t("Action"),
+                }
+                input { class: "mr-2 mb-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "target",
+                    placeholder: t("Target"),
+                }
+                label { class: "mr-2 mb-2", t("From") }
+                input { class: "mr-2 mb-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "date",
+                    name: "since",
+                }
+                label { class: "mr-2 mb-2", t("To") }
+                input { class: "mr-2 mb-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "date",
+                    name: "until",
+                }
+                button { class: "mb-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Filter")
+                }
+            }
+            rendered
+        }
+    })
+}
+
+#[inline_props]
+fn EntryRow<'a>(cx: Scope, entry: &'a AuditEntry) -> Element {
+    let failed = entry.status >= 400;
+
+    cx.render(rsx! {
+        tr {
+            td { class: "pr-4 py-1 text-gray-500", "{format_time(entry.at)}" }
+            td { class: "pr-4 py-1", "{entry.actor}" }
+            td { class: "pr-4 py-1 font-mono", "{entry.action}" }
+            td { class: "pr-4 py-1 font-mono text-gray-500", "{entry.target}" }
+            td { class: if failed { "pr-4 py-1 text-red-800" } else { "pr-4 py-1" },
+                "{entry.status}"
+            }
+            td { class: "py-1 text-gray-500", entry.ip.as_deref().unwrap_or_default() }
+        }
+    })
+}
@@ -1,8 +1,14 @@
+use super::now;
 use super::test::{Action, TestState};
+use crate::i18n::t;
 use dioxus::html::MouseEvent;
 use dioxus::prelude::*;
+use shared::score::Point;
 use wasm_bindgen::JsCast;
-use web_sys::CanvasRenderingContext2d;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// How often the canvas is sent to the teacher's live view while the student is drawing.
+const MIRROR_INTERVAL_MS: f64 = 1000.0;
 
 #[derive(Debug)]
 enum Event {
@@ -20,6 +26,23 @@ pub fn Canvas(cx: Scope) -> Element {
     let test_state = use_shared_state::<TestState>(cx).unwrap();
 
     let pressed = use_state(cx, || false);
+    let last_mirrored = use_ref(cx, || 0.0);
+
+    // The teacher's live view follows along while the student draws
+    let mirror = move |force: bool| {
+        let now = now();
+        if force || now - *last_mirrored.read() >= MIRROR_INTERVAL_MS {
+            last_mirrored.set(now);
+            test_state.read().report_progress(Some(get_drawing()));
+        }
+    };
+
+    // The recognizer gets to follow the pen as well as see the finished drawing
+    let add_point = move |x: f64, y: f64| {
+        if let Some(stroke) = test_state.write().strokes.last_mut() {
+            stroke.push(Point { x, y });
+        }
+    };
 
     let event_handler = move |event: Event| match event {
         Event::MouseMove(e) => {
@@ -30,6 +53,8 @@ pub fn Canvas(cx: Scope) -> Element {
                 context.stroke();
                 context.begin_path();
                 context.move_to(cords.x, cords.y);
+                add_point(cords.x, cords.y);
+                mirror(false);
             }
         }
         Event::MouseUp(e) => {
@@ -38,10 +63,18 @@ pub fn Canvas(cx: Scope) -> Element {
             let cords = e.element_coordinates().to_f64();
             context.line_to(cords.x, cords.y);
             context.stroke();
+            add_point(cords.x, cords.y);
+            mirror(true);
         }
-        Event::MouseDown(_) => {
+        Event::MouseDown(e) => {
             pressed.set(true);
-            test_state.write().has_drawn = true;
+            let cords = e.element_coordinates().to_f64();
+            let mut state = test_state.write();
+            state.has_drawn = true;
+            state.strokes.push(vec![Point {
+                x: cords.x,
+                y: cords.y,
+            }]);
             let context = get_context();
             context.begin_path();
         }
@@ -49,8 +82,12 @@ pub fn Canvas(cx: Scope) -> Element {
 
     let clear_canvas = move |_| {
         let context = get_context();
-        test_state.write().has_drawn = false;
+        let mut state = test_state.write();
+        state.has_drawn = false;
+        state.strokes.clear();
+        drop(state);
         context.clear_rect(0.0, 0.0, c_width as f64, c_height as f64);
+        mirror(true);
     };
 
     cx.render(rsx! {
@@ -71,7 +108,7 @@ pub fn Canvas(cx: Scope) -> Element {
                     button { class: "text-white bg-gray-800 rounded hover:bg-gray-900 font-medium text-sm px-5 py-2.5 me-2 dark:bg-gray-800 dark:hover:bg-gray-700 dark:focus:ring-gray-700 dark:border-gray-700",
                         width: "{c_width}px",
                         onclick: clear_canvas,
-                        "Clear"
+                        t("Clear")
                     }
                 }
 
@@ -97,7 +134,7 @@ fn Buttons<'a>(cx: Scope<'a, ButtonsProps<'a>>) -> Element {
             rsx! {
                     div { class: "flex flex-col text-center py-8",
                     span { class: "text-xl",
-                    "Would you like a harder question?"
+                    t("Would you like a harder question?")
                     }
                         div {
                             button { class: "m-6 px-6 py-2 w-1/4 rounded text-white text-sm tracking-wider font-medium outline-none border-2 border-red-600 bg-red-600 hover:bg-transparent hover:text-black transition-all duration-300",
@@ -106,7 +143,7 @@ fn Buttons<'a>(cx: Scope<'a, ButtonsProps<'a>>) -> Element {
                                     submitted.set(false);
                                     test_state.write().perform_action(Action::Next)
                                 },
-                                "No"
+                                t("No")
                             }
                             button { class: "m-6 px-6 py-2 w-1/4 rounded text-white text-sm tracking-wider font-medium outline-none border-2 border-green-600 bg-green-600 hover:bg-transparent hover:text-black transition-all duration-300",
                                 onclick: move |evt| {
@@ -114,7 +151,7 @@ fn Buttons<'a>(cx: Scope<'a, ButtonsProps<'a>>) -> Element {
                                     submitted.set(false);
                                     test_state.write().perform_action(Action::LevelUp)
                                 },
-                                "Yes"
+                                t("Yes")
                             }
                         }
                     }
@@ -125,8 +162,11 @@ fn Buttons<'a>(cx: Scope<'a, ButtonsProps<'a>>) -> Element {
                     rsx! {
                         div { class: "flex justify-center py-8",
                             button { class: "px-2 py-2.5 min-w-[140px] w-2/5 bg-gradient-to-r from-green-400 rounded text-white text-sm tracking-wider font-medium border-none outline-none bg-green-600 active:from-green-500",
-                                onclick: move |_| submitted.set(true),
-                                "Submit"
+                                onclick: move |_| {
+                                    test_state.write().submit_drawing(get_drawing());
+                                    submitted.set(true)
+                                },
+                                t("Submit")
                             }
                         }
                     }
@@ -136,15 +176,29 @@ fn Buttons<'a>(cx: Scope<'a, ButtonsProps<'a>>) -> Element {
     })
 }
 
-fn get_context() -> CanvasRenderingContext2d {
+/// The canvas as a base64 encoded PNG.
+fn get_drawing() -> String {
+    let data_url = get_canvas()
+        .to_data_url()
+        .expect("a canvas that isn't tainted can always be exported");
+
+    data_url
+        .trim_start_matches("data:image/png;base64,")
+        .to_string()
+}
+
+fn get_canvas() -> HtmlCanvasElement {
     let window = web_sys::window().expect("global window does not exists");
     let document = window.document().expect("expecting a document on window");
-    let canvas = document
+    document
         .get_element_by_id("drawing-box")
         .expect("expecting a canvas in the document")
-        .dyn_into::<web_sys::HtmlCanvasElement>()
-        .unwrap();
-    canvas
+        .dyn_into::<HtmlCanvasElement>()
+        .unwrap()
+}
+
+fn get_context() -> CanvasRenderingContext2d {
+    get_canvas()
         .get_context("2d")
         .unwrap()
         .unwrap()
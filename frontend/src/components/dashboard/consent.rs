@@ -0,0 +1,115 @@
+use super::{alert, format_time, save_file};
+use crate::api::{
+    dashboard::{get_research_export, record_consent},
+    APIError,
+};
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use shared::consent::Consent;
+use shared::test::StudentResult;
+use uuid::Uuid;
+
+/// The consent a student's parents signed, when and by whom it was recorded, and a way to change
+/// it for teachers who may edit the test.
+#[inline_props]
+pub fn ConsentCell<'a>(
+    cx: Scope,
+    test_id: Uuid,
+    result: &'a StudentResult,
+    editable: bool,
+) -> Element {
+    let _ = use_language(cx);
+    // Kept up to date with what was recorded since the results were fetched
+    let consent = use_state(cx, || result.consent);
+    let recorded = use_state(cx, || {
+        result
+            .consent_recorded_at
+            .map(|at| (at, result.consent_recorded_by.clone().unwrap_or_default()))
+    });
+    let resp_text = use_state(cx, || None::<String>);
+
+    let on_change = move |evt: FormEvent| {
+        to_owned![consent, recorded, resp_text];
+        let test_id = *test_id;
+        let result_id = result.id;
+        let Some(chosen) = Consent::from_code(&evt.value) else {
+            return;
+        };
+
+        cx.spawn(async move {
+            match record_consent(test_id, result_id, chosen).await {
+                Ok(_) => {
+                    resp_text.set(None);
+                    consent.set(chosen);
+                    let now = (js_sys::Date::now() / 1000.0) as i64;
+                    recorded.set(Some((now, t("you").to_string())));
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        if *editable {
+            rsx! {
+                select { class: "px-2 py-1 bg-gray-100 rounded",
+                    onchange: on_change,
+                    Consent::ALL.into_iter().map(|option| rsx! {
+                        option {
+                            value: option.code(),
+                            selected: option == *consent.get(),
+                            t(option.name())
+                        }
+                    })
+                }
+            }
+        } else {
+            rsx! { span { t(consent.get().name()) } }
+        }
+        if let Some((at, by)) = recorded.get() {
+            rsx! {
+                div { class: "text-xs text-gray-500",
+                    t("Recorded") " {format_time(*at)} " t("by") " {by}"
+                }
+            }
+        }
+        if let Some(msg) = resp_text.get() {
+            rsx! { div { class: "text-xs text-red-800", "{msg}" } }
+        }
+    })
+}
+
+/// Downloads the answers and drawings of the test's students with research consent, without their
+/// names, to hand over to researchers.
+#[inline_props]
+pub fn ExportButton<'a>(cx: Scope, test_id: Uuid, name: &'a str) -> Element {
+    let _ = use_language(cx);
+    let downloading = use_state(cx, || false);
+
+    let download = move |_| {
+        to_owned![downloading, test_id];
+        let filename = format!("{name}-research.json");
+        downloading.set(true);
+
+        cx.spawn(async move {
+            match get_research_export(test_id).await {
+                Ok(json) => save_file(&filename, &json, "application/json"),
+                Err(e) => alert(&format!(
+                    "{} {e}",
+                    t("There was an issue when exporting the answers:")
+                )),
+            }
+            downloading.set(false);
+        });
+    };
+
+    cx.render(rsx! {
+        button { class: "ml-2 inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-indigo-600 rounded-lg hover:bg-indigo-500 disabled:opacity-50",
+            title: t("Download the answers of the students with research consent, without their names"),
+            disabled: *downloading.get(),
+            onclick: download,
+            t("Research export")
+        }
+    })
+}
@@ -0,0 +1,208 @@
+use crate::api::{
+    sharing::{get_shares, share_test, transfer_tests, unshare_test},
+    APIError,
+};
+use super::format_time;
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use shared::share::{Permission, Share};
+use uuid::Uuid;
+
+/// The other teachers one of the teacher's own tests is shared with, and a form to share it with
+/// someone else or change what they may do with it.
+#[inline_props]
+pub fn CoTeachers(cx: Scope, test_id: Uuid) -> Element {
+    let _ = use_language(cx);
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the shares again after changing them
+    let version = use_state(cx, || 0);
+    let shares = use_future(cx, (test_id, version.get()), |(test_id, _)| async move {
+        get_shares(test_id).await
+    });
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, version];
+        let test_id = *test_id;
+        let email = evt.values["email"][0].clone();
+        let permission = Permission::from_code(&evt.values["permission"][0]).unwrap_or_default();
+        let hours = evt.values["hours"][0].trim().parse().ok();
+
+        cx.spawn(async move {
+            match share_test(test_id, &email, permission, hours).await {
+                Ok(_) => {
+                    resp_text.set(Some(t("Shared").to_string()));
+                    version.modify(|version| version + 1);
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let remove = move |teacher_id: Uuid| {
+        to_owned![version];
+        let test_id = *test_id;
+
+        cx.spawn(async move {
+            if unshare_test(test_id, teacher_id).await.is_ok() {
+                version.modify(|version| version + 1);
+            }
+        });
+    };
+
+    let listed = match shares.value() {
+        Some(Ok(shares)) if shares.is_empty() => rsx! {
+            div { class: "mb-2", t("Only you can see this test.") }
+        },
+        Some(Ok(shares)) => rsx! {
+            table { class: "mb-2",
+                tbody {
+                    shares.iter().map(|share| rsx! {
+                        ShareRow {
+                            key: "{share.teacher_id}",
+                            share: share,
+                            onremove: remove,
+                        }
+                    })
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "mb-2", t("There was an issue fetching who the test is shared with:") " {e}" }
+        },
+        None => rsx! { div { class: "mb-2", t("Fetching who the test is shared with...") } },
+    };
+
+    cx.render(rsx! {
+        div { class: "text-sm",
+            div { class: "mb-2 font-semibold", t("Shared with") }
+            listed
+            form { class: "flex flex-row items-center",
+                onsubmit: on_submit,
+                input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "email",
+                    name: "email",
+                    placeholder: t("A teacher's email"),
+                }
+                select { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "permission",
+                    [Permission::Viewer, Permission::Proctor, Permission::Editor].into_iter().map(|permission| rsx! {
+                        option { value: permission.code(), t(permission.name()) }
+                    })
+                }
+                input { class: "w-20 mr-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "number",
+                    name: "hours",
+                    min: "1",
+                    max: "720",
+                    title: t("How many hours they keep access for, required for proctors"),
+                    placeholder: t("Hours"),
+                }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Share")
+                }
+                if let Some(msg) = resp_text.get() {
+                    rsx! { span { "{msg}" } }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Props)]
+struct ShareRowProps<'a> {
+    share: &'a Share,
+    /// Called with the teacher's id when they should lose access.
+    onremove: EventHandler<'a, Uuid>,
+}
+
+fn ShareRow<'a>(cx: Scope<'a, ShareRowProps<'a>>) -> Element<'a> {
+    let share = cx.props.share;
+
+    cx.render(rsx! {
+        tr {
+            td { class: "pr-4 py-1", "{share.username}" }
+            td { class: "pr-4 py-1 text-gray-500", "{share.email}" }
+            td { class: "pr-4 py-1", t(share.permission.name()) }
+            td { class: "pr-4 py-1 text-gray-500",
+                match share.expires_at {
+                    Some(expires_at) => rsx! { t("Until") " {format_time(expires_at)}" },
+                    None => rsx! { "" },
+                }
+            }
+            td { class: "py-1",
+                button { class: "px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    onclick: move |_| cx.props.onremove.call(share.teacher_id),
+                    t("Remove")
+                }
+            }
+        }
+    })
+}
+
+/// Hands every test the teacher owns over to another teacher, for when they leave the school.
+pub fn TransferForm(cx: Scope) -> Element {
+    let _ = use_language(cx);
+    let visible = use_state(cx, || false);
+    let resp_text = use_state(cx, || None::<String>);
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text];
+        let email = evt.values["email"][0].clone();
+
+        if !confirm(t(
+            "All your tests and their results will belong to this teacher instead. Continue?",
+        )) {
+            return;
+        }
+
+        cx.spawn(async move {
+            match transfer_tests(&email).await {
+                Ok(_) => resp_text.set(Some(t("Your tests were transferred").to_string())),
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(if *visible.get() {
+        rsx! {
+            form { class: "flex flex-row items-center text-sm",
+                onsubmit: on_submit,
+                input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "email",
+                    name: "email",
+                    placeholder: t("A teacher's email"),
+                }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Transfer")
+                }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    r#type: "button",
+                    onclick: move |_| visible.set(false),
+                    t("Cancel")
+                }
+                if let Some(msg) = resp_text.get() {
+                    rsx! { span { "{msg}" } }
+                }
+            }
+        }
+    } else {
+        rsx! {
+            button { class: "px-3 py-1 text-sm font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                title: t("Hand all your tests over to another teacher"),
+                onclick: move |_| {
+                    resp_text.set(None);
+                    visible.set(true)
+                },
+                t("Transfer my tests")
+            }
+        }
+    })
+}
+
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|window| window.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
@@ -1,14 +1,32 @@
 use crate::api::{
-    dashboard::{get_results, get_tests, inverse_closed, StudentResult, Test},
+    dashboard::{get_report, get_results, get_tests, inverse_closed, update_settings},
+    public_url,
+    retention::Retained,
     APIError,
 };
+use crate::components::retention::RetentionPanel;
+use crate::components::share::ShareForm;
+use consent::{ConsentCell, ExportButton};
+use coteachers::{CoTeachers, TransferForm};
+use crate::i18n::{self, t, use_language};
 use dioxus::prelude::*;
 use dioxus_router::prelude::use_navigator;
 use navbar::NavBar;
+use spelling::SpellingScores;
+use shared::i18n::Language;
+use shared::share::Permission;
+use shared::test::{StudentResult, Test, TestSettings};
+use uuid::Uuid;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+mod consent;
+mod coteachers;
 mod navbar;
+mod spelling;
 
 pub fn Dashboard(cx: Scope) -> Element {
     let nav = use_navigator(cx);
+    let _ = use_language(cx);
     let tests_fut = use_future(cx, (), |_| async move { get_tests().await });
 
     let tests_rendered = match tests_fut.value() {
@@ -22,13 +40,13 @@ pub fn Dashboard(cx: Scope) -> Element {
         Some(Err(e)) => match e {
             APIError::Authorization(_) => {
                 nav.push(crate::Route::Login {});
-                rsx! { div { "Redirecting..." } }
+                rsx! { div { t("Redirecting...") } }
             }
             _ => rsx! {
-                div { "There was an issue when fetching your tests..." }
+                div { t("There was an issue when fetching your tests:") " {e}" }
             },
         },
-        None => rsx! { div { "Fetching the tests..." } },
+        None => rsx! { div { t("Fetching the tests...") } },
     };
 
     cx.render(rsx! {
@@ -41,13 +59,16 @@ pub fn Dashboard(cx: Scope) -> Element {
                 thead { class: "bg-gray-100 whitespace-nowrap",
                     tr {
                         th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
-                            "Name"
+                            t("Name")
                         }
                         th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
-                            "Open"
+                            t("Language")
                         }
                         th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
-                            "Link"
+                            t("Open")
+                        }
+                        th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                            t("Link")
                         }
                     }
                 }
@@ -55,31 +76,34 @@ pub fn Dashboard(cx: Scope) -> Element {
                     tests_rendered
                 }
             }
+            div { class: "px-6 py-3", TransferForm {} }
         }
     })
 }
 
 #[inline_props]
 fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
+    let nav = use_navigator(cx);
     let drop_down = use_state(cx, || false);
-    let results = use_future(cx, &test.id, |id| async move { get_results(&id).await });
+    let _ = use_language(cx);
+    let results = use_future(cx, &test.id, |id| async move { get_results(id).await });
 
     let results_rendered = match results.value() {
         Some(Ok(results)) => rsx! {
                 results.iter().map(|r| {
-                    rsx! { ResultComponent { result:r } }
+                    rsx! { ResultComponent { test_id: test.id, result:r, permission: test.permission } }
                 })
         },
-        Some(Err(_)) => rsx! {
-                div { "There was an issue fetching the results for {test.name}..." }
+        Some(Err(e)) => rsx! {
+                div { t("There was an issue fetching the results for") " {test.name}: {e}" }
         },
-        None => rsx! { div { "Fetching the results..." } },
+        None => rsx! { div { t("Fetching the results...") } },
     };
 
     let close = move |_: FormEvent| {
         to_owned![test.id];
         cx.spawn(async move {
-            let _ = inverse_closed(&id).await;
+            let _ = inverse_closed(id).await;
         });
     };
 
@@ -93,6 +117,18 @@ fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
                td { class: "px-6 py-3 text-sm cursor-pointer",
                     onclick: |_| drop_down.modify(|v| !v),
                     "{test.name}"
+                    if test.permission != Permission::Owner {
+                        rsx! {
+                            span { class: "ml-2 text-gray-500",
+                                title: t("Shared with you by another teacher"),
+                                t(test.permission.name())
+                            }
+                        }
+                    }
+               }
+
+               td { class: "px-6 py-3 text-sm",
+                    test.language.name()
                }
 
                td { class: "px-6 py-3 text-sm",
@@ -101,6 +137,7 @@ fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
                         oninput: close,
                         r#type: "checkbox",
                         checked: "{!test.closed}",
+                        disabled: test.permission < Permission::Proctor,
                         name: "toggle",
                         "{test.closed}"
                     }
@@ -108,6 +145,26 @@ fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
                }
                td { class: "px-6 py-3 text-sm cursor-pointer",
                     Link { id: &test.id }
+                    if test.permission >= Permission::Proctor {
+                        rsx! {
+                            button { class: "ml-2 inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                                title: t("Run the test on this device, one student after another"),
+                                onclick: move |_| {
+                                    nav.push(crate::Route::Kiosk { id: test.id });
+                                },
+                                t("Kiosk")
+                            }
+                        }
+                    }
+                    button { class: "ml-2 inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                        title: t("Follow the students taking the test as it happens"),
+                        onclick: move |_| {
+                            nav.push(crate::Route::LiveView { id: test.id });
+                        },
+                        t("Live")
+                    }
+                    ReportButton { test_id: test.id, name: &test.name }
+                    ExportButton { test_id: test.id, name: &test.name }
                }
            }
 
@@ -117,10 +174,19 @@ fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
                     table { class: "min-w-full bg-white font-[sans-serif]",
                         thead { class: "whitespace-nowrap",
                             th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
-                                "Name"
+                                t("Name")
+                            }
+                            th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                                t("Language")
                             }
                             th { class: "px-6 py-3 text-center text-sm font-semibold text-black",
-                                "Level"
+                                t("Level")
+                            }
+                            th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                                t("Consent")
+                            }
+                            th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                                t("Report")
                             }
 
                             results_rendered
@@ -129,29 +195,267 @@ fn TestComponent<'a>(cx: Scope, test: &'a Test) -> Element {
                 }
                 }
            }
+
+           if *drop_down.get() && test.permission >= Permission::Editor {
+               rsx! {
+                tr {
+                    td { class: "px-6 py-3",
+                        colspan: "4",
+                        SettingsForm { test: test }
+                    }
+                }
+               }
+           }
+
+           if *drop_down.get() && test.permission >= Permission::Editor {
+               rsx! {
+                tr {
+                    td { class: "px-6 py-3",
+                        colspan: "4",
+                        RetentionPanel { of: Retained::Test(test.id) }
+                    }
+                }
+               }
+           }
+
+           if *drop_down.get() && test.permission >= Permission::Proctor {
+               rsx! {
+                tr {
+                    td { class: "px-6 py-3",
+                        colspan: "4",
+                        ShareForm { test: test }
+                    }
+                }
+               }
+           }
+
+           if *drop_down.get() && test.permission == Permission::Owner {
+               rsx! {
+                tr {
+                    td { class: "px-6 py-3",
+                        colspan: "4",
+                        CoTeachers { test_id: test.id }
+                    }
+                }
+               }
+           }
     })
 }
 
+/// How the test's questions are read out to the students.
 #[inline_props]
-fn ResultComponent<'a>(cx: Scope, result: &'a StudentResult) -> Element {
+fn SettingsForm<'a>(cx: Scope, test: &'a Test) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    let settings = &test.settings;
+    let voices = crate::prompt::voices();
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, test.id];
+
+        let settings = TestSettings {
+            autoplay: evt.values.contains_key("autoplay"),
+            speech_rate: evt.values["speech_rate"][0].parse().unwrap_or(1.0),
+            voice: Some(evt.values["voice"][0].trim().to_string()).filter(|v| !v.is_empty()),
+        };
+
+        cx.spawn(async move {
+            match update_settings(id, &settings).await {
+                Ok(_) => resp_text.set(Some(t("Saved").to_string())),
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        form { class: "flex flex-row items-center text-sm",
+            onsubmit: on_submit,
+            label { class: "mr-4 flex items-center",
+                input { class: "mr-2",
+                    r#type: "checkbox",
+                    name: "autoplay",
+                    value: "true",
+                    checked: settings.autoplay,
+                }
+                t("Read questions out automatically")
+            }
+            label { class: "mr-4 flex items-center",
+                span { class: "mr-2", t("Speech rate") }
+                input { class: "w-20 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "number",
+                    name: "speech_rate",
+                    min: "0.5",
+                    max: "2",
+                    step: "0.1",
+                    value: "{settings.speech_rate}",
+                }
+            }
+            label { class: "mr-4 flex items-center",
+                span { class: "mr-2", t("Voice") }
+                input { class: "px-2 py-1 bg-gray-100 rounded",
+                    name: "voice",
+                    list: "voices-{test.id}",
+                    placeholder: t("The browser's default"),
+                    value: "{settings.voice.as_deref().unwrap_or_default()}",
+                }
+                datalist {
+                    id: "voices-{test.id}",
+                    voices.iter().map(|voice| rsx! { option { value: "{voice}" } })
+                }
+            }
+            button { class: "mr-2 items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                t("Save")
+            }
+            if let Some(msg) = resp_text.get() {
+                rsx! { span { "{msg}" } }
+            }
+        }
+    })
+}
+
+#[inline_props]
+fn ResultComponent<'a>(
+    cx: Scope,
+    test_id: Uuid,
+    result: &'a StudentResult,
+    permission: Permission,
+) -> Element {
+    let _ = use_language(cx);
+    let show_spelling = use_state(cx, || false);
+
     cx.render(rsx! {
         tr {
             td { class: "px-6 py-3 text-sm",
                 "{result.name}"
             }
+            td { class: "px-6 py-3 text-sm",
+                result.language.map(Language::name).unwrap_or_default()
+            }
             td { class: "px-6 py-3 text-center text-sm",
                 "{result.level}"
             }
+            td { class: "px-6 py-3 text-sm",
+                ConsentCell {
+                    test_id: *test_id,
+                    result: result,
+                    editable: *permission >= Permission::Editor,
+                }
+            }
+            td { class: "px-6 py-3 text-sm",
+                ReportButton { test_id: *test_id, result_id: result.id, name: &result.name }
+                button { class: "ml-2 inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    title: t("Check what the student wrote and their spelling stage"),
+                    onclick: |_| show_spelling.modify(|v| !v),
+                    t("Spelling")
+                }
+            }
+        }
+
+        if *show_spelling.get() {
+            rsx! {
+                tr {
+                    td { class: "px-6 py-3",
+                        colspan: "5",
+                        SpellingScores { test_id: *test_id, result_id: result.id }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Downloads the PDF report of a test's class, or of one of its students when `result_id` is
+/// given, named after `name`.
+#[inline_props]
+fn ReportButton<'a>(cx: Scope, test_id: Uuid, result_id: Option<Uuid>, name: &'a str) -> Element {
+    let _ = use_language(cx);
+    let downloading = use_state(cx, || false);
+
+    let download = move |_| {
+        to_owned![downloading, test_id, result_id];
+        let filename = format!("{name}.pdf");
+        downloading.set(true);
+
+        cx.spawn(async move {
+            match get_report(test_id, result_id).await {
+                Ok(pdf) => save_file(&filename, &pdf, "application/pdf"),
+                Err(e) => alert(&format!(
+                    "{} {e}",
+                    t("There was an issue when making the report:")
+                )),
+            }
+            downloading.set(false);
+        });
+    };
+
+    let label = match result_id {
+        Some(_) => t("Report"),
+        None => t("Class report"),
+    };
+
+    cx.render(rsx! {
+        button { class: "ml-2 inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-indigo-600 rounded-lg hover:bg-indigo-500 disabled:opacity-50",
+            title: t("Download a printable PDF report"),
+            disabled: *downloading.get(),
+            onclick: download,
+            label
         }
     })
 }
 
+// The report is fetched like any other request so it carries the session and language, then
+// handed to the browser through an object URL to be saved.
+fn save_file(filename: &str, bytes: &[u8], mime: &str) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let Ok(blob) =
+        Blob::new_with_u8_array_sequence_and_options(&parts, BlobPropertyBag::new().type_(mime))
+    else {
+        return;
+    };
+    let Ok(url) = Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+
+    if let Some(anchor) = document
+        .create_element("a")
+        .ok()
+        .and_then(|anchor| anchor.dyn_into::<HtmlAnchorElement>().ok())
+    {
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+    }
+
+    let _ = Url::revoke_object_url(&url);
+}
+
+/// A time sent as seconds since the Unix epoch, written the way the browser writes times in the
+/// language the dashboard is in.
+pub(crate) fn format_time(seconds: i64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(seconds as f64 * 1000.0));
+    date.to_locale_string(i18n::current().code(), &JsValue::UNDEFINED)
+        .into()
+}
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
 #[inline_props]
-fn Link<'a>(cx: Scope, id: &'a str) -> Element {
+fn Link<'a>(cx: Scope, id: &'a Uuid) -> Element {
+    let public_url = public_url();
+    let _ = use_language(cx);
+
     cx.render(rsx! {
         a { class: "inline-flex items-center px-5 py-2.5 text-sm font-medium text-center text-white bg-blue-700 rounded-lg hover:bg-blue-800 focus:ring-4 focus:outline-none focus:ring-blue-300 dark:bg-blue-600 dark:hover:bg-blue-700 dark:focus:ring-blue-800",
-            href: "http://localhost:8080/test/{id}/",
-            "Navigate to the test",
+            href: "{public_url}/test/{id}/",
+            t("Navigate to the test"),
                 svg { class: "w-4 h-4 rtl:rotate-180",
                 xmlns: "http://www.w3.org/2000/svg",
                 fill: "none",
@@ -168,7 +472,3 @@ fn Link<'a>(cx: Scope, id: &'a str) -> Element {
         }
     })
 }
-
-
-
-
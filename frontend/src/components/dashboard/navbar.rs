@@ -1,5 +1,8 @@
 use crate::api::{dashboard::create_test, APIError};
+use crate::i18n::{self, t, use_language, LanguageSelect};
 use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use shared::i18n::Language;
 
 #[derive(Props)]
 pub struct NavBarProps<'a> {
@@ -7,6 +10,9 @@ pub struct NavBarProps<'a> {
 }
 
 pub fn NavBar<'a>(cx: Scope<'a, NavBarProps<'a>>) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+
     cx.render(rsx! {
         nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700",
             div { class: "max-w-full flex flex-wrap items-center justify-between mx-auto p-4",
@@ -24,11 +30,26 @@ pub fn NavBar<'a>(cx: Scope<'a, NavBarProps<'a>>) -> Element {
                     }
 
                     span { class: "mx-1",
-                        "Refetch"
+                        t("Refetch")
                     }
                 }
 
-                CreateButton {}
+                div { class: "flex flex-row items-center",
+                    button { class: "mr-2 flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                        onclick: move |_| {
+                            nav.push(crate::Route::QuestionBank {});
+                        },
+                        t("Question bank")
+                    }
+                    button { class: "mr-2 flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                        onclick: move |_| {
+                            nav.push(crate::Route::OrganizationPage {});
+                        },
+                        t("School or district")
+                    }
+                    CreateButton {}
+                    div { class: "ml-2", LanguageSelect {} }
+                }
             }
         }
     })
@@ -37,13 +58,15 @@ pub fn NavBar<'a>(cx: Scope<'a, NavBarProps<'a>>) -> Element {
 fn CreateButton(cx: Scope) -> Element {
     let visible = use_state(cx, || false);
     let resp_text = use_state(cx, || None::<String>);
+    let _ = use_language(cx);
 
     let on_submit = move |evt: FormEvent| {
         to_owned![resp_text];
         to_owned![visible];
 
         cx.spawn(async move {
-            let resp = create_test(evt.values["name"][0].as_str()).await;
+            let language = Language::from_code(&evt.values["language"][0]).unwrap_or_default();
+            let resp = create_test(evt.values["name"][0].as_str(), language).await;
 
             match resp {
                 Err(e) => match e {
@@ -70,7 +93,8 @@ fn CreateButton(cx: Scope) -> Element {
                     rsx! {
                         div { class: "mr-2 px-4 py-2 text-sm text-red-800 rounded-lg bg-red-50 dark:bg-gray-800 dark:text-red-400",
                             span { class: "font-medium",
-                                "Invalid! "
+                                t("Invalid!")
+                                " "
                             }
                             "{msg}"
                         }
@@ -79,12 +103,23 @@ fn CreateButton(cx: Scope) -> Element {
                 input { class: "mr-2 px-4 py-2 bg-gray-100 text-sm outline-[#333] rounded",
                     name: "name" 
                 },
+                // The language students will see the test in
+                select { class: "mr-2 px-4 py-2 bg-gray-100 text-sm outline-[#333] rounded",
+                    name: "language",
+                    Language::ALL.into_iter().map(|option| rsx! {
+                        option {
+                            value: option.code(),
+                            selected: option == i18n::current(),
+                            option.name()
+                        }
+                    })
+                },
                 button { class: "mr-2 items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
-                    "Submit"
+                    t("Submit")
                 }
                 button { class: "items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
                     onclick: move |_| visible.set(false),
-                    "Cancel"
+                    t("Cancel")
                 }
             }
         }
@@ -95,7 +130,7 @@ fn CreateButton(cx: Scope) -> Element {
                     resp_text.set(None);
                     visible.set(true)
                 },
-                "Create"
+                t("Create")
             }
         }
     })
@@ -0,0 +1,168 @@
+use crate::api::{
+    dashboard::{drawing_url, get_responses, score_response},
+    APIError,
+};
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use shared::phonetics::{analyze, Analysis};
+use shared::score::{spelling_score, ScoreResponse, ScoredResponse};
+use uuid::Uuid;
+
+/// A student's answers, for the teacher to accept or correct what the recognizer read in them, and
+/// see what the spelling ones say about the student's spelling stage.
+#[inline_props]
+pub fn SpellingScores(cx: Scope, test_id: Uuid, result_id: Uuid) -> Element {
+    let _ = use_language(cx);
+    let responses = use_future(
+        cx,
+        (test_id, result_id),
+        |(test_id, result_id)| async move { get_responses(test_id, result_id).await },
+    );
+
+    let rendered = match responses.value() {
+        Some(Ok(responses)) if responses.is_empty() => rsx! {
+            div { class: "text-sm", t("No answers yet.") }
+        },
+        Some(Ok(responses)) => rsx! {
+            table { class: "text-sm",
+                thead {
+                    tr {
+                        th { class: "px-4 py-2 text-left font-semibold", t("Drawing") }
+                        th { class: "px-4 py-2 text-left font-semibold", t("Word") }
+                        th { class: "px-4 py-2 text-left font-semibold", t("Suggested") }
+                        th { class: "px-4 py-2 text-left font-semibold", t("Transcription") }
+                        th { class: "px-4 py-2 text-left font-semibold", t("Stage") }
+                    }
+                }
+                tbody {
+                    responses.iter().map(|response| rsx! {
+                        ResponseRow { key: "{response.id}", test_id: *test_id, response: response }
+                    })
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "text-sm", t("There was an issue fetching the answers:") " {e}" }
+        },
+        None => rsx! { div { class: "text-sm", t("Fetching the answers...") } },
+    };
+
+    cx.render(rendered)
+}
+
+#[inline_props]
+fn ResponseRow<'a>(cx: Scope, test_id: Uuid, response: &'a ScoredResponse) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    let given = use_state(cx, || response.transcription.clone());
+    let score = use_state(cx, || response.score);
+    let analysis = use_state(cx, || {
+        response
+            .phonetic_accuracy
+            .zip(response.stage)
+            .map(|(accuracy, stage)| Analysis { accuracy, stage })
+    });
+
+    let save = move |transcription: String| {
+        to_owned![
+            resp_text,
+            given,
+            score,
+            analysis,
+            test_id,
+            response.id,
+            response.word,
+            response.phonemes
+        ];
+
+        cx.spawn(async move {
+            let req = ScoreResponse {
+                transcription: transcription.clone(),
+                score: None,
+            };
+
+            match score_response(test_id, id, &req).await {
+                Ok(_) => {
+                    // The backend works them out the same way
+                    score.set(
+                        word.as_deref()
+                            .map(|word| spelling_score(word, &transcription)),
+                    );
+                    analysis.set(
+                        word.as_deref()
+                            .zip(phonemes.as_deref())
+                            .map(|(word, phonemes)| analyze(word, phonemes, &transcription)),
+                    );
+                    given.set(Some(transcription));
+                    resp_text.set(Some(t("Saved").to_string()));
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        tr {
+            td { class: "px-4 py-2",
+                img { class: "h-16 border rounded",
+                    src: "{drawing_url(*test_id, response.id)}",
+                    alt: "{response.word.as_deref().unwrap_or_default()}",
+                }
+            }
+            td { class: "px-4 py-2",
+                if let Some(word) = &response.word {
+                    rsx! { div { "{word}" } }
+                }
+                if let Some(phonemes) = &response.phonemes {
+                    rsx! { div { class: "text-gray-500 font-mono", "/{phonemes}/" } }
+                }
+            }
+            td { class: "px-4 py-2",
+                match response.suggested_transcription.as_deref().zip(response.suggested_score) {
+                    Some((suggestion, score)) => rsx! {
+                        span { class: "mr-2 font-mono", "{suggestion}" }
+                        span { class: "mr-2 text-gray-500", "{percent(score)}" }
+                        button { class: "px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                            onclick: move |_| save(suggestion.to_string()),
+                            t("Accept")
+                        }
+                    },
+                    None => rsx! { span { class: "text-gray-500", t("None") } },
+                }
+            }
+            td { class: "px-4 py-2",
+                form { class: "flex flex-row items-center",
+                    onsubmit: move |evt: FormEvent| save(evt.values["transcription"][0].trim().to_string()),
+                    input { class: "w-32 mr-2 px-2 py-1 bg-gray-100 rounded font-mono",
+                        name: "transcription",
+                        value: "{given.get().as_deref().unwrap_or_default()}",
+                    }
+                    button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                        t("Save")
+                    }
+                    if let Some(score) = score.get() {
+                        rsx! { span { class: "mr-2", "{percent(*score)}" } }
+                    }
+                    if let Some(msg) = resp_text.get() {
+                        rsx! { span { "{msg}" } }
+                    }
+                }
+            }
+            td { class: "px-4 py-2",
+                if let Some(Analysis { accuracy, stage }) = analysis.get() {
+                    rsx! {
+                        div { t(stage.name()) }
+                        div { class: "text-gray-500",
+                            title: t("The share of the word's sounds the student wrote letters for"),
+                            "{percent(*accuracy)}"
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn percent(score: f64) -> String {
+    format!("{:.0}%", score * 100.0)
+}
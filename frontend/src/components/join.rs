@@ -0,0 +1,80 @@
+use crate::api::{test::find_test, APIError};
+use crate::i18n::{t, use_language, LanguageSelect};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use shared::test::normalize_join_code;
+
+/// Where students type in the join code their teacher gave them.
+pub fn Join(cx: Scope) -> Element {
+    cx.render(rsx! { JoinForm { code: String::new() } })
+}
+
+/// Followed from a test's QR code, which already has the join code in it.
+#[inline_props]
+pub fn JoinCode(cx: Scope, code: String) -> Element {
+    cx.render(rsx! { JoinForm { code: code.clone() } })
+}
+
+#[inline_props]
+fn JoinForm(cx: Scope, code: String) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    let resp_text = use_state(cx, || None::<String>);
+
+    let join: &_ = cx.bump().alloc(move |code: String| {
+        to_owned![resp_text, nav];
+
+        cx.spawn(async move {
+            match find_test(&normalize_join_code(&code)).await {
+                Ok(joined) => {
+                    nav.replace(crate::Route::Test {
+                        id: joined.test_id,
+                    });
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    // Codes that came with the link are looked up straight away
+    use_on_create(cx, || {
+        if !code.is_empty() {
+            join(code.clone());
+        }
+        async {}
+    });
+
+    cx.render(rsx! {
+        div { class: "bg-gray-50 font-[sans-serif] text-[#333]",
+            div { class: "min-h-screen flex flex-col items-center justify-center py-6 px-4",
+                div { class: "max-w-md w-full border py-2 px-6 rounded border-gray-300 bg-white",
+                    form { class: "mt-2 space-y-4",
+                        onsubmit: move |evt| join(evt.values["code"][0].clone()),
+                        style: "display: flex; flex-direction: column; gap: 10px;",
+                        input { class: "px-4 py-3 bg-gray-100 w-full text-2xl text-center font-mono tracking-widest uppercase outline-[#333] rounded",
+                            r#type: "text",
+                            autocomplete: "off",
+                            placeholder: t("Enter the join code"),
+                            value: "{code}",
+                            name: "code"
+                        }
+                        LanguageSelect {}
+                        div { class: "!mt-10",
+                            button { class: "w-full py-2.5 px-4 text-sm rounded text-white bg-blue-600 hover:bg-blue-700 focus:outline-none",
+                                t("Join")
+                            }
+                        }
+                        if let Some(err) = resp_text.get() {
+                            rsx! {
+                                div { class: "p-4 mb-4 text-sm text-red-800 rounded-lg bg-red-50 dark:bg-gray-800 dark:text-red-400",
+                                    "{err}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
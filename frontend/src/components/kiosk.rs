@@ -0,0 +1,295 @@
+//! Kiosk mode, for a teacher sitting with one child after another at a shared device: the teacher
+//! logs in once, picks each student from the test's roster, and the test runs fullscreen with
+//! navigation locked until they leave kiosk mode.
+
+use super::test::{Finished, TestSession, TestState};
+use crate::api::{
+    kiosk::{add_to_roster, get_roster, remove_from_roster, start_session},
+    APIError,
+};
+use crate::i18n::{self, t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use gloo_timers::future::sleep;
+use shared::test::RosterEntry;
+use std::cell::Cell;
+use std::time::Duration;
+use uuid::Uuid;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::BeforeUnloadEvent;
+
+/// How long the thank you screen stays up before the next student can be picked.
+const FINISHED_DELAY: Duration = Duration::from_secs(5);
+
+#[inline_props]
+pub fn Kiosk(cx: Scope, id: Uuid) -> Element {
+    use_on_create(cx, || async { lock_navigation() });
+    use_on_destroy(cx, unlock_navigation);
+
+    cx.render(rsx! { TestSession { id: *id, kiosk: true } })
+}
+
+/// The list of students the teacher picks the next one from.
+#[inline_props]
+pub fn StudentPicker(cx: Scope, id: Uuid) -> Element {
+    let nav = use_navigator(cx);
+    let test_state = use_shared_state::<TestState>(cx).unwrap();
+    let language = use_language(cx);
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the roster again after it's changed
+    let revision = use_state(cx, || 0);
+
+    // Who's expected, and who has already taken the test
+    let roster_fut = use_future(cx, (id, revision.get()), |(id, _)| async move {
+        get_roster(id).await
+    });
+
+    let pick: &_ = cx.bump().alloc(move |entry: RosterEntry| {
+        // Only allowed while handling the click, so it can't wait for the session to start
+        enter_fullscreen();
+
+        to_owned![test_state, language, resp_text];
+        let id = *id;
+
+        cx.spawn(async move {
+            let entry_language = entry.language;
+            let chosen = entry_language.unwrap_or_else(|| test_state.read().test_language());
+
+            match start_session(id, entry.id).await {
+                Ok(session) => {
+                    i18n::set_language(&language, chosen);
+                    test_state.write().start(chosen, Some(session));
+                }
+                Err(e) => resp_text.set(Some(error_message(e))),
+            }
+        });
+    });
+
+    let on_add = move |evt: FormEvent| {
+        to_owned![resp_text, revision];
+        let id = *id;
+
+        cx.spawn(async move {
+            for name in evt.values["names"][0].lines().map(str::trim) {
+                if name.is_empty() {
+                    continue;
+                }
+
+                if let Err(e) = add_to_roster(id, name, None).await {
+                    resp_text.set(Some(format!("{name}: {}", error_message(e))));
+                    break;
+                }
+            }
+
+            revision.modify(|revision| revision + 1);
+        });
+    };
+
+    let on_exit = move |_| {
+        unlock_navigation();
+        exit_fullscreen();
+        nav.push(crate::Route::Dashboard {});
+    };
+
+    let students = match roster_fut.value() {
+        Some(Ok(roster)) => rsx! {
+            div { class: "grid grid-cols-2 md:grid-cols-4 gap-4",
+                roster.iter().map(|entry| {
+                    let is_done = entry.result_id.is_some();
+                    let picked = entry.clone();
+                    let entry_id = entry.id;
+
+                    rsx! {
+                        div { class: "relative",
+                            key: "{entry.id}",
+                            button { class: if is_done {
+                                    "w-full py-8 px-4 text-2xl rounded-lg bg-gray-200 text-gray-500"
+                                } else {
+                                    "w-full py-8 px-4 text-2xl rounded-lg text-white bg-blue-600 hover:bg-blue-700"
+                                },
+                                disabled: is_done,
+                                onclick: move |_| pick(picked.clone()),
+                                "{entry.name}"
+                                if is_done {
+                                    rsx! { " ✓" }
+                                }
+                            }
+                            button { class: "absolute top-0 right-0 m-1 px-2 text-sm text-gray-600 hover:text-red-600",
+                                title: t("Remove"),
+                                onclick: move |_| {
+                                    to_owned![revision];
+                                    let id = *id;
+                                    cx.spawn(async move {
+                                        let _ = remove_from_roster(id, entry_id).await;
+                                        revision.modify(|revision| revision + 1);
+                                    });
+                                },
+                                "×"
+                            }
+                        }
+                    }
+                })
+            }
+        },
+        Some(Err(APIError::Authorization(_))) => rsx! {
+            div { class: "text-xl",
+                t("Log in on this device to use it as a kiosk.")
+                button { class: "ml-4 py-2 px-4 bg-blue-600 text-white rounded hover:bg-blue-700",
+                    onclick: move |_| {
+                        unlock_navigation();
+                        nav.push(crate::Route::Login {});
+                    },
+                    t("Log in")
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { t("There was an issue fetching the roster:") " {e}" }
+        },
+        None => rsx! { div { t("Fetching the roster...") } },
+    };
+
+    cx.render(rsx! {
+        div { class: "min-h-screen bg-gray-50 p-8",
+            div { class: "flex flex-row items-center justify-between mb-8",
+                h2 { class: "text-3xl font-extrabold",
+                    t("Who's next?")
+                }
+                button { class: "py-2 px-4 bg-red-600 text-white rounded hover:bg-red-700",
+                    onclick: on_exit,
+                    t("Leave kiosk mode")
+                }
+            }
+
+            if let Some(err) = resp_text.get() {
+                rsx! {
+                    div { class: "p-4 mb-4 text-sm text-red-800 rounded-lg bg-red-50",
+                        span { class: "font-medium",
+                            t("Invalid!")
+                            " "
+                        }
+                        "{err}"
+                    }
+                }
+            }
+
+            students
+
+            form { class: "mt-8 flex flex-col max-w-md",
+                onsubmit: on_add,
+                textarea { class: "px-4 py-3 bg-gray-100 text-sm outline-[#333] rounded",
+                    name: "names",
+                    rows: "4",
+                    placeholder: t("Add students, one name per line"),
+                }
+                button { class: "mt-2 py-2 px-4 bg-indigo-600 text-white rounded hover:bg-indigo-500",
+                    t("Add")
+                }
+            }
+        }
+    })
+}
+
+/// The thank you screen, after which the device goes back to the student picker.
+pub fn KioskFinished(cx: Scope) -> Element {
+    let test_state = use_shared_state::<TestState>(cx).unwrap();
+
+    use_on_create(cx, || {
+        to_owned![test_state];
+        async move {
+            sleep(FINISHED_DELAY).await;
+            test_state.write().next_student();
+        }
+    });
+
+    cx.render(rsx! {
+        Finished {}
+        button { class: "absolute bottom-0 right-0 mb-4 mr-4 py-2 px-4 bg-blue-600 text-white rounded hover:bg-blue-700",
+            onclick: move |_| test_state.write().next_student(),
+            t("Next student")
+        }
+    })
+}
+
+fn error_message(e: APIError) -> String {
+    match e {
+        APIError::Validation(errors) => errors.join(","),
+        _ => e.to_string(),
+    }
+}
+
+/// Asks the person at the device to confirm `message`.
+pub fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|window| window.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
+
+fn enter_fullscreen() {
+    if let Some(root) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element())
+    {
+        let _ = root.request_fullscreen();
+    }
+}
+
+fn exit_fullscreen() {
+    if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        if document.fullscreen_element().is_some() {
+            document.exit_fullscreen();
+        }
+    }
+}
+
+thread_local! {
+    static LOCKED: Cell<bool> = const { Cell::new(false) };
+    // The listeners live for as long as the page, and check `LOCKED` before doing anything
+    static LISTENING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Keeps the back button and reloads from leaving the kiosk, until `unlock_navigation`.
+fn lock_navigation() {
+    LOCKED.with(|locked| locked.set(true));
+
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    // Going back lands on this entry, which the listener below pushes right back
+    push_history_entry();
+
+    if LISTENING.with(|listening| listening.replace(true)) {
+        return;
+    }
+
+    let on_popstate = Closure::<dyn Fn()>::new(|| {
+        if LOCKED.with(Cell::get) {
+            push_history_entry();
+        }
+    });
+    let on_beforeunload = Closure::<dyn Fn(BeforeUnloadEvent)>::new(|evt: BeforeUnloadEvent| {
+        if LOCKED.with(Cell::get) {
+            evt.prevent_default();
+            evt.set_return_value("");
+        }
+    });
+
+    let _ =
+        window.add_event_listener_with_callback("popstate", on_popstate.as_ref().unchecked_ref());
+    let _ = window
+        .add_event_listener_with_callback("beforeunload", on_beforeunload.as_ref().unchecked_ref());
+
+    on_popstate.forget();
+    on_beforeunload.forget();
+}
+
+fn unlock_navigation() {
+    LOCKED.with(|locked| locked.set(false));
+}
+
+fn push_history_entry() {
+    if let Some(history) = web_sys::window().and_then(|window| window.history().ok()) {
+        let _ = history.push_state(&JsValue::NULL, "");
+    }
+}
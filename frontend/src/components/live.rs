@@ -0,0 +1,333 @@
+//! The teacher's live view of a test while it's being taken: who has registered, which question
+//! each student is on, how long they've been idle, and the canvas of the one being watched.
+
+use super::kiosk::confirm;
+use super::now;
+use crate::api::{
+    dashboard::get_results,
+    live::{control_all, control_student, watch_test},
+    APIError,
+};
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use gloo_timers::future::sleep;
+use shared::i18n::Language;
+use shared::live::{CanvasFrame, Command, StudentProgress};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Students who haven't done anything for this long are highlighted.
+const IDLE_AFTER_SECS: u64 = 60;
+
+/// A student as last reported by the server.
+struct Seen {
+    progress: StudentProgress,
+    /// When the report came in, see `now`.
+    received_at: f64,
+}
+
+impl Seen {
+    fn idle_secs(&self, now: f64) -> Option<u64> {
+        let since = ((now - self.received_at) / 1000.0).max(0.0) as u64;
+        self.progress.idle_secs.map(|idle| idle + since)
+    }
+}
+
+#[inline_props]
+pub fn LiveView(cx: Scope, id: Uuid) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    let students = use_ref(cx, Vec::<Seen>::new);
+    let watched = use_state(cx, || None::<Uuid>);
+    let frame = use_state(cx, || None::<CanvasFrame>);
+    let closed = use_state(cx, || false);
+    // Bumped to open the connection again after the browser gave up on it
+    let revision = use_state(cx, || 0);
+    // Bumped every second so the idle times keep counting up
+    let tick = use_state(cx, || 0u64);
+    let resp_text = use_state(cx, || None::<String>);
+
+    let feed = use_future(cx, (id, watched.get(), revision.get()), |(id, watch, _)| {
+        to_owned![students, frame, closed];
+        async move {
+            frame.set(None);
+            closed.set(false);
+
+            // The stream can't say why it failed, so make sure the teacher may see the test
+            get_results(id).await?;
+
+            let _feed = watch_test(
+                id,
+                watch,
+                move |progress| update(&students, progress),
+                move |canvas| frame.set(Some(canvas)),
+                move || closed.set(true),
+            )?;
+
+            // Keep the connection open until the view goes away or watches someone else
+            std::future::pending::<()>().await;
+            Ok::<_, APIError>(())
+        }
+    });
+
+    let _ = use_future(cx, (), |_| {
+        to_owned![tick];
+        async move {
+            loop {
+                sleep(Duration::from_secs(1)).await;
+                tick.modify(|tick| tick + 1);
+            }
+        }
+    });
+
+    let back = move |_| {
+        nav.push(crate::Route::Dashboard {});
+    };
+
+    // Sends a command to one student, or to everyone if there's no student
+    let send: &_ = cx
+        .bump()
+        .alloc(move |student: Option<Uuid>, command: Command| {
+            to_owned![resp_text];
+            let id = *id;
+
+            cx.spawn(async move {
+                let resp = match student {
+                    Some(student) => control_student(id, student, command).await,
+                    None => control_all(id, command).await,
+                };
+
+                resp_text.set(resp.err().map(|e| e.to_string()));
+            });
+        });
+
+    if let Some(Err(e)) = feed.value() {
+        return cx.render(match e {
+            APIError::Authorization(_) => {
+                nav.push(crate::Route::Login {});
+                rsx! { div { t("Redirecting...") } }
+            }
+            _ => rsx! {
+                div { class: "p-6",
+                    t("There was an issue when opening the live view:")
+                    " {e}"
+                }
+            },
+        });
+    }
+
+    let now = now();
+    let students = students.read();
+    let watched_name = watched.get().and_then(|watched| {
+        students
+            .iter()
+            .find(|seen| seen.progress.id == watched)
+            .map(|seen| seen.progress.name.clone())
+    });
+
+    cx.render(rsx! {
+        div { class: "flex flex-row items-center justify-between p-4 bg-white border-b border-gray-200",
+            button { class: "px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                onclick: back,
+                t("Back to the tests")
+            }
+            if let Some(msg) = resp_text.get() {
+                rsx! { div { class: "text-sm text-red-800", "{msg}" } }
+            }
+            if *closed.get() {
+                rsx! {
+                    div { class: "flex flex-row items-center text-sm text-red-800",
+                        t("The live view was disconnected.")
+                        button { class: "ml-2 px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                            onclick: move |_| revision.modify(|revision| revision + 1),
+                            t("Reconnect")
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-row",
+                button { class: "mr-2 px-4 py-2 font-medium text-white bg-yellow-600 rounded-lg hover:bg-yellow-500",
+                    onclick: move |_| send(None, Command::Pause),
+                    t("Pause everyone")
+                }
+                button { class: "px-4 py-2 font-medium text-white bg-green-600 rounded-lg hover:bg-green-500",
+                    onclick: move |_| send(None, Command::Resume),
+                    t("Resume everyone")
+                }
+            }
+        }
+
+        div { class: "flex flex-row p-4 gap-4",
+            table { class: "w-1/2 bg-white font-[sans-serif] self-start",
+                thead { class: "bg-gray-100 whitespace-nowrap",
+                    tr {
+                        th { class: "px-4 py-3 text-left text-sm font-semibold", t("Name") }
+                        th { class: "px-4 py-3 text-left text-sm font-semibold", t("Language") }
+                        th { class: "px-4 py-3 text-center text-sm font-semibold", t("Level") }
+                        th { class: "px-4 py-3 text-center text-sm font-semibold", t("Attempt") }
+                        th { class: "px-4 py-3 text-center text-sm font-semibold", t("Idle") }
+                        th { class: "px-4 py-3 text-left text-sm font-semibold", t("Status") }
+                        th {}
+                    }
+                }
+                tbody { class: "whitespace-nowrap divide-y divide-gray-200",
+                    students.iter().map(|seen| {
+                        let progress = &seen.progress;
+                        let student_id = progress.id;
+                        let idle = seen.idle_secs(now);
+                        let is_idle = !progress.finished && idle.is_some_and(|idle| idle >= IDLE_AFTER_SECS);
+
+                        let status = if progress.finished {
+                            t("Finished")
+                        } else if progress.paused {
+                            t("Paused")
+                        } else if progress.attempt.is_some() {
+                            t("Testing")
+                        } else {
+                            t("Registered")
+                        };
+
+                        let row_class = match (Some(student_id) == *watched.get(), is_idle) {
+                            (true, _) => "bg-blue-100 cursor-pointer",
+                            (false, true) => "bg-yellow-100 cursor-pointer",
+                            (false, false) => "hover:bg-blue-50 cursor-pointer",
+                        };
+
+                        rsx! {
+                            tr { class: row_class,
+                                key: "{student_id}",
+                                onclick: move |_| watched.set(Some(student_id)),
+                                td { class: "px-4 py-3 text-sm", "{progress.name}" }
+                                td { class: "px-4 py-3 text-sm",
+                                    progress.language.map(Language::name).unwrap_or_default()
+                                }
+                                td { class: "px-4 py-3 text-center text-sm", "{progress.level}" }
+                                td { class: "px-4 py-3 text-center text-sm",
+                                    progress.attempt.map(|attempt| attempt.to_string()).unwrap_or_default()
+                                }
+                                td { class: "px-4 py-3 text-center text-sm",
+                                    idle.filter(|_| !progress.finished).map(format_idle).unwrap_or_default()
+                                }
+                                td { class: "px-4 py-3 text-sm", status }
+                                td { class: "px-4 py-3 text-sm text-right",
+                                    if !progress.finished {
+                                        rsx! {
+                                            StudentControls {
+                                                paused: progress.paused,
+                                                onsend: move |command| send(Some(student_id), command),
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    })
+                }
+            }
+
+            div { class: "w-1/2 flex flex-col items-center",
+                match (watched_name, frame.get()) {
+                    (None, _) => rsx! {
+                        div { class: "text-gray-600", t("Pick a student to see what they're drawing.") }
+                    },
+                    (Some(name), Some(frame)) => rsx! {
+                        div { class: "text-lg font-semibold pb-2", "{name}" }
+                        img { class: "border rounded-lg max-w-full",
+                            src: "data:image/png;base64,{frame.drawing}",
+                        }
+                    },
+                    (Some(name), None) => rsx! {
+                        div { class: "text-lg font-semibold pb-2", "{name}" }
+                        div { class: "text-gray-600", t("Waiting for them to draw something...") }
+                    },
+                }
+            }
+        }
+    })
+}
+
+#[derive(Props)]
+struct StudentControlsProps<'a> {
+    paused: bool,
+    onsend: EventHandler<'a, Command>,
+}
+
+/// The buttons for pausing, resuming, ending and restarting one student's session.
+fn StudentControls<'a>(cx: Scope<'a, StudentControlsProps<'a>>) -> Element<'a> {
+    let button = "ml-1 px-2 py-1 text-xs font-medium text-white rounded";
+    let pause_class = format!("{button} bg-yellow-600 hover:bg-yellow-500");
+    let resume_class = format!("{button} bg-green-600 hover:bg-green-500");
+    let end_class = format!("{button} bg-red-600 hover:bg-red-500");
+    let restart_class = format!("{button} bg-gray-700 hover:bg-gray-600");
+
+    cx.render(rsx! {
+        if cx.props.paused {
+            rsx! {
+                button { class: "{resume_class}",
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        cx.props.onsend.call(Command::Resume)
+                    },
+                    t("Resume")
+                }
+            }
+        } else {
+            rsx! {
+                button { class: "{pause_class}",
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        cx.props.onsend.call(Command::Pause)
+                    },
+                    t("Pause")
+                }
+            }
+        }
+        button { class: "{end_class}",
+            onclick: move |evt| {
+                evt.stop_propagation();
+                if confirm(t("End this student's test?")) {
+                    cx.props.onsend.call(Command::End)
+                }
+            },
+            t("End")
+        }
+        button { class: "{restart_class}",
+            onclick: move |evt| {
+                evt.stop_propagation();
+                if confirm(t("Restart this student's test from the first question? Their answers so far will be deleted.")) {
+                    cx.props.onsend.call(Command::Restart)
+                }
+            },
+            t("Restart")
+        }
+    })
+}
+
+/// Adds or replaces the student in the list, which is kept in alphabetical order.
+fn update(students: &UseRef<Vec<Seen>>, progress: StudentProgress) {
+    let seen = Seen {
+        progress,
+        received_at: now(),
+    };
+
+    students.with_mut(|students| {
+        match students
+            .iter_mut()
+            .find(|other| other.progress.id == seen.progress.id)
+        {
+            Some(other) => *other = seen,
+            None => {
+                students.push(seen);
+                students.sort_by(|a, b| a.progress.name.cmp(&b.progress.name));
+            }
+        }
+    });
+}
+
+fn format_idle(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{secs}s"),
+        _ => format!("{}m {:02}s", secs / 60, secs % 60),
+    }
+}
@@ -1,9 +1,25 @@
+pub mod audit;
 pub mod canvas;
 pub mod dashboard;
 pub mod forms;
+pub mod join;
+pub mod kiosk;
+pub mod live;
+pub mod organization;
+pub mod question_bank;
+pub mod retention;
+pub mod share;
 pub mod test;
+use crate::i18n::t;
 use dioxus::prelude::*;
 
+/// Milliseconds since the page was loaded.
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
 #[inline_props]
 pub fn NotFound(cx: Scope, route: Vec<String>) -> Element {
     let strobe_css = "
@@ -32,7 +48,7 @@ pub fn NotFound(cx: Scope, route: Vec<String>) -> Element {
                 },
                 p {
                     class: "text-white-900 text-white text-9xl",
-                    "NOT FOUND"
+                    t("NOT FOUND")
                 }
             }
 
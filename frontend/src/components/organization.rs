@@ -0,0 +1,590 @@
+use crate::api::{
+    organization::{
+        accept_invitation, add_member, create_organization, create_question, decline_invitation,
+        get_invitations, get_members, get_organizations, get_questions, get_summary,
+        remove_member, update_settings,
+    },
+    retention::Retained,
+    APIError,
+};
+use crate::components::question_bank::QuestionComponent;
+use crate::components::retention::RetentionPanel;
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use shared::organization::{CreateQuestion, Member, Organization, OrganizationKind};
+use shared::question::MAX_LEVEL;
+use shared::test::TestSettings;
+use uuid::Uuid;
+
+/// The school or district the teacher belongs to. Its admins manage its teachers, settings and
+/// questions here, see how every class did, and set up the schools of their district.
+pub fn OrganizationPage(cx: Scope) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    // Bumped to fetch the organizations again after creating one
+    let version = use_state(cx, || 0);
+    let organizations = use_future(cx, version.get(), |_| get_organizations());
+    let admin = matches!(
+        organizations.value(),
+        Some(Ok(organizations)) if organizations.first().is_some_and(|own| own.admin)
+    );
+
+    let rendered = match organizations.value() {
+        Some(Ok(organizations)) => match organizations.split_first() {
+            None => rsx! {
+                div { class: "mb-2 text-sm", t("You don't belong to a school or district yet.") }
+                Invitations { version: version.clone() }
+                CreateOrganizationForm { version: version.clone() }
+            },
+            Some((own, schools)) => rsx! {
+                OrganizationPanel { key: "{own.id}", organization: own.clone() }
+                if own.admin && own.kind == OrganizationKind::District {
+                    rsx! {
+                        div { class: "mb-8",
+                            h2 { class: "mb-2 font-semibold", t("Schools") }
+                            CreateOrganizationForm { parent_id: own.id, version: version.clone() }
+                        }
+                    }
+                }
+                schools.iter().map(|school| rsx! {
+                    OrganizationPanel { key: "{school.id}", organization: school.clone() }
+                })
+            },
+        },
+        Some(Err(e)) => rsx! {
+            div { t("There was an issue fetching your organization:") " {e}" }
+        },
+        None => rsx! { div { t("Fetching your organization...") } },
+    };
+
+    cx.render(rsx! {
+        nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700",
+            div { class: "max-w-full flex flex-wrap items-center justify-between mx-auto p-4",
+                button { class: "flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                    onclick: move |_| {
+                        nav.push(crate::Route::Dashboard {});
+                    },
+                    t("Back to the tests")
+                }
+                if admin {
+                    rsx! {
+                        button { class: "flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                            onclick: move |_| {
+                                nav.push(crate::Route::AuditPage {});
+                            },
+                            t("Audit log")
+                        }
+                    }
+                }
+            }
+        }
+
+        div { class: "p-6",
+            rendered
+        }
+    })
+}
+
+/// The organizations the teacher was invited to, to join one of them. `version` is bumped once
+/// they do, to fetch the organizations again.
+#[inline_props]
+fn Invitations(cx: Scope, version: UseState<i32>) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the invitations again after declining one
+    let declined = use_state(cx, || 0);
+    let invitations = use_future(cx, declined.get(), |_| get_invitations());
+
+    let accept = move |organization_id: Uuid| {
+        to_owned![resp_text, version];
+
+        cx.spawn(async move {
+            match accept_invitation(organization_id).await {
+                Ok(_) => version.modify(|version| version + 1),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let decline = move |organization_id: Uuid| {
+        to_owned![resp_text, declined];
+
+        cx.spawn(async move {
+            match decline_invitation(organization_id).await {
+                Ok(_) => declined.modify(|declined| declined + 1),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let Some(Ok(invitations)) = invitations.value() else {
+        return None;
+    };
+    if invitations.is_empty() {
+        return None;
+    }
+
+    cx.render(rsx! {
+        div { class: "mb-6 text-sm",
+            h2 { class: "mb-2 font-semibold", t("Invitations") }
+            table { class: "mb-2",
+                tbody {
+                    invitations.iter().map(|invitation| {
+                        let organization_id = invitation.organization_id;
+                        rsx! {
+                            tr { key: "{organization_id}",
+                                td { class: "pr-4 py-1", "{invitation.name}" }
+                                td { class: "pr-4 py-1 text-gray-500", t(invitation.kind.name()) }
+                                td { class: "pr-4 py-1", if invitation.admin { t("Admin") } else { "" } }
+                                td { class: "py-1",
+                                    button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                                        onclick: move |_| accept(organization_id),
+                                        t("Accept")
+                                    }
+                                    button { class: "px-3 py-1 font-medium text-white bg-red-600 rounded-lg hover:bg-red-700",
+                                        onclick: move |_| decline(organization_id),
+                                        t("Decline")
+                                    }
+                                }
+                            }
+                        }
+                    })
+                }
+            }
+            if let Some(msg) = resp_text.get() {
+                rsx! { span { "{msg}" } }
+            }
+        }
+    })
+}
+
+/// Creates an organization the teacher becomes the admin of, or a school of the district
+/// `parent_id`. `version` is bumped once it's created, to fetch the organizations again.
+#[inline_props]
+fn CreateOrganizationForm(cx: Scope, parent_id: Option<Uuid>, version: UseState<i32>) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    let parent_id = *parent_id;
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, version];
+        let name = evt.values["name"][0].clone();
+        let kind = match parent_id {
+            Some(_) => OrganizationKind::School,
+            None => OrganizationKind::from_code(&evt.values["kind"][0])
+                .unwrap_or(OrganizationKind::School),
+        };
+
+        cx.spawn(async move {
+            match create_organization(&name, kind, parent_id).await {
+                Ok(_) => {
+                    resp_text.set(None);
+                    version.modify(|version| version + 1);
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        form { class: "flex flex-row items-center text-sm",
+            onsubmit: on_submit,
+            input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                name: "name",
+                placeholder: if parent_id.is_some() { t("The school's name") } else { t("Its name") },
+            }
+            if parent_id.is_none() {
+                rsx! {
+                    select { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                        name: "kind",
+                        OrganizationKind::ALL.into_iter().map(|kind| rsx! {
+                            option { value: kind.code(), t(kind.name()) }
+                        })
+                    }
+                }
+            }
+            button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                if parent_id.is_some() { t("Add a school") } else { t("Create") }
+            }
+            if let Some(msg) = resp_text.get() {
+                rsx! { span { "{msg}" } }
+            }
+        }
+    })
+}
+
+#[inline_props]
+fn OrganizationPanel(cx: Scope, organization: Organization) -> Element {
+    let _ = use_language(cx);
+
+    cx.render(rsx! {
+        div { class: "mb-8",
+            h1 { class: "mb-2 text-xl font-semibold",
+                "{organization.name} "
+                span { class: "text-sm font-normal text-gray-500", t(organization.kind.name()) }
+            }
+            if organization.admin {
+                rsx! {
+                    OrganizationSettings { organization: organization.clone() }
+                    Members { organization_id: organization.id }
+                    Summary { organization_id: organization.id }
+                    OrganizationQuestions { organization_id: organization.id }
+                    RetentionPanel { of: Retained::Organization(organization.id) }
+                }
+            } else {
+                rsx! {
+                    div { class: "text-sm",
+                        t("Your tests start with the organization's settings and ask its questions.")
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[inline_props]
+fn OrganizationSettings(cx: Scope, organization: Organization) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    let settings = &organization.settings;
+    let voices = crate::prompt::voices();
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, organization.id];
+
+        let settings = TestSettings {
+            autoplay: evt.values.contains_key("autoplay"),
+            speech_rate: evt.values["speech_rate"][0].parse().unwrap_or(1.0),
+            voice: Some(evt.values["voice"][0].trim().to_string()).filter(|v| !v.is_empty()),
+        };
+
+        cx.spawn(async move {
+            match update_settings(id, &settings).await {
+                Ok(_) => resp_text.set(Some(t("Saved").to_string())),
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        h2 { class: "mb-2 font-semibold", t("What new tests start with") }
+        form { class: "mb-6 flex flex-row items-center text-sm",
+            onsubmit: on_submit,
+            label { class: "mr-4 flex items-center",
+                input { class: "mr-2",
+                    r#type: "checkbox",
+                    name: "autoplay",
+                    value: "true",
+                    checked: settings.autoplay,
+                }
+                t("Read questions out automatically")
+            }
+            label { class: "mr-4 flex items-center",
+                span { class: "mr-2", t("Speech rate") }
+                input { class: "w-20 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "number",
+                    name: "speech_rate",
+                    min: "0.5",
+                    max: "2",
+                    step: "0.1",
+                    value: "{settings.speech_rate}",
+                }
+            }
+            label { class: "mr-4 flex items-center",
+                span { class: "mr-2", t("Voice") }
+                input { class: "px-2 py-1 bg-gray-100 rounded",
+                    name: "voice",
+                    list: "voices-{organization.id}",
+                    placeholder: t("The browser's default"),
+                    value: "{settings.voice.as_deref().unwrap_or_default()}",
+                }
+                datalist {
+                    id: "voices-{organization.id}",
+                    voices.iter().map(|voice| rsx! { option { value: "{voice}" } })
+                }
+            }
+            button { class: "mr-2 items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                t("Save")
+            }
+            if let Some(msg) = resp_text.get() {
+                rsx! { span { "{msg}" } }
+            }
+        }
+    })
+}
+
+/// The organization's teachers and the ones invited to it, and a form to invite another one or
+/// make them an admin.
+#[inline_props]
+fn Members(cx: Scope, organization_id: Uuid) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the members again after changing them
+    let version = use_state(cx, || 0);
+    let members = use_future(
+        cx,
+        (organization_id, version.get()),
+        |(organization_id, _)| async move { get_members(organization_id).await },
+    );
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, version];
+        let organization_id = *organization_id;
+        let email = evt.values["email"][0].clone();
+        let admin = evt.values.contains_key("admin");
+
+        cx.spawn(async move {
+            match add_member(organization_id, &email, admin).await {
+                Ok(_) => {
+                    resp_text.set(None);
+                    version.modify(|version| version + 1);
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let remove = move |teacher_id: Uuid| {
+        to_owned![resp_text, version];
+        let organization_id = *organization_id;
+
+        cx.spawn(async move {
+            match remove_member(organization_id, teacher_id).await {
+                Ok(_) => version.modify(|version| version + 1),
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let listed = match members.value() {
+        Some(Ok(members)) => rsx! {
+            table { class: "mb-2",
+                tbody {
+                    members.iter().map(|member| rsx! {
+                        MemberRow { key: "{member.id}", member: member, onremove: remove }
+                    })
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "mb-2", t("There was an issue fetching the teachers:") " {e}" }
+        },
+        None => rsx! { div { class: "mb-2", t("Fetching the teachers...") } },
+    };
+
+    cx.render(rsx! {
+        div { class: "mb-6 text-sm",
+            h2 { class: "mb-2 font-semibold", t("Teachers") }
+            listed
+            form { class: "flex flex-row items-center",
+                onsubmit: on_submit,
+                input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "email",
+                    name: "email",
+                    placeholder: t("A teacher's email"),
+                }
+                label { class: "mr-2 flex items-center",
+                    input { class: "mr-2",
+                        r#type: "checkbox",
+                        name: "admin",
+                        value: "true",
+                    }
+                    t("Admin")
+                }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Invite")
+                }
+                if let Some(msg) = resp_text.get() {
+                    rsx! { span { "{msg}" } }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Props)]
+struct MemberRowProps<'a> {
+    member: &'a Member,
+    /// Called with the teacher's id when they should leave the organization.
+    onremove: EventHandler<'a, Uuid>,
+}
+
+fn MemberRow<'a>(cx: Scope<'a, MemberRowProps<'a>>) -> Element<'a> {
+    let member = cx.props.member;
+
+    cx.render(rsx! {
+        tr {
+            td { class: "pr-4 py-1", "{member.username}" }
+            td { class: "pr-4 py-1 text-gray-500", "{member.email}" }
+            td { class: "pr-4 py-1", if member.admin { t("Admin") } else { "" } }
+            td { class: "pr-4 py-1 text-gray-500", if member.invited { t("Invited") } else { "" } }
+            td { class: "py-1",
+                button { class: "px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    onclick: move |_| cx.props.onremove.call(member.id),
+                    t("Remove")
+                }
+            }
+        }
+    })
+}
+
+/// How every class of the organization did, by teacher, without any student's name.
+#[inline_props]
+fn Summary(cx: Scope, organization_id: Uuid) -> Element {
+    let summary = use_future(cx, organization_id, |organization_id| async move {
+        get_summary(organization_id).await
+    });
+    let levels = 1..=MAX_LEVEL;
+
+    let rendered = match summary.value() {
+        Some(Ok(summary)) => rsx! {
+            table { class: "min-w-full bg-white font-[sans-serif]",
+                thead { class: "bg-gray-100 whitespace-nowrap",
+                    tr {
+                        th { class: "px-4 py-2 text-left text-sm font-semibold text-black", t("Teacher") }
+                        th { class: "px-4 py-2 text-center text-sm font-semibold text-black", t("Tests") }
+                        th { class: "px-4 py-2 text-center text-sm font-semibold text-black", t("Students") }
+                        th { class: "px-4 py-2 text-center text-sm font-semibold text-black", t("Finished") }
+                        levels.clone().map(|level| rsx! {
+                            th { class: "px-4 py-2 text-center text-sm font-semibold text-black",
+                                t("Level") " {level}"
+                            }
+                        })
+                    }
+                }
+                tbody { class: "whitespace-nowrap divide-y divide-gray-200",
+                    summary.teachers.iter().map(|teacher| rsx! {
+                        tr { key: "{teacher.teacher_id}",
+                            td { class: "px-4 py-2 text-sm", "{teacher.username}" }
+                            td { class: "px-4 py-2 text-center text-sm", "{teacher.tests}" }
+                            td { class: "px-4 py-2 text-center text-sm", "{teacher.students}" }
+                            td { class: "px-4 py-2 text-center text-sm", "{teacher.finished}" }
+                            teacher.levels.iter().map(|count| rsx! {
+                                td { class: "px-4 py-2 text-center text-sm", "{count}" }
+                            })
+                        }
+                    })
+                    tr { class: "font-semibold",
+                        td { class: "px-4 py-2 text-sm", t("Everyone") }
+                        td { class: "px-4 py-2 text-center text-sm",
+                            "{summary.teachers.iter().map(|teacher| teacher.tests).sum::<i64>()}"
+                        }
+                        td { class: "px-4 py-2 text-center text-sm", "{summary.students}" }
+                        td { class: "px-4 py-2 text-center text-sm", "{summary.finished}" }
+                        summary.levels.iter().map(|count| rsx! {
+                            td { class: "px-4 py-2 text-center text-sm", "{count}" }
+                        })
+                    }
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "text-sm", t("There was an issue fetching the results:") " {e}" }
+        },
+        None => rsx! { div { class: "text-sm", t("Fetching the results...") } },
+    };
+
+    cx.render(rsx! {
+        div { class: "mb-6 overflow-x-auto",
+            h2 { class: "mb-2 font-semibold", t("Where the students ended the test") }
+            rendered
+        }
+    })
+}
+
+/// The organization's own questions, asked instead of the shared bank's of the same level.
+#[inline_props]
+fn OrganizationQuestions(cx: Scope, organization_id: Uuid) -> Element {
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the questions again after adding one
+    let version = use_state(cx, || 0);
+    let questions = use_future(
+        cx,
+        (organization_id, version.get()),
+        |(organization_id, _)| async move { get_questions(organization_id).await },
+    );
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, version];
+        let organization_id = *organization_id;
+        let optional = |name: &str| {
+            Some(evt.values[name][0].trim().to_string()).filter(|value| !value.is_empty())
+        };
+        let req = CreateQuestion {
+            level: evt.values["level"][0].parse().unwrap_or(1),
+            question: evt.values["question"][0].trim().to_string(),
+            word: optional("word"),
+            phonemes: optional("phonemes"),
+        };
+
+        cx.spawn(async move {
+            match create_question(organization_id, req).await {
+                Ok(_) => {
+                    resp_text.set(None);
+                    version.modify(|version| version + 1);
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let listed = match questions.value() {
+        Some(Ok(questions)) if questions.is_empty() => rsx! {
+            div { class: "mb-2 text-sm", t("Tests only ask the shared questions.") }
+        },
+        Some(Ok(questions)) => rsx! {
+            table { class: "mb-2 min-w-full bg-white font-[sans-serif]",
+                tbody { class: "whitespace-nowrap divide-y divide-gray-200",
+                    questions.iter().map(|question| rsx! {
+                        QuestionComponent {
+                            key: "{question.id}",
+                            question: question.clone(),
+                            organization_id: *organization_id,
+                        }
+                    })
+                }
+            }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "mb-2 text-sm", t("There was an issue when fetching the questions:") " {e}" }
+        },
+        None => rsx! { div { class: "mb-2 text-sm", t("Fetching the questions...") } },
+    };
+
+    cx.render(rsx! {
+        div { class: "mb-6",
+            h2 { class: "mb-2 font-semibold", t("Questions of your own") }
+            listed
+            form { class: "flex flex-row items-center text-sm",
+                onsubmit: on_submit,
+                select { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "level",
+                    (1..=MAX_LEVEL).map(|level| rsx! {
+                        option { value: "{level}", t("Level") " {level}" }
+                    })
+                }
+                input { class: "mr-2 w-80 px-2 py-1 bg-gray-100 rounded",
+                    name: "question",
+                    placeholder: t("Question"),
+                }
+                input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "word",
+                    placeholder: t("The word to spell, if any"),
+                }
+                input { class: "mr-2 px-2 py-1 bg-gray-100 rounded",
+                    name: "phonemes",
+                    title: t("The word's sounds in ARPAbet, like K AE T for cat"),
+                    placeholder: t("Its sounds"),
+                }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Add")
+                }
+                if let Some(msg) = resp_text.get() {
+                    rsx! { span { "{msg}" } }
+                }
+            }
+        }
+    })
+}
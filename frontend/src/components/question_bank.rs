@@ -0,0 +1,214 @@
+use crate::api::{
+    media_url,
+    organization::remove_question,
+    question::{get_questions, remove_audio, upload_audio},
+    APIError,
+};
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use shared::question::Question;
+use uuid::Uuid;
+
+pub fn QuestionBank(cx: Scope) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    let questions = use_future(cx, (), |_| get_questions());
+
+    let questions_rendered = match questions.value() {
+        Some(Ok(questions)) => rsx! {
+            questions.iter().map(|q| {
+                rsx! {
+                    QuestionComponent { key: "{q.id}", question: q.clone() }
+                }
+            })
+        },
+        Some(Err(e)) => rsx! {
+            div { t("There was an issue when fetching the questions:") " {e}" }
+        },
+        None => rsx! { div { t("Fetching the questions...") } },
+    };
+
+    cx.render(rsx! {
+        nav { class: "bg-white border-gray-200 dark:bg-gray-900 dark:border-gray-700",
+            div { class: "max-w-full flex flex-wrap items-center justify-between mx-auto p-4",
+                button { class: "flex items-center px-4 py-2 font-medium tracking-wide text-white capitalize transition-colors duration-300 transform bg-indigo-600 rounded-lg hover:bg-indigo-500 focus:outline-none focus:ring focus:ring-indigo-300 focus:ring-opacity-80",
+                    onclick: move |_| {
+                        nav.push(crate::Route::Dashboard {});
+                    },
+                    t("Back to the tests")
+                }
+            }
+        }
+
+        div { class: "py-14 overflow-x-auto",
+            table { class: "min-w-full bg-white font-[sans-serif]",
+                thead { class: "bg-gray-100 whitespace-nowrap",
+                    tr {
+                        th { class: "px-6 py-3 text-center text-sm font-semibold text-black",
+                            t("Level")
+                        }
+                        th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                            t("Question")
+                        }
+                        th { class: "px-6 py-3 text-left text-sm font-semibold text-black",
+                            t("Recording")
+                        }
+                    }
+                }
+                tbody { class: "whitespace-nowrap divide-y divide-gray-200",
+                    questions_rendered
+                }
+            }
+        }
+    })
+}
+
+/// A question and its recording. Questions of an organization, given as `organization_id`, can be
+/// removed altogether as well.
+#[inline_props]
+pub fn QuestionComponent(
+    cx: Scope,
+    question: Question,
+    organization_id: Option<Uuid>,
+) -> Element {
+    // Replaced by what the server returns after every upload or removal
+    let question = use_state(cx, || question.clone());
+    let resp_text = use_state(cx, || None::<String>);
+    let removed = use_state(cx, || false);
+
+    let on_upload = move |evt: FormEvent| {
+        to_owned![question, resp_text];
+
+        cx.spawn(async move {
+            let Some(files) = evt.files.clone() else {
+                return;
+            };
+            let Some(name) = files.files().into_iter().next() else {
+                return;
+            };
+            let Some(content_type) = audio_content_type(&name) else {
+                return resp_text.set(Some(
+                    t("The recording must be an MP3, Ogg, WAV, WebM or MP4 audio file").to_string(),
+                ));
+            };
+            let Some(recording) = files.read_file(&name).await else {
+                return;
+            };
+
+            match upload_audio(question.id, recording, content_type).await {
+                Ok(updated) => {
+                    resp_text.set(None);
+                    question.set(updated);
+                }
+                Err(e) => resp_text.set(Some(error_message(e))),
+            }
+        });
+    };
+
+    let on_remove = move |_| {
+        to_owned![question, resp_text];
+
+        cx.spawn(async move {
+            match remove_audio(question.id).await {
+                Ok(updated) => {
+                    resp_text.set(None);
+                    question.set(updated);
+                }
+                Err(e) => resp_text.set(Some(error_message(e))),
+            }
+        });
+    };
+
+    let on_remove_question = move |_| {
+        to_owned![question, resp_text, removed];
+        let Some(organization_id) = *organization_id else {
+            return;
+        };
+
+        cx.spawn(async move {
+            match remove_question(organization_id, question.id).await {
+                Ok(_) => removed.set(true),
+                Err(e) => resp_text.set(Some(error_message(e))),
+            }
+        });
+    };
+
+    if *removed.get() {
+        return None;
+    }
+
+    cx.render(rsx! {
+        tr {
+            td { class: "px-6 py-3 text-center text-sm",
+                "{question.level}"
+            }
+            td { class: "px-6 py-3 text-sm",
+                "{question.question}"
+            }
+            td { class: "px-6 py-3 text-sm",
+                div { class: "flex flex-row items-center",
+                    if let Some(path) = &question.audio_path {
+                        rsx! {
+                            audio { class: "mr-2",
+                                controls: true,
+                                src: "{media_url(path)}",
+                            }
+                            button { class: "mr-2 px-4 py-2 text-white bg-red-600 rounded-lg hover:bg-red-700",
+                                onclick: on_remove,
+                                t("Remove")
+                            }
+                        }
+                    }
+                    input { class: "text-sm",
+                        r#type: "file",
+                        accept: "audio/*",
+                        onchange: on_upload,
+                    }
+                }
+                if let Some(err) = resp_text.get() {
+                    rsx! {
+                        div { class: "mt-2 px-4 py-2 text-sm text-red-800 rounded-lg bg-red-50",
+                            span { class: "font-medium",
+                                t("Invalid!")
+                                " "
+                            }
+                            "{err}"
+                        }
+                    }
+                }
+            }
+            if organization_id.is_some() {
+                rsx! {
+                    td { class: "px-6 py-3 text-sm",
+                        button { class: "px-4 py-2 text-white bg-red-600 rounded-lg hover:bg-red-700",
+                            onclick: on_remove_question,
+                            t("Remove question")
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn error_message(e: APIError) -> String {
+    match e {
+        APIError::Validation(errors) => errors.join(","),
+        _ => e.to_string(),
+    }
+}
+
+/// The MIME type of an audio file the server accepts, going by its name.
+fn audio_content_type(file_name: &str) -> Option<&'static str> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+
+    match extension.to_ascii_lowercase().as_str() {
+        "mp3" => Some("audio/mpeg"),
+        "ogg" | "oga" | "opus" => Some("audio/ogg"),
+        "wav" => Some("audio/wav"),
+        "webm" => Some("audio/webm"),
+        "m4a" | "mp4" => Some("audio/mp4"),
+        _ => None,
+    }
+}
@@ -0,0 +1,182 @@
+use crate::api::{
+    retention::{get_retention, set_retention, Retained},
+    APIError,
+};
+use crate::components::dashboard::format_time;
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use shared::retention::{Purge, RetentionPolicy, RetentionReport};
+
+/// How long the drawings and names of the students are kept, what would be removed if the
+/// policy were enforced right now, and what it removed before.
+#[inline_props]
+pub fn RetentionPanel(cx: Scope, of: Retained) -> Element {
+    let _ = use_language(cx);
+    let resp_text = use_state(cx, || None::<String>);
+    // Bumped to fetch the report again after changing the policy
+    let version = use_state(cx, || 0);
+    let report = use_future(cx, (of, version.get()), |(of, _)| async move {
+        get_retention(of).await
+    });
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, version];
+        let of = *of;
+        let days = |name: &str| evt.values[name][0].trim().parse().ok();
+        let policy = RetentionPolicy {
+            drawings_days: days("drawings_days"),
+            names_days: days("names_days"),
+        };
+
+        cx.spawn(async move {
+            match set_retention(of, &policy).await {
+                Ok(_) => {
+                    resp_text.set(Some(t("Saved").to_string()));
+                    version.modify(|version| version + 1);
+                }
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let report = match report.value() {
+        Some(Ok(report)) => report,
+        Some(Err(e)) => return cx.render(rsx! {
+            div { class: "text-sm", t("There was an issue fetching the retention policy:") " {e}" }
+        }),
+        None => {
+            return cx.render(rsx! {
+                div { class: "text-sm", t("Fetching the retention policy...") }
+            })
+        }
+    };
+    let policy = report.policy;
+    let organization = matches!(of, Retained::Organization(_));
+
+    cx.render(rsx! {
+        div { class: "mb-6 text-sm",
+            div { class: "mb-2 font-semibold", t("How long students' data is kept") }
+            form { class: "mb-2 flex flex-row items-center",
+                onsubmit: on_submit,
+                label { class: "mr-2", t("Delete drawings after") }
+                input { class: "w-20 mr-1 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "number",
+                    name: "drawings_days",
+                    min: "1",
+                    max: "3650",
+                    placeholder: t("Never"),
+                    value: "{days(policy.drawings_days)}",
+                }
+                span { class: "mr-4", t("days") }
+                label { class: "mr-2", t("Remove names after") }
+                input { class: "w-20 mr-1 px-2 py-1 bg-gray-100 rounded",
+                    r#type: "number",
+                    name: "names_days",
+                    min: "1",
+                    max: "3650",
+                    placeholder: t("Never"),
+                    value: "{days(policy.names_days)}",
+                }
+                span { class: "mr-4", t("days") }
+                button { class: "mr-2 px-3 py-1 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                    t("Save")
+                }
+                if let Some(msg) = resp_text.get() {
+                    rsx! { span { "{msg}" } }
+                }
+            }
+            div { class: "mb-2 text-gray-500",
+                t("Counted from when each student registered. The strictest of the test's, school's and district's periods applies.")
+            }
+            DryRun { report: report, organization: organization }
+            Purges { report: report }
+        }
+    })
+}
+
+/// The number of days to show in a period's input, empty when it's kept for good.
+fn days(days: Option<i32>) -> String {
+    days.map(|days| days.to_string()).unwrap_or_default()
+}
+
+/// What enforcing the policies would remove from each test right now.
+#[inline_props]
+fn DryRun<'a>(cx: Scope, report: &'a RetentionReport, organization: bool) -> Element {
+    let period = |days: Option<i32>| match days {
+        Some(days) => format!("{days} {}", t("days")),
+        None => t("Kept").to_string(),
+    };
+
+    if report.tests.is_empty() {
+        return cx.render(rsx! {
+            div { class: "mb-2", t("None of the organization's teachers have tests yet.") }
+        });
+    }
+
+    cx.render(rsx! {
+        div { class: "mb-1 font-semibold", t("What would be removed now") }
+        table { class: "mb-2",
+            thead {
+                tr {
+                    th { class: "pr-4 py-1 text-left", t("Test") }
+                    if *organization {
+                        rsx! { th { class: "pr-4 py-1 text-left", t("Teacher") } }
+                    }
+                    th { class: "pr-4 py-1 text-left", t("Drawings") }
+                    th { class: "pr-4 py-1 text-left", t("Names") }
+                }
+            }
+            tbody {
+                report.tests.iter().map(|test| rsx! {
+                    tr { key: "{test.test_id}",
+                        td { class: "pr-4 py-1", "{test.test_name}" }
+                        if *organization {
+                            rsx! { td { class: "pr-4 py-1 text-gray-500", "{test.teacher}" } }
+                        }
+                        td { class: "pr-4 py-1",
+                            "{test.drawings_due} "
+                            span { class: "text-gray-500", "({period(test.effective.drawings_days)})" }
+                        }
+                        td { class: "pr-4 py-1",
+                            "{test.names_due} "
+                            span { class: "text-gray-500", "({period(test.effective.names_days)})" }
+                        }
+                    }
+                })
+            }
+        }
+    })
+}
+
+/// What the retention job removed before, newest first.
+#[inline_props]
+fn Purges<'a>(cx: Scope, report: &'a RetentionReport) -> Element {
+    if report.purges.is_empty() {
+        return cx.render(rsx! {
+            div { class: "text-gray-500", t("Nothing was removed yet.") }
+        });
+    }
+
+    let test_name = |purge: &Purge| {
+        report
+            .tests
+            .iter()
+            .find(|test| test.test_id == purge.test_id)
+            .map(|test| test.test_name.clone())
+            .unwrap_or_default()
+    };
+
+    cx.render(rsx! {
+        div { class: "mb-1 font-semibold", t("Removed") }
+        ul {
+            report.purges.iter().map(|purge| rsx! {
+                li { class: "text-gray-500",
+                    "{format_time(purge.purged_at)} · {test_name(purge)} · {purge.drawings} "
+                    t("drawings") ", {purge.names} " t("names") ", {purge.roster_entries} "
+                    t("roster entries") ", {purge.transcriptions} " t("transcriptions")
+                }
+            })
+        }
+    })
+}
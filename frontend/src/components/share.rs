@@ -0,0 +1,178 @@
+//! Getting students to a test without typing its link: a short join code, an optional PIN, and QR
+//! codes that can be printed out and taped to the devices.
+
+use crate::api::{
+    dashboard::{get_tests, set_pin},
+    kiosk::get_roster,
+    public_url, APIError,
+};
+use crate::i18n::{t, use_language};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use qrcode::{render::svg, QrCode};
+use shared::test::Test;
+use uuid::Uuid;
+
+/// How many cards are printed for a test nobody has been added to the roster of.
+const BLANK_CARDS: usize = 12;
+
+/// Where students end up on the test from its join code.
+pub fn join_url(code: &str) -> String {
+    format!("{}/join/{code}", public_url())
+}
+
+/// The join code as it's shown to people, split in two so it's easier to read out.
+fn display_code(code: &str) -> String {
+    let (first, second) = code.split_at(code.len() / 2);
+    format!("{first} {second}")
+}
+
+#[inline_props]
+fn QrImage<'a>(cx: Scope, text: &'a str, size: u32) -> Element {
+    let image = QrCode::new(text.as_bytes())
+        .map(|code| {
+            code.render::<svg::Color>()
+                .min_dimensions(*size, *size)
+                .build()
+        })
+        .unwrap_or_default();
+    // The XML declaration doesn't belong in an HTML document
+    let image = image.split_once("?>").map_or(image.as_str(), |(_, svg)| svg);
+
+    cx.render(rsx! {
+        div { dangerous_inner_html: "{image}" }
+    })
+}
+
+/// The join code, QR code and PIN of one of the teacher's tests, shown on the dashboard.
+#[inline_props]
+pub fn ShareForm<'a>(cx: Scope, test: &'a Test) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+    let resp_text = use_state(cx, || None::<String>);
+    let url: &str = cx.bump().alloc(join_url(&test.join_code));
+
+    let on_submit = move |evt: FormEvent| {
+        to_owned![resp_text, test.id];
+        let pin = Some(evt.values["pin"][0].trim().to_string()).filter(|pin| !pin.is_empty());
+
+        cx.spawn(async move {
+            match set_pin(id, pin).await {
+                Ok(_) => resp_text.set(Some(t("Saved").to_string())),
+                Err(APIError::Validation(errors)) => resp_text.set(Some(errors.join(","))),
+                Err(e) => resp_text.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    cx.render(rsx! {
+        div { class: "flex flex-row items-center text-sm",
+            QrImage { text: url, size: 128 }
+            div { class: "ml-4 flex flex-col",
+                span { t("Join code") }
+                span { class: "text-2xl font-mono font-semibold tracking-widest",
+                    "{display_code(&test.join_code)}"
+                }
+                a { class: "text-blue-700 underline", href: "{url}", "{url}" }
+                form { class: "mt-2 flex flex-row items-center",
+                    onsubmit: on_submit,
+                    span { class: "mr-2", t("PIN") }
+                    input { class: "w-24 mr-2 px-2 py-1 bg-gray-100 rounded",
+                        name: "pin",
+                        inputmode: "numeric",
+                        placeholder: t("None"),
+                        value: "{test.pin.as_deref().unwrap_or_default()}",
+                    }
+                    button { class: "mr-2 px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                        t("Save")
+                    }
+                    if let Some(msg) = resp_text.get() {
+                        rsx! { span { "{msg}" } }
+                    }
+                }
+            }
+            button { class: "ml-4 px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                onclick: move |_| {
+                    nav.push(crate::Route::QrSheet { id: test.id });
+                },
+                t("Print QR codes")
+            }
+        }
+    })
+}
+
+/// A printable sheet of cards with the test's QR code, one for each student on its roster.
+#[inline_props]
+pub fn QrSheet(cx: Scope, id: Uuid) -> Element {
+    let nav = use_navigator(cx);
+    let _ = use_language(cx);
+
+    let sheet = use_future(cx, id, |id| async move {
+        let test = get_tests()
+            .await?
+            .into_iter()
+            .find(|test| test.id == id)
+            .ok_or_else(|| APIError::ServerResponse(t("This test ID is not valid").to_string()))?;
+        let names: Vec<Option<String>> = match get_roster(id).await? {
+            roster if roster.is_empty() => vec![None; BLANK_CARDS],
+            roster => roster.into_iter().map(|entry| Some(entry.name)).collect(),
+        };
+
+        Ok::<_, APIError>((test, names))
+    });
+
+    let print = |_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    };
+
+    let cards = match sheet.value() {
+        Some(Ok((test, names))) => {
+            let url: &str = cx.bump().alloc(join_url(&test.join_code));
+            let code: &str = cx.bump().alloc(display_code(&test.join_code));
+
+            rsx! {
+                div { class: "grid grid-cols-3 gap-4 p-4",
+                    names.iter().map(|name| rsx! {
+                        div { class: "flex flex-col items-center p-4 border-2 border-dashed border-gray-400 rounded-lg",
+                            style: "break-inside: avoid;",
+                            if let Some(name) = name {
+                                rsx! { div { class: "text-xl font-semibold", "{name}" } }
+                            }
+                            div { class: "text-sm text-gray-600", "{test.name}" }
+                            QrImage { text: url, size: 160 }
+                            div { class: "text-sm", t("Join code") }
+                            div { class: "text-2xl font-mono font-semibold tracking-widest", "{code}" }
+                        }
+                    })
+                }
+            }
+        }
+        Some(Err(APIError::Authorization(_))) => {
+            nav.push(crate::Route::Login {});
+            rsx! { div { t("Redirecting...") } }
+        }
+        Some(Err(e)) => rsx! {
+            div { class: "p-6", t("There was an issue fetching the roster:") " {e}" }
+        },
+        None => rsx! { div { class: "p-6", t("Fetching the roster...") } },
+    };
+
+    cx.render(rsx! {
+        style { "@media print {{ .no-print {{ display: none; }} }}" }
+        div { class: "no-print flex flex-row p-4 bg-white border-b border-gray-200",
+            button { class: "mr-2 px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                onclick: move |_| {
+                    nav.push(crate::Route::Dashboard {});
+                },
+                t("Back to the tests")
+            }
+            button { class: "px-4 py-2 font-medium text-white bg-indigo-600 rounded-lg hover:bg-indigo-500",
+                onclick: print,
+                t("Print")
+            }
+        }
+        cards
+    })
+}
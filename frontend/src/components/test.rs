@@ -1,16 +1,46 @@
 use super::canvas::Canvas;
+use super::kiosk::{confirm, KioskFinished, StudentPicker};
 use crate::api::{
-    test::{get_questions, register_student, set_level},
+    live::{poll_control, report_progress},
+    media_url,
+    outbox::{self, Submission},
+    test::{get_plan, preload, register_student},
     APIError,
 };
+use crate::i18n::{self, t, use_language, LanguageSelect};
+use crate::prompt::Prompt;
 use dioxus::prelude::*;
-use web_sys::SpeechSynthesisUtterance;
+use gloo_timers::future::sleep;
+use shared::i18n::Language;
+use shared::live::{ReportProgress, SessionControl};
+use shared::question::{Question, QuestionTranslation};
+use shared::score::Stroke;
+use shared::test::{KioskSession, SubmitResponse, TestSettings};
+use std::time::Duration;
+use uuid::Uuid;
 
 pub struct TestState {
+    id: Uuid,
     state: State,
     level: i32,
     attempt: i32,
+    questions: Vec<Question>,
+    translations: Vec<QuestionTranslation>,
+    language: Language,
+    test_language: Language,
+    settings: TestSettings,
+    needs_pin: bool,
+    /// Set when the session was started from a kiosk, see `Submission`.
+    token: Option<String>,
+    result_id: Option<Uuid>,
+    pending: usize,
+    /// Whether what's pending waits on a teacher logging in again on the device.
+    unauthorized: bool,
+    /// What the teacher last asked the device to do, once it has checked in.
+    control: Option<SessionControl>,
     pub has_drawn: bool,
+    /// How the current drawing was drawn, sent along with it for the recognizer.
+    pub strokes: Vec<Stroke>,
 }
 
 pub enum Action {
@@ -26,21 +56,96 @@ enum State {
 }
 
 impl TestState {
-    pub fn new() -> Self {
+    pub fn new(id: Uuid) -> Self {
         TestState {
+            id,
             state: State::Registration,
             level: 1,
             attempt: 1,
+            questions: Vec::new(),
+            translations: Vec::new(),
+            language: Language::default(),
+            test_language: Language::default(),
+            settings: TestSettings::default(),
+            needs_pin: false,
+            token: None,
+            result_id: None,
+            pending: outbox::pending(),
+            unauthorized: false,
+            control: None,
             has_drawn: false,
+            strokes: Vec::new(),
         }
     }
 
+    /// Starts the test for a student who has just been registered, from a kiosk if there's a
+    /// `session`.
+    pub fn start(&mut self, language: Language, session: Option<KioskSession>) {
+        self.language = language;
+        self.token = session.as_ref().map(|session| session.token.clone());
+        self.result_id = session.map(|session| session.result.id);
+        self.state = State::Testing;
+        self.report_progress(None);
+    }
+
+    /// Gets the device ready for the next student, forgetting everything about the previous one
+    /// except what's still waiting to be sent.
+    pub fn next_student(&mut self) {
+        *self = TestState {
+            questions: std::mem::take(&mut self.questions),
+            translations: std::mem::take(&mut self.translations),
+            language: self.test_language,
+            test_language: self.test_language,
+            settings: self.settings.clone(),
+            needs_pin: self.needs_pin,
+            unauthorized: self.unauthorized,
+            ..TestState::new(self.id)
+        };
+    }
+
+    /// The language the test is given in, unless a student picks another one.
+    pub fn test_language(&self) -> Language {
+        self.test_language
+    }
+
+    /// Carries out what the teacher asked for since the device last checked in.
+    pub fn apply_control(&mut self, control: SessionControl) {
+        if !matches!(self.state, State::Testing) {
+            return;
+        }
+
+        // Restarts from before the device first checked in are already behind it
+        let restarted = self
+            .control
+            .as_ref()
+            .is_some_and(|last| control.restarts > last.restarts);
+
+        if restarted {
+            self.level = 1;
+            self.attempt = 1;
+            self.has_drawn = false;
+            self.strokes.clear();
+            self.report_progress(None);
+        }
+
+        if control.ended {
+            self.finish();
+        }
+
+        self.control = Some(control);
+    }
+
+    /// Whether the teacher paused the test.
+    fn is_paused(&self) -> bool {
+        self.control.as_ref().is_some_and(|control| control.paused)
+    }
+
     pub fn perform_action(&mut self, action: Action) {
         match action {
-            Action::Quit => self.state = State::Finished,
+            Action::Quit => self.finish(),
             Action::Next => match self.attempt {
                 1..=2 => self.attempt += 1,
-                3 => self.state = State::Finished,
+                3 => self.finish(),
                 _ => unreachable!(),
             },
             Action::LevelUp => match self.level {
@@ -48,95 +153,387 @@ impl TestState {
                     self.level += 1;
                     self.attempt = 1;
                 }
-                _ => self.state = State::Finished,
+                _ => self.finish(),
             },
         }
+
+        self.report_progress(None);
+    }
+
+    /// Lets the teacher's live view know which question the student is on, and what they've
+    /// drawn so far. Nothing is retried, the next report will catch the teacher up anyway.
+    pub fn report_progress(&self, drawing: Option<String>) {
+        if !matches!(self.state, State::Testing) {
+            return;
+        }
+
+        let id = self.id;
+        let token = self.token.clone();
+        let req = ReportProgress {
+            level: self.level,
+            attempt: self.attempt,
+            drawing,
+        };
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = report_progress(id, &req, token.as_deref()).await;
+        });
+    }
+
+    /// The question being asked at the current level and attempt.
+    pub fn question(&self) -> Option<&Question> {
+        self.questions
+            .iter()
+            .filter(|question| question.level == self.level)
+            .nth((self.attempt - 1) as usize)
+    }
+
+    /// The question's text in the language the student is taking the test in, or in English if it
+    /// hasn't been translated.
+    pub fn question_text<'a>(&'a self, question: &'a Question) -> &'a str {
+        if self.language == Language::English {
+            return &question.question;
+        }
+
+        self.translations
+            .iter()
+            .find(|translation| {
+                translation.question_id == question.id && translation.language == self.language
+            })
+            .map_or(&question.question, |translation| &translation.question)
+    }
+
+    /// The question as it's read out to the student.
+    fn prompt(&self, question: &Question) -> Prompt {
+        Prompt {
+            text: self.question_text(question).to_string(),
+            // Recordings are of the question's original, English, text
+            recording: question
+                .audio_path
+                .clone()
+                .filter(|_| self.language == Language::English),
+            language: self.language,
+            settings: self.settings.clone(),
+        }
+    }
+
+    /// Queues the drawing, a base64 encoded PNG, as the answer to the current question.
+    pub fn submit_drawing(&mut self, drawing: String) {
+        let Some(question) = self.question() else {
+            return;
+        };
+
+        let response = SubmitResponse {
+            id: Uuid::new_v4(),
+            question_id: question.id,
+            level: self.level,
+            attempt: self.attempt,
+            drawing,
+            strokes: std::mem::take(&mut self.strokes),
+        };
+
+        self.queue(Submission::Response {
+            test_id: self.id,
+            response,
+            token: self.token.clone(),
+            result_id: self.result_id,
+        });
+    }
+
+    fn finish(&mut self) {
+        self.state = State::Finished;
+        self.queue(Submission::Level {
+            test_id: self.id,
+            level: self.level,
+            token: self.token.clone(),
+            result_id: self.result_id,
+        });
+    }
+
+    fn queue(&mut self, submission: Submission) {
+        outbox::push(submission);
+        self.pending = outbox::pending();
     }
 }
 
 #[inline_props]
-pub fn Test(cx: Scope, id: String) -> Element {
-    let _ = use_shared_state_provider(cx, || TestState::new());
+pub fn Test(cx: Scope, id: Uuid) -> Element {
+    cx.render(rsx! { TestSession { id: *id, kiosk: false } })
+}
+
+/// A test taken by one student after another. On a kiosk the teacher picks each student instead of
+/// them registering themselves.
+#[inline_props]
+pub fn TestSession(cx: Scope, id: Uuid, kiosk: bool) -> Element {
+    let _ = use_shared_state_provider(cx, || TestState::new(*id));
     let test_state = use_shared_state::<TestState>(cx).unwrap();
+    let language = use_language(cx);
+
+    // Everything the test needs is fetched before it starts, so it can go on without a connection
+    let questions = use_future(cx, id, |id| {
+        to_owned![test_state, language];
+        async move {
+            let plan = get_plan(id).await?;
+            i18n::set_language(&language, plan.language);
+
+            for path in plan
+                .questions
+                .iter()
+                .flat_map(|q| [q.image_path.as_deref(), q.audio_path.as_deref()])
+                .flatten()
+            {
+                preload(path).await?;
+            }
+
+            let mut state = test_state.write();
+            state.questions = plan.questions;
+            state.translations = plan.translations;
+            state.settings = plan.settings;
+            state.needs_pin = plan.needs_pin;
+            state.language = plan.language;
+            state.test_language = plan.language;
+            Ok::<_, APIError>(())
+        }
+    });
+
+    // Answers are sent in the background for as long as the test is open
+    let _ = use_future(cx, (), |_| {
+        to_owned![test_state];
+        outbox::run(move |pending, unauthorized| {
+            let mut state = test_state.write();
+            state.pending = pending;
+            state.unauthorized = unauthorized;
+        })
+    });
+
+    let _ = use_future(cx, (), |_| follow_teacher(test_state.clone()));
+
+    // Keyed by the number of restarts, so a restarted session starts over on a blank canvas
+    let restarts = test_state
+        .read()
+        .control
+        .as_ref()
+        .map_or(0, |control| control.restarts);
 
     cx.render(match test_state.read().state {
         State::Testing => {
             rsx! {
                 button { class: "absolute top-0 right-0 mt-2 mr-2 py-2 px-4 bg-red-600 text-white rounded-full hover:bg-red-700",
-                    onclick: move |_| test_state.write().perform_action(Action::Quit),
-                    "Quit"
+                    onclick: move |_| {
+                        // A child can't end a proctored test by accident
+                        if !*kiosk || confirm(t("End this student's test?")) {
+                            test_state.write().perform_action(Action::Quit)
+                        }
+                    },
+                    t("Quit")
                 }
 
-                QuestionBar { level: test_state.read().level, attempt: test_state.read().attempt }
+                [restarts].into_iter().map(|restarts| rsx! {
+                    div { key: "{restarts}",
+                        QuestionBar {}
 
-                Canvas {}
-            }
-        }
-        State::Registration => {
-            rsx! { Registration { id: id.clone() }
+                        Canvas {}
+                    }
+                })
+
+                Pending {}
+
+                if test_state.read().is_paused() {
+                    rsx! { Paused {} }
+                }
             }
         }
+        State::Registration => match questions.value() {
+            Some(Ok(_)) if *kiosk => rsx! {
+                StudentPicker { id: *id }
+                Pending {}
+            },
+            Some(Ok(_)) => rsx! { Registration { id: *id } },
+            Some(Err(e)) => rsx! {
+                div { class: "min-h-screen flex flex-col items-center justify-center text-xl",
+                    t("There was an error getting the test ready:")
+                    " {e}"
+                    button { class: "mt-6 py-2 px-4 bg-blue-600 text-white rounded hover:bg-blue-700",
+                        onclick: move |_| questions.restart(),
+                        t("Try again")
+                    }
+                }
+            },
+            None => rsx! {
+                div { class: "min-h-screen flex items-center justify-center text-xl",
+                    t("Getting the test ready...")
+                }
+            },
+        },
+        State::Finished if *kiosk => rsx! { KioskFinished {} },
         State::Finished => {
-            rsx! { Finished { id: id.clone(), level: test_state.read().level } }
+            rsx! { Finished {} }
         }
     })
 }
 
-fn speak(text: &str) {
-    let window = web_sys::window().unwrap();
-    let speech_synthesis = window.speech_synthesis().unwrap();
-    let utterance = SpeechSynthesisUtterance::new_with_text(text).unwrap();
+fn QuestionBar(cx: Scope) -> Element {
+    let test_state = use_shared_state::<TestState>(cx).unwrap();
+    let state = test_state.read();
 
-    speech_synthesis.speak(&utterance);
-}
+    let question = state.question().cloned();
+    let prompt = question.as_ref().map(|question| state.prompt(question));
 
-#[inline_props]
-fn QuestionBar(cx: Scope, level: i32, attempt: i32) -> Element {
-    let questions = use_future(cx, level, |level| async move { get_questions(level).await });
+    // Read each question out as soon as it's shown, if the teacher asked for it
+    use_effect(
+        cx,
+        (&prompt, &state.level, &state.attempt),
+        |(prompt, _, _)| async move {
+            if let Some(prompt) = prompt.filter(|prompt| prompt.settings.autoplay) {
+                prompt.read_out().await;
+            }
+        },
+    );
+
+    let (Some(question), Some(prompt)) = (question, prompt) else {
+        return cx.render(rsx! {
+            div { class: "text-center py-6 text-xl", t("There is no question for this level.") }
+        });
+    };
 
-    let question = match questions.value() {
-        Some(Ok(questions)) => {
-            let question = &questions[(*attempt - 1) as usize];
+    let text = prompt.text.clone();
+    let read_out = move |_| {
+        to_owned![prompt];
+        cx.spawn(async move { prompt.read_out().await });
+    };
 
-            rsx! {
-                div {
-                    class: "text-center py-6 text-xl",
-                    span { class: "cursor-pointer",
-                        onclick: move |_| speak(&question.question),
-                        "{question.question}"
+    cx.render(rsx! {
+        div {
+            class: "text-center py-6 text-xl",
+            span { class: "cursor-pointer",
+                onclick: read_out.clone(),
+                "{text}"
+            }
+
+            if question.image_path.is_some() {
+                rsx! {
+                    img { class: "block mx-auto",
+                        height: "128",
+                        width: "128",
+                        src: "{media_url(question.image_path.as_deref().unwrap())}",
                     }
+                }
+            }
 
-                    if question.image_path.is_some() {
-                        rsx! {
-                            img { class: "block mx-auto",
-                                height: "128",
-                                width: "128",
-                                src: "/{question.image_path.clone().unwrap()}",
-                            }
-                        }
+            // Big enough for a child to find without being able to read
+            button { class: "mx-auto mt-4 flex items-center justify-center w-20 h-20 rounded-full bg-blue-600 text-white hover:bg-blue-700",
+                title: t("Hear the question again"),
+                onclick: read_out,
+                svg { class: "w-10 h-10",
+                    xmlns: "http://www.w3.org/2000/svg",
+                    view_box: "0 0 20 20",
+                    fill: "currentColor",
+                    path {
+                        fill_rule: "evenodd",
+                        d: "M9.383 3.076A1 1 0 0110 4v12a1 1 0 01-1.707.707L4.586 13H2a1 1 0 01-1-1V8a1 1 0 011-1h2.586l3.707-3.707a1 1 0 011.09-.217zM14.657 2.929a1 1 0 011.414 0A9.972 9.972 0 0119 10a9.972 9.972 0 01-2.929 7.071 1 1 0 01-1.414-1.414A7.971 7.971 0 0017 10c0-2.21-.894-4.208-2.343-5.657a1 1 0 010-1.414zm-2.829 2.828a1 1 0 011.415 0A5.983 5.983 0 0115 10a5.984 5.984 0 01-1.757 4.243 1 1 0 01-1.415-1.415A3.984 3.984 0 0013 10a3.983 3.983 0 00-1.172-2.828 1 1 0 010-1.415z",
+                        clip_rule: "evenodd",
                     }
                 }
             }
         }
-        Some(Err(_)) => rsx! { div { "There was an error fetching questions..." } },
-        None => rsx! { div { "Fetching a question..." } },
+    })
+}
+
+/// Keeps up with what the teacher wants the device to do, for as long as a student is taking the
+/// test.
+async fn follow_teacher(test_state: UseSharedState<TestState>) {
+    loop {
+        let (id, token, version) = {
+            let state = test_state.read();
+            if !matches!(state.state, State::Testing) {
+                drop(state);
+                sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+
+            let version = state.control.as_ref().map(|control| control.version);
+            (state.id, state.token.clone(), version)
+        };
+
+        match poll_control(id, version, token.as_deref()).await {
+            // On a kiosk the next student may have started in the meantime
+            Ok(control) if test_state.read().token == token => {
+                test_state.write().apply_control(control)
+            }
+            Ok(_) => {}
+            Err(_) => sleep(Duration::from_secs(5)).await,
+        }
+    }
+}
+
+/// Shown over the test while the teacher has paused it.
+fn Paused(cx: Scope) -> Element {
+    let _ = use_language(cx);
+
+    cx.render(rsx! {
+        div { class: "fixed inset-0 z-50 flex flex-col items-center justify-center bg-blue-50 text-center",
+            svg { class: "w-32 h-32 text-blue-600",
+                xmlns: "http://www.w3.org/2000/svg",
+                view_box: "0 0 20 20",
+                fill: "currentColor",
+                path {
+                    fill_rule: "evenodd",
+                    d: "M18 10a8 8 0 11-16 0 8 8 0 0116 0zM7 8a1 1 0 012 0v4a1 1 0 11-2 0V8zm5-1a1 1 0 00-1 1v4a1 1 0 102 0V8a1 1 0 00-1-1z",
+                    clip_rule: "evenodd",
+                }
+            }
+            div { class: "pt-6 text-4xl font-medium", t("Time for a little break!") }
+            div { class: "pt-4 text-2xl", t("Your teacher will let you know when to carry on.") }
+        }
+    })
+}
+
+/// Lets the proctor know when answers are still waiting for the connection to come back, or for
+/// them to log in again.
+fn Pending(cx: Scope) -> Element {
+    let state = use_shared_state::<TestState>(cx).unwrap();
+    let (pending, unauthorized) = {
+        let state = state.read();
+        (state.pending, state.unauthorized)
     };
 
-    cx.render(question)
+    cx.render(rsx! {
+        if pending > 0 {
+            rsx! {
+                div { class: "fixed bottom-0 left-0 m-2 py-1 px-3 rounded-full bg-yellow-200 text-sm",
+                    "{pending} "
+                    t("answer(s) waiting to be sent")
+                    if unauthorized {
+                        rsx! {
+                            ". "
+                            t("A teacher has to log in again on this device to send them.")
+                        }
+                    }
+                }
+            }
+        }
+    })
 }
 
 #[inline_props]
-fn Registration(cx: Scope, id: String) -> Element {
+fn Registration(cx: Scope, id: Uuid) -> Element {
     let resp_text = use_state(cx, || None::<String>);
     let test_state = use_shared_state::<TestState>(cx).unwrap();
+    let _ = use_language(cx);
 
     let onsubmit = move |evt: FormEvent| {
         to_owned![resp_text];
         to_owned![test_state];
-        to_owned![id];
+        let id = *id;
+        let language = i18n::current();
+        let pin = evt
+            .values
+            .get("pin")
+            .map(|pin| pin[0].trim().to_string())
+            .filter(|pin| !pin.is_empty());
 
         cx.spawn(async move {
-            let resp = register_student(&id, evt.values["name"][0].as_str()).await;
+            let resp = register_student(id, evt.values["name"][0].as_str(), language, pin).await;
 
             match resp {
                 Err(e) => match e {
@@ -146,11 +543,13 @@ fn Registration(cx: Scope, id: String) -> Element {
                     _ => resp_text.set(Some(e.to_string())),
                 },
 
-                Ok(_) => test_state.write().state = State::Testing,
+                Ok(_) => test_state.write().start(language, None),
             }
         });
     };
 
+    let needs_pin = test_state.read().needs_pin;
+
     let (visible, err) = match resp_text.get() {
         Some(v) => (true, v.as_str()),
         None => (false, ""),
@@ -165,19 +564,31 @@ fn Registration(cx: Scope, id: String) -> Element {
                         style: "display: flex; flex-direction: column; gap: 10px;",
                         input { class: "px-4 py-3 bg-gray-100 w-full text-sm outline-[#333] rounded",
                             r#type: "text",
-                            placeholder: "Enter your name",
+                            placeholder: t("Enter your name"),
                             name: "name"
                         }
+                        if needs_pin {
+                            rsx! {
+                                input { class: "px-4 py-3 bg-gray-100 w-full text-sm outline-[#333] rounded",
+                                    r#type: "password",
+                                    inputmode: "numeric",
+                                    placeholder: t("Enter the PIN your teacher gave you"),
+                                    name: "pin"
+                                }
+                            }
+                        }
+                        LanguageSelect {}
                         div { class: "!mt-10",
                             button { class: "w-full py-2.5 px-4 text-sm rounded text-white bg-blue-600 hover:bg-blue-700 focus:outline-none",
-                                "Submit"
+                                t("Submit")
                             }
                         }
                         if visible {
                             rsx! {
                                 div { class: "p-4 mb-4 text-sm text-red-800 rounded-lg bg-red-50 dark:bg-gray-800 dark:text-red-400",
                                     span { class: "font-medium",
-                                        "Invalid! "
+                                        t("Invalid!")
+                                        " "
                                     }
                                     "{err}"
                                 }
@@ -190,22 +601,18 @@ fn Registration(cx: Scope, id: String) -> Element {
     })
 }
 
-#[inline_props]
-fn Finished(cx: Scope, id: String, level: i32) -> Element {
-    let resp = use_future(cx, (id, level), |(id, level)| async move {
-        set_level(&id, level).await
-    });
+pub fn Finished(cx: Scope) -> Element {
+    let pending = use_shared_state::<TestState>(cx).unwrap().read().pending;
 
-    let resp_text = match resp.value() {
-        Some(Ok(_)) => {
-            rsx! { "Thank you, your score has been submitted." }
-        }
-        Some(Err(_)) => {
-            rsx! { "There was an error submitting your score..." }
-        }
-        None => {
-            rsx! { "Submitting your score..." }
-        }
+    let resp_text = match pending {
+        0 => rsx! { t("Thank you, your score has been submitted.") },
+        _ => rsx! {
+            t("Thank you! Your answers are being sent...")
+            div { class: "text-xl pt-4",
+                "{pending} "
+                t("submission(s) still waiting to be sent. They are saved on this device and will be sent once the connection is back.")
+            }
+        },
     };
 
     cx.render(rsx! {
@@ -0,0 +1,89 @@
+//! The language the UI is shown in, picked from the browser's settings until someone chooses one.
+
+use std::cell::Cell;
+
+use dioxus::prelude::*;
+use shared::i18n::{translate, Language};
+
+const STORAGE_KEY: &str = "fnk.language";
+
+thread_local! {
+    // Mirrored into a shared state by `App`, so components re-render when it changes
+    static LANGUAGE: Cell<Language> = Cell::new(initial());
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn initial() -> Language {
+    let stored = storage()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|code| Language::from_code(&code));
+
+    stored
+        .or_else(|| {
+            let language = web_sys::window()?.navigator().language()?;
+            Language::from_code(&language)
+        })
+        .unwrap_or_default()
+}
+
+pub fn current() -> Language {
+    LANGUAGE.with(Cell::get)
+}
+
+/// Translates one of the UI's messages into the current language.
+pub fn t(message: &'static str) -> &'static str {
+    translate(current(), message)
+}
+
+/// Provides the current language to every component below, see `use_language`.
+pub fn use_language_provider(cx: &ScopeState) {
+    use_shared_state_provider(cx, current);
+}
+
+/// The current language, re-rendering the component whenever it changes.
+pub fn use_language(cx: &ScopeState) -> UseSharedState<Language> {
+    use_shared_state::<Language>(cx)
+        .expect("the language is provided by `App`")
+        .clone()
+}
+
+/// Switches the UI to `language` for this page only, e.g. to the one a test is given in.
+pub fn set_language(state: &UseSharedState<Language>, language: Language) {
+    LANGUAGE.with(|current| current.set(language));
+    *state.write() = language;
+}
+
+/// Switches the UI to `language` and remembers it on this device.
+pub fn choose_language(state: &UseSharedState<Language>, language: Language) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(STORAGE_KEY, language.code());
+    }
+
+    set_language(state, language);
+}
+
+/// A dropdown to choose the UI language with.
+pub fn LanguageSelect(cx: Scope) -> Element {
+    let language = use_language(cx);
+    let selected = *language.read();
+
+    cx.render(rsx! {
+        select { class: "px-4 py-2 bg-gray-100 text-sm outline-[#333] rounded",
+            onchange: move |evt| {
+                if let Some(chosen) = Language::from_code(&evt.value) {
+                    choose_language(&language, chosen);
+                }
+            },
+            Language::ALL.into_iter().map(|option| rsx! {
+                option {
+                    value: option.code(),
+                    selected: option == selected,
+                    option.name()
+                }
+            })
+        }
+    })
+}
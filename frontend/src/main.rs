@@ -1,13 +1,23 @@
 mod api;
 mod components;
+mod i18n;
+mod prompt;
 use components::{
+    audit::AuditPage,
     dashboard::Dashboard,
     forms::{Login, Register},
+    join::{Join, JoinCode},
+    kiosk::Kiosk,
+    live::LiveView,
+    organization::OrganizationPage,
+    question_bank::QuestionBank,
+    share::QrSheet,
     test::Test,
     NotFound,
 };
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
+use uuid::Uuid;
 
 #[derive(Routable, Clone)]
 enum Route {
@@ -17,8 +27,24 @@ enum Route {
     Login {},
     #[route("/register")]
     Register {},
+    #[route("/questions")]
+    QuestionBank {},
+    #[route("/organization")]
+    OrganizationPage {},
+    #[route("/audit")]
+    AuditPage {},
     #[route("/test/:id/")]
-    Test { id: String },
+    Test { id: Uuid },
+    #[route("/test/:id/kiosk")]
+    Kiosk { id: Uuid },
+    #[route("/test/:id/live")]
+    LiveView { id: Uuid },
+    #[route("/test/:id/qr")]
+    QrSheet { id: Uuid },
+    #[route("/join")]
+    Join {},
+    #[route("/join/:code")]
+    JoinCode { code: String },
     #[route("/:..route")]
     NotFound { route: Vec<String> },
 }
@@ -28,11 +54,16 @@ fn main() {
 }
 
 fn App(cx: Scope) -> Element {
+    let config = use_future(cx, (), |_| api::load_config());
+    i18n::use_language_provider(cx);
+
     render! {
         link {
             rel: "stylesheet",
             href: "https://unpkg.com/tailwindcss@^2.0/dist/tailwind.min.css"
         }
-        Router::<Route> {}
+        if config.value().is_some() {
+            rsx! { Router::<Route> {} }
+        }
     }
 }
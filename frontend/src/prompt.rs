@@ -0,0 +1,109 @@
+//! Reading questions out loud: from the teacher's recording when there is one, through the
+//! browser's speech synthesis otherwise.
+
+use std::cell::RefCell;
+
+use shared::i18n::Language;
+use shared::test::TestSettings;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlAudioElement, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+use crate::api::media_url;
+
+thread_local! {
+    // Whatever is being played, so replaying a question doesn't talk over itself
+    static PLAYING: RefCell<Option<HtmlAudioElement>> = const { RefCell::new(None) };
+}
+
+/// A question as it's read out to a student.
+#[derive(Clone, PartialEq)]
+pub struct Prompt {
+    pub text: String,
+    /// The path of a recording of `text`.
+    pub recording: Option<String>,
+    pub language: Language,
+    pub settings: TestSettings,
+}
+
+impl Prompt {
+    /// Reads the question out, interrupting any question still being read. Falls back to speech
+    /// synthesis when there's no recording or the browser refuses to play it.
+    pub async fn read_out(&self) {
+        stop();
+
+        if let Some(recording) = &self.recording {
+            if play(recording).await.is_ok() {
+                return;
+            }
+        }
+
+        self.speak();
+    }
+
+    fn speak(&self) {
+        let Some(speech_synthesis) = web_sys::window().and_then(|w| w.speech_synthesis().ok())
+        else {
+            return;
+        };
+        let Ok(utterance) = SpeechSynthesisUtterance::new_with_text(&self.text) else {
+            return;
+        };
+
+        utterance.set_lang(self.language.code());
+        utterance.set_rate(self.settings.speech_rate as f32);
+
+        // Browsers only pick a voice of the utterance's language by themselves on some platforms
+        let voices: Vec<SpeechSynthesisVoice> = speech_synthesis
+            .get_voices()
+            .iter()
+            .filter_map(|voice| voice.dyn_into().ok())
+            .collect();
+
+        let chosen = self
+            .settings
+            .voice
+            .as_ref()
+            .and_then(|name| voices.iter().find(|voice| voice.name() == *name));
+        let matching = voices
+            .iter()
+            .find(|voice| Language::from_code(&voice.lang()) == Some(self.language));
+
+        if let Some(voice) = chosen.or(matching) {
+            utterance.set_voice(Some(voice));
+        }
+
+        speech_synthesis.speak(&utterance);
+    }
+}
+
+async fn play(path: &str) -> Result<(), JsValue> {
+    let audio = HtmlAudioElement::new_with_src(&media_url(path))?;
+    PLAYING.with(|playing| *playing.borrow_mut() = Some(audio.clone()));
+
+    JsFuture::from(audio.play()?).await.map(|_| ())
+}
+
+fn stop() {
+    if let Some(audio) = PLAYING.with(|playing| playing.borrow_mut().take()) {
+        let _ = audio.pause();
+    }
+
+    if let Some(speech_synthesis) = web_sys::window().and_then(|w| w.speech_synthesis().ok()) {
+        speech_synthesis.cancel();
+    }
+}
+
+/// The names of the speech synthesis voices this browser has.
+pub fn voices() -> Vec<String> {
+    let Some(speech_synthesis) = web_sys::window().and_then(|w| w.speech_synthesis().ok()) else {
+        return Vec::new();
+    };
+
+    speech_synthesis
+        .get_voices()
+        .iter()
+        .filter_map(|voice| voice.dyn_into::<SpeechSynthesisVoice>().ok())
+        .map(|voice| voice.name())
+        .collect()
+}
@@ -0,0 +1,42 @@
+//! The audit log of what teachers changed and exported, for administrators.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One request a teacher made that changed something or exported student data.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub teacher_id: Uuid,
+    /// The teacher's username when they made the request.
+    pub actor: String,
+    /// The teacher's organization when they made the request.
+    pub organization_id: Option<Uuid>,
+    /// The method and route, like `POST /v1/test/:test_id/manage`.
+    pub action: String,
+    /// The path that was requested, with the ids of what was acted on.
+    pub target: String,
+    /// The HTTP status the request was answered with.
+    pub status: i32,
+    /// The address the request came from.
+    pub ip: Option<String>,
+    /// In seconds since the Unix epoch.
+    pub at: i64,
+}
+
+/// Narrows down the audit log. Text filters match anywhere in the field.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    /// Part of the teacher's username.
+    pub actor: Option<String>,
+    /// Part of the method and route.
+    pub action: Option<String>,
+    /// Part of the requested path, like a test's id.
+    pub target: Option<String>,
+    /// Only entries from this time on, in seconds since the Unix epoch.
+    pub since: Option<i64>,
+    /// Only entries before this time, in seconds since the Unix epoch.
+    pub until: Option<i64>,
+}
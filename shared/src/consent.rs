@@ -0,0 +1,111 @@
+//! Parental consent, which decides who besides a student's own teacher may see their drawings and
+//! whether they can be used for research.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::i18n::{translate, Language};
+use crate::phonetics::Stage;
+
+/// What a student's parents agreed their drawings may be used for. Each consent allows everything
+/// the ones before it do.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Consent {
+    /// Nothing was signed, so the student's drawings never leave their teacher.
+    #[default]
+    None,
+    /// Used at school, by the teacher who gave the test.
+    School,
+    /// Seen by other teachers as well, and included in research exports.
+    Research,
+}
+
+impl Consent {
+    pub const ALL: [Consent; 3] = [Consent::None, Consent::School, Consent::Research];
+
+    /// The name the consent is stored and sent under.
+    pub fn code(self) -> &'static str {
+        match self {
+            Consent::None => "none",
+            Consent::School => "school",
+            Consent::Research => "research",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Consent::ALL
+            .into_iter()
+            .find(|consent| consent.code() == code)
+    }
+
+    /// The consent's name, translated through the catalog like any other message.
+    pub fn name(self) -> &'static str {
+        match self {
+            Consent::None => "No consent",
+            Consent::School => "School use only",
+            Consent::Research => "Research use",
+        }
+    }
+}
+
+/// Records the consent a student's parents signed, or that they withdrew it.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecordConsent {
+    pub consent: Consent,
+}
+
+/// The code students are known by wherever they can't be identified, the same every time for the
+/// same student.
+pub fn pseudonym(id: Uuid) -> String {
+    id.simple().to_string()[..8].to_string()
+}
+
+/// What students who can't be identified are called instead of their name, in `language`.
+pub fn anonymous_name(id: Uuid, language: Language) -> String {
+    format!("{} {}", translate(language, "Student"), pseudonym(id))
+}
+
+/// Everything the students of a test with research consent did, without anything identifying them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResearchExport {
+    pub test_id: Uuid,
+    /// The test's language.
+    pub language: Language,
+    pub students: Vec<ResearchStudent>,
+}
+
+/// A student with research consent, known only by their [`pseudonym`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResearchStudent {
+    pub pseudonym: String,
+    pub level: i32,
+    /// The language the student picked, if it differs from the test's.
+    pub language: Option<Language>,
+    pub finished: bool,
+    pub responses: Vec<ResearchResponse>,
+}
+
+/// One of a student's answers, with what their teacher decided they wrote.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResearchResponse {
+    pub question_id: Uuid,
+    pub level: i32,
+    pub attempt: i32,
+    /// The drawing, as a base64 encoded PNG, unless it was deleted under the retention policy.
+    pub drawing: Option<String>,
+    pub transcription: Option<String>,
+    pub score: Option<f64>,
+    pub phonetic_accuracy: Option<f64>,
+    pub stage: Option<Stage>,
+}
+
+stored_by_code!(Consent, "consent");
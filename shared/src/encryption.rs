@@ -0,0 +1,34 @@
+//! How much of the student data at rest is encrypted with which key, for the server's admins to
+//! follow a key rotation.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many names and drawings one key encrypts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KeyUsage {
+    /// The key's id, or none for what's stored unencrypted.
+    pub key_id: Option<String>,
+    /// Students' names, on their results and on rosters.
+    pub names: i64,
+    /// Counting the strokes the drawings were made with, which share their key.
+    pub drawings: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EncryptionStatus {
+    /// The key new names and drawings are encrypted with, or none if the server has no keys.
+    pub active_key_id: Option<String>,
+    /// Every key that still encrypts something, whether the server has it or not. Once only the
+    /// active key is left, the older keys can be removed.
+    pub keys: Vec<KeyUsage>,
+}
+
+/// A key rotation that was started, running as a background job.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Rotation {
+    pub job_id: Uuid,
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use validator::ValidationErrors;
+
+use crate::i18n::{translate, Language};
+
+/// A machine-readable classification of an API error.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Something went wrong on the server, the details are only in its logs.
+    Internal,
+    /// The request body failed validation, see `errors`.
+    Validation,
+    /// The client isn't logged in, or its session is no longer valid.
+    Unauthorized,
+    /// The request was well-formed but couldn't be processed.
+    Unprocessable,
+    /// The request conflicts with existing data.
+    Conflict,
+    /// The client is logged in, but isn't allowed to do this.
+    Forbidden,
+    /// The teacher paused the student's session, the request can be sent again once it's resumed.
+    Paused,
+}
+
+/// The body of every error returned by the API.
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+
+    /// The `Display` output of the error.
+    pub message: String,
+
+    /// The message of every field that failed validation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<String>>,
+
+    /// Whether the client needs to log in (again) to perform this request.
+    pub auth_error: bool,
+
+    /// The id the server logged this request under, to be quoted when reporting a problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Flattens validation errors into one message per invalid field, translated into `language`.
+pub fn validation_messages(errors: &ValidationErrors, language: Language) -> Vec<String> {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            errors[0]
+                .message
+                .as_ref()
+                .map(|message| translate(language, message).to_string())
+                .unwrap_or_else(|| format!("{field} is required"))
+        })
+        .collect()
+}
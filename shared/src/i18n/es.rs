@@ -0,0 +1,441 @@
+//! The Spanish catalog.
+
+pub(super) fn translate(message: &str) -> Option<&'static str> {
+    let translation = match message {
+        // Validation
+        "An email is required" => "Se requiere un correo electrónico",
+        "The email you entered is invalid" => "El correo electrónico que ingresaste no es válido",
+        "Your username must be atleast 3 characters long" => {
+            "Tu nombre de usuario debe tener al menos 3 caracteres"
+        }
+        "Your password must be between 8 and 40 characters long" => {
+            "Tu contraseña debe tener entre 8 y 40 caracteres"
+        }
+        "The test's name must be between 3 and 40 characters long" => {
+            "El nombre de la prueba debe tener entre 3 y 40 caracteres"
+        }
+        "Your name must be between 3 and 40 characters long" => {
+            "Tu nombre debe tener entre 3 y 40 caracteres"
+        }
+        "Invalid level range" => "Nivel fuera de rango",
+        "Invalid attempt range" => "Intento fuera de rango",
+        "The drawing is empty" => "El dibujo está vacío",
+        "The speech rate must be between 0.5 and 2" => {
+            "La velocidad de lectura debe estar entre 0.5 y 2"
+        }
+        "The voice's name is too long" => "El nombre de la voz es demasiado largo",
+        "The PIN must be between 4 and 8 digits long" => "El PIN debe tener entre 4 y 8 dígitos",
+        "The PIN must only contain digits" => "El PIN solo puede contener dígitos",
+        "The drawing has too many strokes" => "El dibujo tiene demasiados trazos",
+        "The transcription is too long" => "La transcripción es demasiado larga",
+        "The score must be between 0 and 1" => "El puntaje debe estar entre 0 y 1",
+        "Access can only be given for between 1 and 720 hours" => {
+            "Solo se puede dar acceso por entre 1 y 720 horas"
+        }
+
+        // API errors
+        "an internal database error occurred" => "ocurrió un error interno en la base de datos",
+        "an internal server error occurred" => "ocurrió un error interno en el servidor",
+        "validation error in request body" => "error de validación en la solicitud",
+        "This email is already registered." => "Este correo electrónico ya está registrado.",
+        "Invalid username/password" => "Usuario o contraseña incorrectos",
+        "This test is closed to new registration" => "Esta prueba ya no acepta nuevos registros",
+        "This name is already taken" | "This name is already taken." => {
+            "Este nombre ya está en uso."
+        }
+        "This test ID is not valid" => "Este identificador de prueba no es válido",
+        "The drawing must be a PNG image" => "El dibujo debe ser una imagen PNG",
+        "This question ID is not valid" => "Este identificador de pregunta no es válido",
+        "This join code is not valid" => "Este código no es válido",
+        "The PIN is not correct" => "El PIN no es correcto",
+        "This student ID is not valid" => "Este identificador de estudiante no es válido",
+        "This roster entry ID is not valid" => "Este identificador de la lista de estudiantes no es válido",
+        "This response ID is not valid" => "Este identificador de respuesta no es válido",
+        "This student has already finished the test" => "Este estudiante ya terminó la prueba",
+        "The teacher paused the test" => "El maestro pausó la prueba",
+        "Only pausing and resuming apply to every student at once" => {
+            "Solo se puede pausar y reanudar a todos los estudiantes a la vez"
+        }
+        "The recording must be an MP3, Ogg, WAV, WebM or MP4 audio file" => {
+            "La grabación debe ser un archivo de audio MP3, Ogg, WAV, WebM o MP4"
+        }
+        "Invalid token" => "Token no válido",
+        "You are not logged in" => "No has iniciado sesión",
+        "You are not logged in, please provide token" => {
+            "No has iniciado sesión, por favor proporciona un token"
+        }
+        "The teacher belonging to this token no longer exists" => {
+            "El docente de este token ya no existe"
+        }
+        "The student result belonging to this token no longer exists" => {
+            "El resultado del estudiante de este token ya no existe"
+        }
+        "An unexpected error occurred when trying to communicate with the server" => {
+            "Ocurrió un error inesperado al comunicarse con el servidor"
+        }
+        "request ID" => "ID de solicitud",
+        "No teacher is registered with this email" => "No hay ningún docente registrado con este correo",
+        "Tests have to be transferred to change their owner" => {
+            "Para cambiar el dueño de una prueba hay que transferirla"
+        }
+        "Proctors can only be given access for a while" => {
+            "A los supervisores solo se les puede dar acceso por un tiempo"
+        }
+        "You already own this test" => "Ya eres dueño de esta prueba",
+        "You already own these tests" => "Ya eres dueño de estas pruebas",
+        "Only the test's owner can see this student's drawings without research consent" => {
+            "Sin consentimiento para investigación, solo el dueño de la prueba puede ver los dibujos de este estudiante"
+        }
+        "This drawing was deleted under the retention policy" => {
+            "Este dibujo se eliminó según la política de conservación"
+        }
+        "Retention periods must be between 1 and 3650 days" => {
+            "Los períodos de conservación deben estar entre 1 y 3650 días"
+        }
+        "Only the server's admins can see its background jobs" => {
+            "Solo los administradores del servidor pueden ver sus tareas en segundo plano"
+        }
+        "Only jobs that failed can be retried" => "Solo se pueden reintentar las tareas que fallaron",
+        "Only the server's admins can manage its encryption keys" => {
+            "Solo los administradores del servidor pueden gestionar sus claves de cifrado"
+        }
+        "The server has no encryption key to rotate to" => {
+            "El servidor no tiene una clave de cifrado a la que rotar"
+        }
+        "Only administrators can see the audit log" => {
+            "Solo los administradores pueden ver el registro de auditoría"
+        }
+        "Only the organization's admins can record its questions" => {
+            "Solo los administradores de la organización pueden grabar sus preguntas"
+        }
+        "Only the server's admins can record the shared bank's questions" => {
+            "Solo los administradores del servidor pueden grabar las preguntas del banco compartido"
+        }
+        "This organization ID is not valid" => "Este ID de organización no es válido",
+        "You already belong to an organization" => "Ya perteneces a una organización",
+        "You weren't invited to this organization" => "No te invitaron a esta organización",
+        "Only districts can have organizations of their own, and only schools" => {
+            "Solo los distritos pueden tener organizaciones propias, y solo escuelas"
+        }
+        "This teacher belongs to another organization" => "Este docente pertenece a otra organización",
+        "You can't remove yourself from the organization" => {
+            "No puedes quitarte a ti mismo de la organización"
+        }
+        "This question has already been answered, so it can't be removed" => {
+            "Esta pregunta ya fue respondida, así que no se puede quitar"
+        }
+        "The organization's name must be between 3 and 80 characters long" => {
+            "El nombre de la organización debe tener entre 3 y 80 caracteres"
+        }
+        "The question must be between 3 and 300 characters long" => {
+            "La pregunta debe tener entre 3 y 300 caracteres"
+        }
+        "The word is too long" => "La palabra es demasiado larga",
+        "The phonemes are too long" => "Los fonemas son demasiado largos",
+
+        // Login and registration
+        "Login to your account" => "Inicia sesión en tu cuenta",
+        "Create an account" => "Crear una cuenta",
+        "Enter your email" => "Ingresa tu correo electrónico",
+        "Enter your password" => "Ingresa tu contraseña",
+        "Enter a email" => "Ingresa un correo electrónico",
+        "Enter a username" => "Ingresa un nombre de usuario",
+        "Enter a password" => "Ingresa una contraseña",
+        "Confirm previous password" => "Confirma la contraseña",
+        "The provided passwords do not match." => "Las contraseñas no coinciden.",
+        "Log in" => "Iniciar sesión",
+        "Register" => "Registrarse",
+        "Invalid!" => "¡Error!",
+        "Redirecting..." => "Redirigiendo...",
+
+        // Dashboard
+        "Refetch" => "Actualizar",
+        "Create" => "Crear",
+        "Cancel" => "Cancelar",
+        "Name" => "Nombre",
+        "Open" => "Abierta",
+        "Link" => "Enlace",
+        "Level" => "Nivel",
+        "Language" => "Idioma",
+        "Navigate to the test" => "Ir a la prueba",
+        "Kiosk" => "Modo quiosco",
+        "Run the test on this device, one student after another" => {
+            "Aplicar la prueba en este dispositivo, un estudiante tras otro"
+        }
+        "Who's next?" => "¿Quién sigue?",
+        "Leave kiosk mode" => "Salir del modo quiosco",
+        "Add students, one name per line" => "Agrega estudiantes, un nombre por línea",
+        "Add" => "Agregar",
+        "Next student" => "Siguiente estudiante",
+        "End this student's test?" => "¿Terminar la prueba de este estudiante?",
+        "Log in on this device to use it as a kiosk." => {
+            "Inicia sesión en este dispositivo para usarlo como quiosco."
+        }
+        "Fetching the roster..." => "Cargando la lista...",
+        "There was an issue fetching the roster:" => "Hubo un problema al cargar la lista:",
+        "Live" => "En vivo",
+        "Follow the students taking the test as it happens" => {
+            "Sigue a los estudiantes mientras hacen la prueba"
+        }
+        "Attempt" => "Intento",
+        "Idle" => "Inactivo",
+        "Status" => "Estado",
+        "Registered" => "Registrado",
+        "Testing" => "En la prueba",
+        "Finished" => "Terminó",
+        "Reconnect" => "Reconectar",
+        "The live view was disconnected." => "La vista en vivo se desconectó.",
+        "There was an issue when opening the live view:" => {
+            "Hubo un problema al abrir la vista en vivo:"
+        }
+        "Pick a student to see what they're drawing." => {
+            "Elige un estudiante para ver lo que está dibujando."
+        }
+        "Waiting for them to draw something..." => "Esperando a que dibuje algo...",
+        "Join code" => "Código para unirse",
+        "PIN" => "PIN",
+        "None" => "Ninguno",
+        "Print QR codes" => "Imprimir códigos QR",
+        "Print" => "Imprimir",
+        "Paused" => "En pausa",
+        "Pause" => "Pausar",
+        "Resume" => "Reanudar",
+        "End" => "Terminar",
+        "Restart" => "Reiniciar",
+        "Pause everyone" => "Pausar a todos",
+        "Resume everyone" => "Reanudar a todos",
+        "Restart this student's test from the first question? Their answers so far will be deleted." => {
+            "¿Reiniciar la prueba de este estudiante desde la primera pregunta? Se borrarán sus respuestas hasta ahora."
+        }
+        "Read questions out automatically" => "Leer las preguntas automáticamente",
+        "Speech rate" => "Velocidad de lectura",
+        "Voice" => "Voz",
+        "The browser's default" => "La del navegador",
+        "Save" => "Guardar",
+        "Saved" => "Guardado",
+        "Question bank" => "Banco de preguntas",
+        "Back to the tests" => "Volver a las pruebas",
+        "Question" => "Pregunta",
+        "Recording" => "Grabación",
+        "Remove" => "Quitar",
+        "Fetching the questions..." => "Cargando las preguntas...",
+        "There was an issue when fetching the questions:" => {
+            "Hubo un problema al cargar las preguntas:"
+        }
+        "Report" => "Informe",
+        "Class report" => "Informe de la clase",
+        "Download a printable PDF report" => "Descargar un informe en PDF para imprimir",
+        "There was an issue when making the report:" => "Hubo un problema al preparar el informe:",
+        "Spelling" => "Ortografía",
+        "Check what the student wrote and their spelling stage" => {
+            "Revisar lo que escribió el estudiante y su etapa de ortografía"
+        }
+        "Drawing" => "Dibujo",
+        "Word" => "Palabra",
+        "Suggested" => "Sugerencia",
+        "Transcription" => "Transcripción",
+        "Accept" => "Aceptar",
+        "No answers yet." => "Todavía no hay respuestas.",
+        "Stage" => "Etapa",
+        "The share of the word's sounds the student wrote letters for" => {
+            "La parte de los sonidos de la palabra para los que el estudiante escribió letras"
+        }
+        "Precommunicative" => "Precomunicativa",
+        "Semiphonetic" => "Semifonética",
+        "Phonetic" => "Fonética",
+        "Transitional" => "De transición",
+        "Conventional" => "Convencional",
+        "Fetching the answers..." => "Cargando las respuestas...",
+        "There was an issue fetching the answers:" => "Hubo un problema al cargar las respuestas:",
+        "Fetching the tests..." => "Cargando las pruebas...",
+        "Fetching the results..." => "Cargando los resultados...",
+        "There was an issue when fetching your tests:" => "Hubo un problema al cargar tus pruebas:",
+        "There was an issue fetching the results for" => {
+            "Hubo un problema al cargar los resultados de"
+        }
+        "Viewer" => "Lector",
+        "Proctor" => "Supervisor",
+        "Editor" => "Editor",
+        "Owner" => "Dueño",
+        "Shared with you by another teacher" => "Otro docente la compartió contigo",
+        "Shared with" => "Compartida con",
+        "Only you can see this test." => "Solo tú puedes ver esta prueba.",
+        "Fetching who the test is shared with..." => "Cargando con quién se comparte la prueba...",
+        "There was an issue fetching who the test is shared with:" => {
+            "Hubo un problema al cargar con quién se comparte la prueba:"
+        }
+        "A teacher's email" => "El correo de un docente",
+        "Invite" => "Invitar",
+        "Invited" => "Invitado",
+        "Invitations" => "Invitaciones",
+        "Decline" => "Rechazar",
+        "Hours" => "Horas",
+        "How many hours they keep access for, required for proctors" => {
+            "Cuántas horas conserva el acceso, obligatorio para supervisores"
+        }
+        "Share" => "Compartir",
+        "Shared" => "Compartida",
+        "Until" => "Hasta",
+        "Transfer my tests" => "Transferir mis pruebas",
+        "Hand all your tests over to another teacher" => "Entregar todas tus pruebas a otro docente",
+        "Transfer" => "Transferir",
+        "All your tests and their results will belong to this teacher instead. Continue?" => {
+            "Todas tus pruebas y sus resultados pasarán a ser de este docente. ¿Continuar?"
+        }
+        "Your tests were transferred" => "Tus pruebas fueron transferidas",
+
+        // Consent
+        "Consent" => "Consentimiento",
+        "No consent" => "Sin consentimiento",
+        "School use only" => "Solo uso escolar",
+        "Research use" => "Uso en investigación",
+        "Recorded" => "Registrado el",
+        "by" => "por",
+        "you" => "ti",
+        "Student" => "Estudiante",
+        "Research export" => "Exportar para investigación",
+        "Download the answers of the students with research consent, without their names" => {
+            "Descargar las respuestas de los estudiantes con consentimiento para investigación, sin sus nombres"
+        }
+        "There was an issue when exporting the answers:" => "Hubo un problema al exportar las respuestas:",
+
+        // Retention
+        "How long students' data is kept" => "Cuánto tiempo se conservan los datos de los estudiantes",
+        "Delete drawings after" => "Eliminar los dibujos después de",
+        "Remove names after" => "Quitar los nombres después de",
+        "days" => "días",
+        "Never" => "Nunca",
+        "Kept" => "Se conservan",
+        "Counted from when each student registered. The strictest of the test's, school's and district's periods applies." => {
+            "Se cuenta desde que cada estudiante se registró. Se aplica el período más estricto entre el de la prueba, la escuela y el distrito."
+        }
+        "What would be removed now" => "Lo que se eliminaría ahora",
+        "Names" => "Nombres",
+        "Removed" => "Eliminado",
+        "drawings" => "dibujos",
+        "names" => "nombres",
+        "roster entries" => "entradas de la lista",
+        "transcriptions" => "transcripciones",
+        "Nothing was removed yet." => "Todavía no se eliminó nada.",
+        "None of the organization's teachers have tests yet." => {
+            "Ningún docente de la organización tiene pruebas todavía."
+        }
+        "Fetching the retention policy..." => "Cargando la política de conservación...",
+        "There was an issue fetching the retention policy:" => {
+            "Hubo un problema al cargar la política de conservación:"
+        }
+
+        // Audit log
+        "Audit log" => "Registro de auditoría",
+        "When" => "Cuándo",
+        "Action" => "Acción",
+        "Target" => "Objetivo",
+        "IP address" => "Dirección IP",
+        "From" => "Desde",
+        "To" => "Hasta",
+        "Filter" => "Filtrar",
+        "Nothing matches these filters." => "Nada coincide con estos filtros.",
+        "Fetching the audit log..." => "Cargando el registro de auditoría...",
+        "There was an issue fetching the audit log:" => {
+            "Hubo un problema al cargar el registro de auditoría:"
+        }
+
+        // Schools and districts
+        "School or district" => "Escuela o distrito",
+        "School" => "Escuela",
+        "District" => "Distrito",
+        "Schools" => "Escuelas",
+        "You don't belong to a school or district yet." => "Todavía no perteneces a una escuela o distrito.",
+        "Its name" => "Su nombre",
+        "The school's name" => "El nombre de la escuela",
+        "Add a school" => "Agregar una escuela",
+        "Fetching your organization..." => "Cargando tu organización...",
+        "There was an issue fetching your organization:" => {
+            "Hubo un problema al cargar tu organización:"
+        }
+        "Your tests start with the organization's settings and ask its questions." => {
+            "Tus pruebas empiezan con la configuración de la organización y hacen sus preguntas."
+        }
+        "What new tests start with" => "Con qué empiezan las pruebas nuevas",
+        "Teachers" => "Docentes",
+        "Teacher" => "Docente",
+        "Admin" => "Administrador",
+        "Fetching the teachers..." => "Cargando los docentes...",
+        "There was an issue fetching the teachers:" => "Hubo un problema al cargar los docentes:",
+        "Where the students ended the test" => "Dónde terminaron la prueba los estudiantes",
+        "Tests" => "Pruebas",
+        "Everyone" => "Todos",
+        "There was an issue fetching the results:" => "Hubo un problema al cargar los resultados:",
+        "Questions of your own" => "Preguntas propias",
+        "Tests only ask the shared questions." => "Las pruebas solo hacen las preguntas compartidas.",
+        "The word to spell, if any" => "La palabra a deletrear, si hay una",
+        "Its sounds" => "Sus sonidos",
+        "The word's sounds in ARPAbet, like K AE T for cat" => {
+            "Los sonidos de la palabra en ARPAbet, como K AE T para cat"
+        }
+        "Remove question" => "Quitar pregunta",
+
+        // Reports
+        "Student report" => "Informe del estudiante",
+        "Test" => "Prueba",
+        "Taken on" => "Fecha",
+        "Not taken yet" => "Todavía no la hizo",
+        "Level reached" => "Nivel alcanzado",
+        "The student hasn't finished the test yet." => "El estudiante todavía no terminó la prueba.",
+        "Sounds spelled" => "Sonidos escritos",
+        "Drawings" => "Dibujos",
+        "No drawings yet." => "Todavía no hay dibujos.",
+        "attempt" => "intento",
+        "Printed on" => "Impreso el",
+        "Students" => "Estudiantes",
+        "Levels reached" => "Niveles alcanzados",
+        "Still taking the test:" => "Todavía haciendo la prueba:",
+        "In progress" => "En curso",
+        "Draws a picture to share an idea" => "Hace un dibujo para compartir una idea",
+        "Writes names" => "Escribe nombres",
+        "Spells short words, like cat and pig" => "Escribe palabras cortas, como cat y pig",
+        "Spells words that start with two consonants, like crab and frog" => {
+            "Escribe palabras que empiezan con dos consonantes, como crab y frog"
+        }
+        "Spells words with letter pairs that make one sound, like church and cheetah" => {
+            "Escribe palabras con pares de letras que hacen un solo sonido, como church y cheetah"
+        }
+        "Spells longer words with several syllables, like birthday and playground" => {
+            "Escribe palabras más largas de varias sílabas, como birthday y playground"
+        }
+        "Writes a word of their own" => "Escribe una palabra propia",
+        "Writes sentences of their own" => "Escribe oraciones propias",
+        "No level reached yet" => "Todavía no alcanzó ningún nivel",
+
+        // Test
+        "Enter your name" => "Escribe tu nombre",
+        "Submit" => "Enviar",
+        "Quit" => "Salir",
+        "Clear" => "Borrar",
+        "Yes" => "Sí",
+        "No" => "No",
+        "Try again" => "Intentar de nuevo",
+        "Hear the question again" => "Escuchar la pregunta de nuevo",
+        "Would you like a harder question?" => "¿Quieres una pregunta más difícil?",
+        "Getting the test ready..." => "Preparando la prueba...",
+        "There was an error getting the test ready:" => "Hubo un error al preparar la prueba:",
+        "There is no question for this level." => "No hay una pregunta para este nivel.",
+        "answer(s) waiting to be sent" => "respuesta(s) esperando ser enviadas",
+        "A teacher has to log in again on this device to send them." => "Un docente tiene que volver a iniciar sesión en este dispositivo para enviarlas.",
+        "Thank you! Your answers are being sent..." => "¡Gracias! Tus respuestas se están enviando...",
+        "Thank you, your score has been submitted." => "Gracias, tu puntaje ha sido enviado.",
+        "submission(s) still waiting to be sent. They are saved on this device and will be sent once the connection is back." => {
+            "envío(s) todavía esperando. Están guardados en este dispositivo y se enviarán cuando vuelva la conexión."
+        }
+        "Enter the join code" => "Escribe el código",
+        "Join" => "Entrar",
+        "Enter the PIN your teacher gave you" => "Escribe el PIN que te dio tu docente",
+        "Time for a little break!" => "¡Es hora de un pequeño descanso!",
+        "Your teacher will let you know when to carry on." => "Tu docente te avisará cuándo continuar.",
+        "NOT FOUND" => "NO ENCONTRADO",
+
+        _ => return None,
+    };
+
+    Some(translation)
+}
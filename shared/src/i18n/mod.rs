@@ -0,0 +1,65 @@
+//! The languages fnk can be used in, and the catalog its messages are translated through.
+//!
+//! Messages are looked up by their English text, the way gettext does it, so anything missing from
+//! a catalog is simply shown in English.
+
+use serde::{Deserialize, Serialize};
+
+mod es;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// The ISO 639-1 code, as stored in the database and sent over the wire.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The language's name, written in that language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// Parses a language code, ignoring any region such as the `-MX` of `es-MX`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.split(['-', '_']).next()?.trim();
+
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(primary))
+    }
+
+    /// Picks the first supported language out of an `Accept-Language` style list.
+    pub fn negotiate(accept_language: &str) -> Option<Self> {
+        accept_language
+            .split(',')
+            .filter_map(|range| range.split(';').next())
+            .find_map(Language::from_code)
+    }
+}
+
+/// Translates `message` into `language`, falling back to the English original.
+pub fn translate(language: Language, message: &str) -> &str {
+    match language {
+        Language::English => message,
+        Language::Spanish => es::translate(message).unwrap_or(message),
+    }
+}
+
+stored_by_code!(Language, "language");
@@ -0,0 +1,86 @@
+//! The background jobs the server runs outside of any request, as shown to its admins.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a job is at.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting until it's due, for the first time or to be retried.
+    #[default]
+    Pending,
+    Running,
+    Succeeded,
+    /// Gave up on after failing every attempt it had.
+    Failed,
+}
+
+impl JobStatus {
+    pub const ALL: [JobStatus; 4] = [
+        JobStatus::Pending,
+        JobStatus::Running,
+        JobStatus::Succeeded,
+        JobStatus::Failed,
+    ];
+
+    /// The name the status is stored and sent under.
+    pub fn code(self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        JobStatus::ALL
+            .into_iter()
+            .find(|status| status.code() == code)
+    }
+}
+
+/// A background job, and how its attempts went.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Job {
+    pub id: Uuid,
+    /// What the job does, like `purge_retention`.
+    pub kind: String,
+    /// The job's task as JSON.
+    pub payload: String,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// When it's due, in seconds since the Unix epoch like every other time here.
+    pub run_at: i64,
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    /// Why the latest attempt failed, if it did.
+    pub last_error: Option<String>,
+}
+
+/// How many jobs have each status.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct JobCounts {
+    pub pending: i64,
+    pub running: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+}
+
+/// The state of the server's background jobs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct JobsReport {
+    pub counts: JobCounts,
+    /// The latest jobs, most recently created first.
+    pub jobs: Vec<Job>,
+}
+
+stored_by_code!(JobStatus, "job status");
@@ -0,0 +1,23 @@
+//! The types sent over the wire between the backend and the frontend.
+//!
+//! Both sides compile against this crate, so changing the shape of a request or response
+//! is a compile error everywhere it's used instead of a runtime surprise.
+
+#[macro_use]
+mod macros;
+
+pub mod audit;
+pub mod consent;
+pub mod encryption;
+pub mod error;
+pub mod i18n;
+pub mod job;
+pub mod live;
+pub mod organization;
+pub mod phonetics;
+pub mod question;
+pub mod retention;
+pub mod score;
+pub mod share;
+pub mod teacher;
+pub mod test;
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::i18n::Language;
+
+/// The name of the server-sent events carrying a [`StudentProgress`].
+pub const PROGRESS_EVENT: &str = "progress";
+
+/// The name of the server-sent events carrying a [`CanvasFrame`].
+pub const CANVAS_EVENT: &str = "canvas";
+
+/// Where a student is in a test, as shown on the teacher's live view.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StudentProgress {
+    /// The id of the student's result.
+    pub id: Uuid,
+    pub name: String,
+    pub language: Option<Language>,
+    pub level: i32,
+    /// Unknown until the student's device reports in.
+    pub attempt: Option<i32>,
+    /// How long ago the student last did anything, as of when this was sent. Unknown for students
+    /// who haven't been seen since the server started.
+    pub idle_secs: Option<u64>,
+    pub finished: bool,
+    /// Whether the teacher paused the student's session.
+    pub paused: bool,
+}
+
+/// A snapshot of the canvas a student is drawing on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CanvasFrame {
+    /// The id of the student's result.
+    pub id: Uuid,
+    /// The canvas, as a base64 encoded PNG.
+    pub drawing: String,
+}
+
+/// Sent by a student's device whenever they move on to another question or draw something.
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReportProgress {
+    #[validate(range(min = 1, max = 8, message = "Invalid level range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 8))]
+    pub level: i32,
+
+    #[validate(range(min = 1, max = 3, message = "Invalid attempt range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 3))]
+    pub attempt: i32,
+
+    /// The canvas, as a base64 encoded PNG, if it changed.
+    #[serde(default)]
+    pub drawing: Option<String>,
+}
+
+/// What the teacher can have a student's device do.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Command {
+    /// Put the test aside until it's resumed.
+    Pause,
+    Resume,
+    /// Finish the test at the level the student reached.
+    End,
+    /// Throw away the student's answers and start again from the first question.
+    Restart,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SendCommand {
+    pub command: Command,
+}
+
+/// The state the teacher has put a student's session in, which their device keeps up with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionControl {
+    /// Bumped on every change, so the device can wait for the next one.
+    pub version: u64,
+    pub paused: bool,
+    /// The test should be ended.
+    pub ended: bool,
+    /// How many times the session was restarted.
+    pub restarts: u32,
+}
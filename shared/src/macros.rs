@@ -0,0 +1,50 @@
+/// Stores `$ty` by its code in a text column, on every database the backend supports. The type
+/// needs a `code` and a `from_code` like [`crate::share::Permission`]'s, and a code it doesn't know
+/// fails to decode with an error that calls it `$what`.
+macro_rules! stored_by_code {
+    ($ty:ident, $what:literal) => {
+        #[cfg(feature = "sqlx")]
+        const _: () = {
+            use sqlx::database::{HasArguments, HasValueRef};
+            use sqlx::encode::IsNull;
+            use sqlx::error::BoxDynError;
+            use sqlx::{Database, Decode, Encode, Type};
+
+            impl<DB: Database> Type<DB> for $ty
+            where
+                str: Type<DB>,
+            {
+                fn type_info() -> DB::TypeInfo {
+                    <str as Type<DB>>::type_info()
+                }
+
+                fn compatible(ty: &DB::TypeInfo) -> bool {
+                    <str as Type<DB>>::compatible(ty)
+                }
+            }
+
+            impl<'q, DB: Database> Encode<'q, DB> for $ty
+            where
+                &'q str: Encode<'q, DB>,
+            {
+                fn encode_by_ref(
+                    &self,
+                    buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer,
+                ) -> IsNull {
+                    self.code().encode_by_ref(buf)
+                }
+            }
+
+            impl<'r, DB: Database> Decode<'r, DB> for $ty
+            where
+                &'r str: Decode<'r, DB>,
+            {
+                fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+                    let code = <&str as Decode<DB>>::decode(value)?;
+
+                    $ty::from_code(code).ok_or_else(|| format!("unknown {} `{code}`", $what).into())
+                }
+            }
+        };
+    };
+}
@@ -0,0 +1,180 @@
+//! Schools and districts, the organizations teachers belong to. Their admins see how every one of
+//! their teachers' classes did, and set the questions and settings their teachers' tests use.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::test::TestSettings;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationKind {
+    School,
+    /// A group of schools, whose admins administer each of them as well.
+    District,
+}
+
+impl OrganizationKind {
+    pub const ALL: [OrganizationKind; 2] = [OrganizationKind::School, OrganizationKind::District];
+
+    /// The name the kind is stored and sent under.
+    pub fn code(self) -> &'static str {
+        match self {
+            OrganizationKind::School => "school",
+            OrganizationKind::District => "district",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        OrganizationKind::ALL
+            .into_iter()
+            .find(|kind| kind.code() == code)
+    }
+
+    /// The kind's name, translated through the catalog like any other message.
+    pub fn name(self) -> &'static str {
+        match self {
+            OrganizationKind::School => "School",
+            OrganizationKind::District => "District",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Organization {
+    pub id: Uuid,
+    /// The district a school belongs to, if it's part of one.
+    pub parent_id: Option<Uuid>,
+    pub kind: OrganizationKind,
+    pub name: String,
+    /// What the tests the organization's teachers create start with.
+    #[serde(flatten)]
+    #[cfg_attr(feature = "sqlx", sqlx(flatten))]
+    pub settings: TestSettings,
+    /// Whether the logged in teacher administers the organization.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub admin: bool,
+}
+
+/// Creates an organization the logged in teacher becomes the admin of, or a school of a district
+/// they administer.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateOrganization {
+    #[validate(length(
+        min = 3,
+        max = 80,
+        message = "The organization's name must be between 3 and 80 characters long"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(min_length = 3, max_length = 80))]
+    pub name: String,
+    pub kind: OrganizationKind,
+    /// The district the school is part of, which the logged in teacher has to administer.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+/// A teacher who belongs to an organization, or was invited to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Member {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub admin: bool,
+    /// Whether the teacher hasn't accepted their invitation yet.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub invited: bool,
+}
+
+/// Invites a teacher to an organization, or changes whether one of its teachers administers it.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AddMember {
+    /// The email the teacher registered with.
+    #[validate(
+        length(min = 1, message = "An email is required"),
+        email(message = "The email you entered is invalid")
+    )]
+    #[cfg_attr(feature = "openapi", schema(format = "email"))]
+    pub email: String,
+    #[serde(default)]
+    pub admin: bool,
+}
+
+/// An organization the logged in teacher was invited to, which they join by accepting it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Invitation {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub kind: OrganizationKind,
+    /// Whether they'll administer it.
+    pub admin: bool,
+}
+
+/// How the classes of one of an organization's teachers did.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TeacherSummary {
+    pub teacher_id: Uuid,
+    pub username: String,
+    /// The organization the teacher belongs to, one of the district's schools for districts.
+    pub organization_id: Uuid,
+    pub tests: i64,
+    pub students: i64,
+    /// How many of the students got to the end of the test.
+    pub finished: i64,
+    /// How many students ended the test at each level, from level 1 up.
+    pub levels: Vec<i64>,
+}
+
+/// How the classes of every teacher of an organization did, and of its schools' for districts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OrganizationSummary {
+    pub organization_id: Uuid,
+    pub teachers: Vec<TeacherSummary>,
+    /// The teachers' counts added up.
+    pub students: i64,
+    pub finished: i64,
+    pub levels: Vec<i64>,
+}
+
+/// A question of an organization's own, asked on its teachers' tests instead of the shared bank's
+/// questions of the same level.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateQuestion {
+    #[validate(range(min = 1, max = 8, message = "Invalid level range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 8))]
+    pub level: i32,
+
+    #[validate(length(
+        min = 3,
+        max = 300,
+        message = "The question must be between 3 and 300 characters long"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(min_length = 3, max_length = 300))]
+    pub question: String,
+
+    /// The word students are asked to spell, on spelling questions.
+    #[validate(length(max = 40, message = "The word is too long"))]
+    #[serde(default)]
+    pub word: Option<String>,
+
+    /// The word's phonemes, in space separated ARPAbet, to work out students' spelling stage.
+    #[validate(length(max = 200, message = "The phonemes are too long"))]
+    #[serde(default)]
+    pub phonemes: Option<String>,
+}
+
+stored_by_code!(OrganizationKind, "organization kind");
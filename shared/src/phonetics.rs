@@ -0,0 +1,281 @@
+//! Scoring invented spelling by how many of a word's sounds the student managed to write down, and
+//! which developmental stage of spelling that puts them at.
+//!
+//! Words are broken down into their phonemes in ARPAbet, the notation of the CMU pronouncing
+//! dictionary, so `cat` is `K AE T`. Each phoneme has the letters it's conventionally spelled with,
+//! and the ones children plausibly use for it before they know better, like `e` for the short `i`
+//! of `pig` or `h` for the `ch` of `church` (its letter name, "aitch", has the sound in it).
+
+use serde::{Deserialize, Serialize};
+
+/// The stages children's spelling develops through, from Gentry's classification.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    /// Letters, or letter-like marks, that don't stand for the word's sounds.
+    Precommunicative,
+    /// Some of the sounds are written down, usually the first and last consonants, like `KT`.
+    Semiphonetic,
+    /// Every sound is written down, though not always the way it's spelled, like `PEG` for pig.
+    Phonetic,
+    /// Every sound is written down with letters English really spells it with, like `KAT` or
+    /// `CHURCHE`.
+    Transitional,
+    /// Spelled right.
+    Conventional,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Precommunicative,
+        Stage::Semiphonetic,
+        Stage::Phonetic,
+        Stage::Transitional,
+        Stage::Conventional,
+    ];
+
+    /// The name the stage is stored and sent under.
+    pub fn code(self) -> &'static str {
+        match self {
+            Stage::Precommunicative => "precommunicative",
+            Stage::Semiphonetic => "semiphonetic",
+            Stage::Phonetic => "phonetic",
+            Stage::Transitional => "transitional",
+            Stage::Conventional => "conventional",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Stage::ALL.into_iter().find(|stage| stage.code() == code)
+    }
+
+    /// The stage's name, translated through the catalog like any other message.
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Precommunicative => "Precommunicative",
+            Stage::Semiphonetic => "Semiphonetic",
+            Stage::Phonetic => "Phonetic",
+            Stage::Transitional => "Transitional",
+            Stage::Conventional => "Conventional",
+        }
+    }
+}
+
+/// What a transcription says about how the student spells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    /// The share of the word's phonemes the transcription has letters for, from 0 to 1.
+    pub accuracy: f64,
+    pub stage: Stage,
+}
+
+/// Analyses what a student wrote for `word`, whose phonemes are `phonemes` in space separated
+/// ARPAbet. Phonemes the rules don't know can't be matched, so count against the student. Only
+/// the letters of English count, anything else the student wrote is left out.
+pub fn analyze(word: &str, phonemes: &str, transcription: &str) -> Analysis {
+    let letters = |text: &str| -> String {
+        text.chars()
+            .filter(char::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let (word, transcription) = (letters(word), letters(transcription));
+    let phonemes: Vec<&str> = phonemes.split_whitespace().collect();
+
+    let (matched, conventional) = align(&phonemes, &transcription);
+    let accuracy = match phonemes.len() {
+        0 => 0.0,
+        total => matched as f64 / total as f64,
+    };
+
+    let stage = if !word.is_empty() && transcription == word {
+        Stage::Conventional
+    } else if !phonemes.is_empty() && conventional == phonemes.len() {
+        Stage::Transitional
+    } else if !phonemes.is_empty() && matched == phonemes.len() {
+        Stage::Phonetic
+    } else if matched > 0 {
+        Stage::Semiphonetic
+    } else {
+        Stage::Precommunicative
+    };
+
+    Analysis { accuracy, stage }
+}
+
+/// The stage most of a student's answers are at, the more advanced one on a tie.
+pub fn typical_stage(stages: impl IntoIterator<Item = Stage>) -> Option<Stage> {
+    let mut counts = [0; Stage::ALL.len()];
+    for stage in stages {
+        counts[stage as usize] += 1;
+    }
+
+    Stage::ALL
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(stage, count)| (*count, *stage))
+        .map(|(stage, _)| stage)
+}
+
+/// Matches the phonemes, in order, to letters of the transcription, also in order, and returns
+/// how many of them could be matched at most, and how many of those with conventional spellings.
+fn align(phonemes: &[&str], transcription: &str) -> (usize, usize) {
+    let letters = transcription.len();
+    // best[i][j]: the most phonemes of the first `i` matched within the first `j` letters
+    let mut best = vec![vec![(0, 0); letters + 1]; phonemes.len() + 1];
+
+    for (i, phoneme) in phonemes.iter().enumerate() {
+        let (conventional, invented) = spellings(phoneme);
+
+        for j in 0..=letters {
+            // Leave the phoneme out, or write a letter that doesn't stand for it
+            let mut here = best[i][j];
+            if j > 0 {
+                here = here.max(best[i + 1][j - 1]);
+            }
+            best[i + 1][j] = best[i + 1][j].max(here);
+
+            let rest = &transcription[j..];
+            let candidates = conventional
+                .iter()
+                .map(|spelling| (spelling, 1))
+                .chain(invented.iter().map(|spelling| (spelling, 0)));
+            for (spelling, is_conventional) in candidates {
+                if rest.starts_with(spelling) {
+                    let (matched, conventional) = best[i][j];
+                    let end = j + spelling.len();
+                    best[i + 1][end] =
+                        best[i + 1][end].max((matched + 1, conventional + is_conventional));
+                }
+            }
+        }
+    }
+
+    // Letters written after the last sound was matched don't take anything away
+    best[phonemes.len()]
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+}
+
+/// The letters a phoneme is conventionally spelled with, and the other ones children plausibly
+/// use for it.
+fn spellings(phoneme: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    // Stress markers, as in the `AH0` of the CMU dictionary, don't change the spelling
+    let phoneme = phoneme.trim_end_matches(|c: char| c.is_ascii_digit());
+
+    match phoneme.to_ascii_uppercase().as_str() {
+        // Vowels
+        "AA" => (&["o", "a", "au", "aw", "ah"], &["u"]),
+        "AE" => (&["a"], &["e"]),
+        "AH" => (&["u", "a", "e", "o", "i"], &[]),
+        "AO" => (&["aw", "au", "o", "a", "ough"], &["u"]),
+        "AW" => (&["ow", "ou"], &["au", "o", "a"]),
+        "AY" => (&["i", "y", "igh", "ie", "ai"], &["e"]),
+        "EH" => (&["e", "ea"], &["a"]),
+        "ER" => (&["er", "ir", "ur", "ar", "or", "ear"], &["r"]),
+        "EY" => (&["a", "ay", "ai", "ey", "ei", "ea"], &[]),
+        "IH" => (&["i", "y"], &["e"]),
+        "IY" => (&["ee", "ea", "e", "y", "ie", "ey", "i"], &[]),
+        "OW" => (&["o", "oa", "ow", "oe"], &[]),
+        "OY" => (&["oy", "oi"], &[]),
+        "UH" => (&["oo", "u", "ou"], &["o"]),
+        "UW" => (&["oo", "u", "ue", "ew", "ui", "o", "ou"], &["w"]),
+        // Consonants
+        "B" => (&["b", "bb"], &["p"]),
+        "CH" => (&["ch", "tch"], &["h", "j", "c", "t"]),
+        "D" => (&["d", "dd", "ed"], &["t"]),
+        "DH" => (&["th"], &["d", "v"]),
+        "F" => (&["f", "ff", "ph", "gh"], &["v"]),
+        "G" => (&["g", "gg", "gh"], &["k"]),
+        "HH" => (&["h", "wh"], &[]),
+        "JH" => (&["j", "g", "dg", "dge", "ge"], &["d"]),
+        "K" => (&["c", "k", "ck", "ch", "q"], &["g"]),
+        "L" => (&["l", "ll", "le"], &[]),
+        "M" => (&["m", "mm"], &[]),
+        "N" => (&["n", "nn", "kn"], &[]),
+        "NG" => (&["ng", "n"], &[]),
+        "P" => (&["p", "pp"], &["b"]),
+        "R" => (&["r", "rr", "wr"], &["w"]),
+        "S" => (&["s", "ss", "c", "ce"], &["z"]),
+        "SH" => (&["sh", "ti", "ci", "s"], &["h", "c"]),
+        "T" => (&["t", "tt", "ed"], &["d"]),
+        "TH" => (&["th"], &["f", "t", "h"]),
+        "V" => (&["v", "ve"], &["f"]),
+        "W" => (&["w", "wh"], &["u", "y"]),
+        "Y" => (&["y"], &[]),
+        "Z" => (&["z", "s", "zz", "se"], &[]),
+        "ZH" => (&["s", "g", "si"], &["z"]),
+        _ => (&[], &[]),
+    }
+}
+
+stored_by_code!(Stage, "stage");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analyzes_spellings() {
+        // (word, phonemes, transcription, accuracy, stage)
+        let cases = [
+            // Correct
+            ("cat", "K AE1 T", "cat", 1.0, Stage::Conventional),
+            ("cat", "K AE1 T", "Cat!", 1.0, Stage::Conventional),
+            ("church", "CH ER1 CH", "church", 1.0, Stage::Conventional),
+            // Substituted, with letters English spells the sound with or only children do
+            ("cat", "K AE1 T", "kat", 1.0, Stage::Transitional),
+            ("pig", "P IH1 G", "peg", 1.0, Stage::Phonetic),
+            ("cat", "K AE1 T", "kot", 2.0 / 3.0, Stage::Semiphonetic),
+            // Omitted
+            ("cat", "K AE1 T", "ct", 2.0 / 3.0, Stage::Semiphonetic),
+            ("cat", "K AE1 T", "k", 1.0 / 3.0, Stage::Semiphonetic),
+            ("cat", "K AE1 T", "", 0.0, Stage::Precommunicative),
+            // Inserted
+            ("cat", "K AE1 T", "caat", 1.0, Stage::Transitional),
+            ("cat", "K AE1 T", "xcat", 1.0, Stage::Transitional),
+            ("cat", "K AE1 T", "xyz", 0.0, Stage::Precommunicative),
+            // Spelled with more than one letter
+            ("church", "CH ER1 CH", "churche", 1.0, Stage::Transitional),
+            ("church", "CH ER1 CH", "hrh", 1.0, Stage::Phonetic),
+            ("night", "N AY1 T", "nite", 1.0, Stage::Transitional),
+            ("laugh", "L AE1 F", "laf", 1.0, Stage::Transitional),
+            ("ship", "SH IH1 P", "shep", 1.0, Stage::Phonetic),
+            ("ship", "SH IH1 P", "sip", 1.0, Stage::Transitional),
+        ];
+
+        for (word, phonemes, transcription, accuracy, stage) in cases {
+            let analysis = analyze(word, phonemes, transcription);
+
+            assert_eq!(analysis.stage, stage, "{transcription:?} for {word:?}");
+            assert!(
+                (analysis.accuracy - accuracy).abs() < 1e-9,
+                "{transcription:?} for {word:?} is {} accurate",
+                analysis.accuracy
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_phonemes_count_against_the_student() {
+        let analysis = analyze("cat", "K AE1 XX", "cat");
+
+        assert_eq!(analysis.stage, Stage::Conventional);
+        assert!((analysis.accuracy - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn typical_stage_prefers_the_more_advanced_on_a_tie() {
+        let stages = [Stage::Phonetic, Stage::Semiphonetic, Stage::Phonetic];
+        assert_eq!(typical_stage(stages), Some(Stage::Phonetic));
+
+        let stages = [Stage::Semiphonetic, Stage::Transitional];
+        assert_eq!(typical_stage(stages), Some(Stage::Transitional));
+
+        assert_eq!(typical_stage([]), None);
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::i18n::Language;
+use crate::test::TestSettings;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Question {
+    pub id: Uuid,
+    pub level: i32,
+    pub question: String,
+    pub image_path: Option<String>,
+    /// A recording of the question being read out, played instead of speech synthesis.
+    pub audio_path: Option<String>,
+    /// The organization that added the question, none for the shared bank's questions.
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+/// A question's text in a language other than English.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QuestionTranslation {
+    pub question_id: Uuid,
+    pub language: Language,
+    pub question: String,
+}
+
+/// Every question a student might be asked during a test, fetched once before it starts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QuestionPlan {
+    /// The test's language.
+    pub language: Language,
+    /// Up to three questions per level, ordered by level.
+    pub questions: Vec<Question>,
+    /// The questions' text in every other language it's available in.
+    pub translations: Vec<QuestionTranslation>,
+    /// How the questions are read out.
+    pub settings: TestSettings,
+    /// Whether students have to enter the test's PIN to register.
+    pub needs_pin: bool,
+}
+
+/// The number of attempts a student gets at each level.
+pub const ATTEMPTS_PER_LEVEL: usize = 3;
+
+/// The level students who get through every question end the test at.
+pub const MAX_LEVEL: i32 = 8;
+
+/// What the questions of `level` ask students to do, which a student who ended the test there has
+/// shown they can do. Translated through the catalog like any other message.
+pub fn level_description(level: i32) -> &'static str {
+    match level {
+        1 => "Draws a picture to share an idea",
+        2 => "Writes names",
+        3 => "Spells short words, like cat and pig",
+        4 => "Spells words that start with two consonants, like crab and frog",
+        5 => "Spells words with letter pairs that make one sound, like church and cheetah",
+        6 => "Spells longer words with several syllables, like birthday and playground",
+        7 => "Writes a word of their own",
+        8 => "Writes sentences of their own",
+        _ => "No level reached yet",
+    }
+}
@@ -0,0 +1,99 @@
+//! How long students' drawings and names are kept, for schools and districts that have to delete
+//! them after a while.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// How many days after a student registered their drawings are deleted and their name removed,
+/// kept for good when unset.
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetentionPolicy {
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "Retention periods must be between 1 and 3650 days"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 3650))]
+    #[serde(default)]
+    pub drawings_days: Option<i32>,
+
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "Retention periods must be between 1 and 3650 days"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 3650))]
+    #[serde(default)]
+    pub names_days: Option<i32>,
+}
+
+impl RetentionPolicy {
+    /// The strictest of the policies, keeping everything for as short as any of them says to.
+    pub fn strictest(policies: impl IntoIterator<Item = RetentionPolicy>) -> RetentionPolicy {
+        policies
+            .into_iter()
+            .fold(RetentionPolicy::default(), |strictest, policy| {
+                let shortest = |a: Option<i32>, b: Option<i32>| match (a, b) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+
+                RetentionPolicy {
+                    drawings_days: shortest(strictest.drawings_days, policy.drawings_days),
+                    names_days: shortest(strictest.names_days, policy.names_days),
+                }
+            })
+    }
+}
+
+/// What the retention policy of a test would remove if it were enforced now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TestRetention {
+    pub test_id: Uuid,
+    pub test_name: String,
+    /// The name of the teacher who owns the test.
+    pub teacher: String,
+    /// The test's own policy.
+    pub policy: RetentionPolicy,
+    /// The policy that's enforced, the strictest of the test's and its organization's.
+    pub effective: RetentionPolicy,
+    /// How many drawings are past their retention period.
+    pub drawings_due: i64,
+    /// How many students' names are past their retention period.
+    pub names_due: i64,
+    /// How many of those students are on the test's roster, which they're taken off along with
+    /// their name.
+    pub roster_entries_due: i64,
+    /// How many transcriptions of those students' answers to name questions are removed along
+    /// with their name, since they spell it out.
+    pub transcriptions_due: i64,
+}
+
+/// What the retention job removed from a test in one run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Purge {
+    pub test_id: Uuid,
+    /// In seconds since the Unix epoch.
+    pub purged_at: i64,
+    pub drawings: i64,
+    pub names: i64,
+    pub roster_entries: i64,
+    pub transcriptions: i64,
+}
+
+/// A test's or organization's retention policy, what it would remove now, and what it removed
+/// before.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetentionReport {
+    pub policy: RetentionPolicy,
+    /// Every test the policy applies to, with what would be removed from it.
+    pub tests: Vec<TestRetention>,
+    /// The latest runs of the retention job that removed anything, newest first.
+    pub purges: Vec<Purge>,
+}
@@ -0,0 +1,94 @@
+//! Scoring what students wrote on the spelling questions against the word they were asked for.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::phonetics::Stage;
+
+/// One point of a stroke, in canvas pixels from its top left corner.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// The points the pen went through between being put down and lifted up.
+pub type Stroke = Vec<Point>;
+
+/// A student's answer to a question, with what was suggested and decided about what they wrote.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScoredResponse {
+    pub id: Uuid,
+    pub level: i32,
+    pub attempt: i32,
+    /// The word the student was asked to spell, on the spelling questions.
+    pub word: Option<String>,
+    /// The word's phonemes, in space separated ARPAbet.
+    pub phonemes: Option<String>,
+    /// What the recognizer read in the drawing, if it has had a look at it yet.
+    pub suggested_transcription: Option<String>,
+    pub suggested_score: Option<f64>,
+    /// What the teacher decided the student wrote, once they have.
+    pub transcription: Option<String>,
+    /// How close the transcription is to the word, see [`spelling_score`].
+    pub score: Option<f64>,
+    /// The share of the word's phonemes the transcription has letters for.
+    pub phonetic_accuracy: Option<f64>,
+    pub stage: Option<Stage>,
+}
+
+/// What the teacher read in a response, either accepting the suggestion or overriding it.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScoreResponse {
+    #[validate(length(max = 500, message = "The transcription is too long"))]
+    #[cfg_attr(feature = "openapi", schema(max_length = 500))]
+    pub transcription: String,
+
+    /// Worked out from the transcription with [`spelling_score`] when unset. Only kept for the
+    /// spelling questions.
+    #[validate(range(min = 0.0, max = 1.0, message = "The score must be between 0 and 1"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 0.0, maximum = 1.0))]
+    #[serde(default)]
+    pub score: Option<f64>,
+}
+
+/// How close `transcription` is to `word`, from 0 for nothing in common to 1 for spelled right.
+///
+/// Case, spaces and punctuation are ignored, since the students are only just learning them.
+pub fn spelling_score(word: &str, transcription: &str) -> f64 {
+    let letters = |text: &str| -> Vec<char> {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (word, transcription) = (letters(word), letters(transcription));
+
+    let longest = word.len().max(transcription.len());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    1.0 - edit_distance(&word, &transcription) as f64 / longest as f64
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
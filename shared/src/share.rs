@@ -0,0 +1,113 @@
+//! Sharing tests with other teachers, and handing them over to another teacher for good.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// What a teacher may do with a test. Each permission allows everything the ones before it do.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// See the results, answers and reports, and watch students take the test.
+    #[default]
+    Viewer,
+    /// Run the test as well: open and close it, start kiosk sessions and pause or end students'
+    /// sessions. Meant for substitutes, so only ever given for a while.
+    Proctor,
+    /// Change the test as well: its settings, PIN and roster, and score the answers.
+    Editor,
+    /// The teacher who created the test, or had it transferred to them. Only they can share it.
+    Owner,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 4] = [
+        Permission::Viewer,
+        Permission::Proctor,
+        Permission::Editor,
+        Permission::Owner,
+    ];
+
+    /// The name the permission is stored and sent under.
+    pub fn code(self) -> &'static str {
+        match self {
+            Permission::Viewer => "viewer",
+            Permission::Proctor => "proctor",
+            Permission::Editor => "editor",
+            Permission::Owner => "owner",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Permission::ALL
+            .into_iter()
+            .find(|permission| permission.code() == code)
+    }
+
+    /// The permission's name, translated through the catalog like any other message.
+    pub fn name(self) -> &'static str {
+        match self {
+            Permission::Viewer => "Viewer",
+            Permission::Proctor => "Proctor",
+            Permission::Editor => "Editor",
+            Permission::Owner => "Owner",
+        }
+    }
+}
+
+/// Another teacher a test is shared with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Share {
+    pub teacher_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub permission: Permission,
+    /// When the teacher loses access, in seconds since the Unix epoch, if they ever do.
+    pub expires_at: Option<i64>,
+}
+
+/// Shares a test with another teacher, or changes what they may do with it.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShareTest {
+    /// The email the other teacher registered with.
+    #[validate(
+        length(min = 1, message = "An email is required"),
+        email(message = "The email you entered is invalid")
+    )]
+    #[cfg_attr(feature = "openapi", schema(format = "email"))]
+    pub email: String,
+
+    /// Tests can't be shared as [`Permission::Owner`], they have to be transferred.
+    pub permission: Permission,
+
+    /// How long the teacher keeps access for, for good when unset. Required for proctors.
+    #[validate(range(
+        min = 1,
+        max = 720,
+        message = "Access can only be given for between 1 and 720 hours"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 720))]
+    #[serde(default)]
+    pub hours: Option<u32>,
+}
+
+/// Hands every test of the logged in teacher over to another teacher.
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TransferTests {
+    /// The email the other teacher registered with.
+    #[validate(
+        length(min = 1, message = "An email is required"),
+        email(message = "The email you entered is invalid")
+    )]
+    #[cfg_attr(feature = "openapi", schema(format = "email"))]
+    pub email: String,
+}
+
+stored_by_code!(Permission, "permission");
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegisterTeacher {
+    #[validate(
+        length(min = 1, message = "An email is required"),
+        email(message = "The email you entered is invalid")
+    )]
+    #[cfg_attr(feature = "openapi", schema(format = "email"))]
+    pub email: String,
+    #[validate(length(min = 3, message = "Your username must be atleast 3 characters long"))]
+    #[cfg_attr(feature = "openapi", schema(min_length = 3))]
+    pub username: String,
+    #[validate(length(
+        min = 8,
+        max = 40,
+        message = "Your password must be between 8 and 40 characters long"
+    ))]
+    #[cfg_attr(
+        feature = "openapi",
+        schema(min_length = 8, max_length = 40, format = "password")
+    )]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginTeacher {
+    #[validate(
+        length(min = 1, message = "An email is required"),
+        email(message = "The email you entered is invalid")
+    )]
+    #[cfg_attr(feature = "openapi", schema(format = "email"))]
+    pub email: String,
+    #[validate(length(
+        min = 8,
+        max = 40,
+        message = "Your password must be between 8 and 40 characters long"
+    ))]
+    #[cfg_attr(
+        feature = "openapi",
+        schema(min_length = 8, max_length = 40, format = "password")
+    )]
+    pub password: String,
+}
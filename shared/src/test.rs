@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::consent::Consent;
+use crate::i18n::Language;
+use crate::score::Stroke;
+use crate::share::Permission;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Test {
+    pub id: Uuid,
+    pub teacher_id: Uuid,
+    pub name: String,
+    pub closed: bool,
+    /// The language students see the test in, unless they pick another one.
+    pub language: Language,
+    #[serde(flatten)]
+    #[cfg_attr(feature = "sqlx", sqlx(flatten))]
+    pub settings: TestSettings,
+    /// A short code students can type in to find the test, instead of its link.
+    pub join_code: String,
+    /// Asked for when a student registers, if set.
+    pub pin: Option<String>,
+    /// What the logged in teacher may do with the test.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub permission: Permission,
+}
+
+/// How the questions of a test are read out to the students taking it.
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TestSettings {
+    /// Read each question out as soon as it's shown, rather than waiting for the replay button.
+    pub autoplay: bool,
+
+    /// How fast questions without a recording are read out by speech synthesis, 1 being normal.
+    #[validate(range(
+        min = 0.5,
+        max = 2.0,
+        message = "The speech rate must be between 0.5 and 2"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 0.5, maximum = 2.0))]
+    pub speech_rate: f64,
+
+    /// The speech synthesis voice to use on browsers that have it, by name.
+    #[validate(length(max = 100, message = "The voice's name is too long"))]
+    pub voice: Option<String>,
+}
+
+impl Default for TestSettings {
+    fn default() -> Self {
+        TestSettings {
+            autoplay: false,
+            speech_rate: 1.0,
+            voice: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateTest {
+    #[validate(length(
+        min = 3,
+        max = 40,
+        message = "The test's name must be between 3 and 40 characters long"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(min_length = 3, max_length = 40))]
+    pub name: String,
+    #[serde(default)]
+    pub language: Language,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StudentResult {
+    pub id: Uuid,
+    pub test_id: Uuid,
+    pub name: String,
+    pub level: i32,
+    /// The language the student picked, if it differs from the test's.
+    pub language: Option<Language>,
+    /// Whether the student got to the end of the test, rather than still taking it.
+    pub finished: bool,
+    /// What the student's parents agreed their drawings may be used for.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub consent: Consent,
+    /// When the consent was last recorded, in seconds since the Unix epoch.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub consent_recorded_at: Option<i64>,
+    /// The name of the teacher who recorded it.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub consent_recorded_by: Option<String>,
+    /// When the student's name was removed under the retention policy, in seconds since the Unix
+    /// epoch. They're only known by their pseudonym from then on.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub name_purged_at: Option<i64>,
+    /// The id of the key the stored name is encrypted with, none once it's been decrypted. Never
+    /// sent over the wire.
+    #[serde(skip)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub name_key_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegisterStudent {
+    #[validate(length(
+        min = 3,
+        max = 40,
+        message = "Your name must be between 3 and 40 characters long"
+    ))]
+    #[cfg_attr(feature = "openapi", schema(min_length = 3, max_length = 40))]
+    pub name: String,
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// Only checked for tests that have a PIN.
+    #[serde(default)]
+    pub pin: Option<String>,
+}
+
+/// The test a join code belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct JoinTest {
+    pub test_id: Uuid,
+    /// Whether students have to enter the test's PIN to register.
+    pub needs_pin: bool,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SetPin {
+    /// Removes the PIN when unset.
+    #[validate(
+        length(
+            min = 4,
+            max = 8,
+            message = "The PIN must be between 4 and 8 digits long"
+        ),
+        custom(function = "digits_only", message = "The PIN must only contain digits")
+    )]
+    #[cfg_attr(
+        feature = "openapi",
+        schema(min_length = 4, max_length = 8, pattern = "^[0-9]+$")
+    )]
+    pub pin: Option<String>,
+}
+
+fn digits_only(pin: &str) -> Result<(), ValidationError> {
+    match pin.chars().all(|c| c.is_ascii_digit()) {
+        true => Ok(()),
+        false => Err(ValidationError::new("digits_only")),
+    }
+}
+
+/// Puts a join code the way it's stored, so students don't have to care about case or the spaces
+/// and dashes it may have been printed with.
+pub fn normalize_join_code(code: &str) -> String {
+    code.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// A student the teacher expects to take a test, as listed in the kiosk's student picker.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RosterEntry {
+    pub id: Uuid,
+    pub test_id: Uuid,
+    pub name: String,
+    pub language: Option<Language>,
+    /// The result of the student's session, once they've started one.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub result_id: Option<Uuid>,
+    /// The id of the key the stored name is encrypted with, like [`StudentResult::name_key_id`].
+    #[serde(skip)]
+    #[cfg_attr(feature = "sqlx", sqlx(default))]
+    pub name_key_id: Option<String>,
+}
+
+/// A student's session started by the teacher from a kiosk.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KioskSession {
+    /// Sent as a bearer token instead of a cookie, so the next student on the same device can't
+    /// end up in this session.
+    pub token: String,
+    pub result: StudentResult,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SetLevel {
+    #[validate(range(min = 1, max = 8, message = "Invalid level range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 8))]
+    pub level: i32,
+}
+
+/// A student's drawing for one question, submitted once they're happy with it.
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SubmitResponse {
+    /// Chosen by the client, so a response resent after a dropped connection is only stored once.
+    pub id: Uuid,
+
+    pub question_id: Uuid,
+
+    #[validate(range(min = 1, max = 8, message = "Invalid level range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 8))]
+    pub level: i32,
+
+    #[validate(range(min = 1, max = 3, message = "Invalid attempt range"))]
+    #[cfg_attr(feature = "openapi", schema(minimum = 1, maximum = 3))]
+    pub attempt: i32,
+
+    /// The canvas, as a base64 encoded PNG.
+    #[validate(length(min = 1, message = "The drawing is empty"))]
+    pub drawing: String,
+
+    /// How the drawing was drawn, for recognizers that follow the pen rather than read the image.
+    #[validate(length(max = 1000, message = "The drawing has too many strokes"))]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Vec<crate::score::Point>>, max_items = 1000))]
+    #[serde(default)]
+    pub strokes: Vec<Stroke>,
+}